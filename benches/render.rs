@@ -0,0 +1,101 @@
+//! Renders a small scene on one thread and reports how long it took and how much it allocated.
+//! Run with `cargo bench`.
+
+use std::{
+    alloc::{GlobalAlloc, Layout, System},
+    sync::{Arc, atomic::{AtomicUsize, Ordering}},
+    time::Instant,
+};
+
+use raytracer::{
+    camera::Camera, 
+    color::Color, 
+    dielectric::Dielectric, 
+    hittable_list::HittableList, 
+    lambertian::Lambertian, 
+    light_list::LightList, 
+    logger::Logger, 
+    material_arena::MaterialArena, 
+    metal::Metal, 
+    sphere::Sphere, 
+    vec::{Point3, Vec2},
+};
+
+/// Counts every allocation made through the global allocator.
+struct CountingAllocator;
+
+static ALLOCATIONS: AtomicUsize = AtomicUsize::new(0);
+static ALLOCATED_BYTES: AtomicUsize = AtomicUsize::new(0);
+
+unsafe impl GlobalAlloc for CountingAllocator {
+    unsafe fn alloc(&self, layout: Layout) -> *mut u8 {
+        ALLOCATIONS.fetch_add(1, Ordering::Relaxed);
+        ALLOCATED_BYTES.fetch_add(layout.size(), Ordering::Relaxed);
+        System.alloc(layout)
+    }
+
+    unsafe fn dealloc(&self, pointer: *mut u8, layout: Layout) {
+        System.dealloc(pointer, layout)
+    }
+}
+
+#[global_allocator]
+static ALLOCATOR: CountingAllocator = CountingAllocator;
+
+/// A grid of small spheres of every material on a large ground sphere.
+fn scene() -> (HittableList, MaterialArena) {
+    let mut world = HittableList::default();
+    let mut materials = MaterialArena::default();
+    world.add(Arc::<_>::new(Sphere {
+        center: Point3 {x: 0.0, y: -1000.0, z: 0.0},
+        radius: 1000.0,
+        material: materials.add(Box::<_>::new(Lambertian::new(Color {x: 0.5, y: 0.5, z: 0.5}))),
+    }));
+    for a in -5..5_i32 {
+        for b in -5..5 {
+            let center = Point3 {x: a as f32, y: 0.3, z: b as f32};
+            let color = Color {x: 0.5 + a as f32 / 10.0, y: 0.5 + b as f32 / 10.0, z: 0.6};
+            world.add(Arc::<_>::new(Sphere {
+                center,
+                radius: 0.3,
+                material: materials.add(match (a + b).rem_euclid(3) {
+                    0 => Box::<_>::new(Lambertian::new(color)),
+                    1 => Box::<_>::new(Metal::new(&color, 0.2)),
+                    _ => Box::<_>::new(Dielectric {ir: 1.5}),
+                }),
+            }));
+        }
+    }
+    (world, materials)
+}
+
+fn main() {
+    let (world, materials) = scene();
+    let world = world.build();
+    let mut camera = Camera::default();
+    camera.image = Vec2 {width: 64, height: 48};
+    camera.samples_per_pixel = 16;
+    camera.max_depth = 8;
+    camera.look_from = Point3 {x: 8.0, y: 3.0, z: 6.0};
+    camera.look_at = Point3::default();
+    camera.vertical_field_of_view = 40.0;
+    camera.thread_count = 1;
+    camera.seed = Some(1);
+
+    let mut logger = Logger {stdout: std::io::stdout().lock(), stderr: std::io::stderr().lock()};
+    let allocations = ALLOCATIONS.load(Ordering::Relaxed);
+    let bytes = ALLOCATED_BYTES.load(Ordering::Relaxed);
+    let start = Instant::now();
+    camera.render(&mut logger, world.as_ref(), &materials, &LightList::default());
+    let elapsed = start.elapsed();
+
+    let samples = camera.image.width * camera.image.height * camera.samples_per_pixel;
+    let allocations = ALLOCATIONS.load(Ordering::Relaxed) - allocations;
+    println!(
+        "\nrender: {:.1?}, {} allocations ({:.2} per sample), {} bytes allocated",
+        elapsed,
+        allocations,
+        allocations as f64 / samples as f64,
+        ALLOCATED_BYTES.load(Ordering::Relaxed) - bytes,
+    );
+}
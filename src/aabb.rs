@@ -0,0 +1,97 @@
+use crate::{interval::{self, Interval}, ray::Ray, vec::{Point3, Vec3}};
+
+/// An axis-aligned bounding box, stored as one interval per axis.
+#[derive(Clone, Copy)]
+pub struct Aabb {
+    pub x: Interval,
+    pub y: Interval,
+    pub z: Interval,
+}
+
+impl Default for Aabb {
+    fn default() -> Self {
+        EMPTY
+    }
+}
+
+impl Aabb {
+    /// Treats the two points as extrema for the box, so their order does not matter.
+    pub fn from_points(a: &Point3, b: &Point3) -> Self {
+        Self {
+            x: Interval {min: a.x.min(b.x), max: a.x.max(b.x)},
+            y: Interval {min: a.y.min(b.y), max: a.y.max(b.y)},
+            z: Interval {min: a.z.min(b.z), max: a.z.max(b.z)},
+        }
+    }
+
+    pub fn enclosing(a: &Aabb, b: &Aabb) -> Self {
+        Self {
+            x: Interval::enclosing(&a.x, &b.x),
+            y: Interval::enclosing(&a.y, &b.y),
+            z: Interval::enclosing(&a.z, &b.z),
+        }
+    }
+
+    pub fn axis(&self, n: usize) -> &Interval {
+        match n {
+            0 => &self.x,
+            1 => &self.y,
+            2 => &self.z,
+            _ => panic!("Can only index for 0, 1, or 2"),
+        }
+    }
+
+    pub fn longest_axis(&self) -> usize {
+        let sizes = [self.x.size(), self.y.size(), self.z.size()];
+        if sizes[0] > sizes[1] {
+            if sizes[0] > sizes[2] {0} else {2}
+        } else if sizes[1] > sizes[2] {1} else {2}
+    }
+
+    pub fn is_empty(&self) -> bool {
+        self.x.size() < 0.0 || self.y.size() < 0.0 || self.z.size() < 0.0
+    }
+
+    pub fn is_bounded(&self) -> bool {
+        [self.x, self.y, self.z].iter().all(|axis| axis.min.is_finite() && axis.max.is_finite())
+    }
+
+    pub fn centroid(&self) -> Point3 {
+        Point3 {
+            x: 0.5 * (self.x.min + self.x.max),
+            y: 0.5 * (self.y.min + self.y.max),
+            z: 0.5 * (self.z.min + self.z.max),
+        }
+    }
+
+    pub fn surface_area(&self) -> f32 {
+        if self.is_empty() {return 0.0;}
+        let extent = Vec3 {x: self.x.size(), y: self.y.size(), z: self.z.size()};
+        2.0 * (extent.x * extent.y + extent.y * extent.z + extent.z * extent.x)
+    }
+
+    /// Grows any axis thinner than `delta`, so flat primitives still have a hittable box.
+    pub fn pad(&self, delta: f32) -> Self {
+        let pad_axis = |axis: Interval| if axis.size() < delta {axis.expand(delta)} else {axis};
+        Self {x: pad_axis(self.x), y: pad_axis(self.y), z: pad_axis(self.z)}
+    }
+
+    /// Slab test for whether the ray enters the box anywhere inside `ray_t`.
+    pub fn hit(&self, ray: &Ray, mut ray_t: Interval) -> bool {
+        for axis in 0..3 {
+            let inverse_direction = 1.0 / ray.direction[axis];
+            let bounds = self.axis(axis);
+            let mut t0 = (bounds.min - ray.origin[axis]) * inverse_direction;
+            let mut t1 = (bounds.max - ray.origin[axis]) * inverse_direction;
+            if inverse_direction < 0.0 {std::mem::swap(&mut t0, &mut t1);}
+
+            ray_t.min = ray_t.min.max(t0);
+            ray_t.max = ray_t.max.min(t1);
+            if ray_t.max <= ray_t.min {return false;}
+        }
+        true
+    }
+}
+
+pub const EMPTY: Aabb = Aabb {x: interval::EMPTY, y: interval::EMPTY, z: interval::EMPTY};
+pub const UNIVERSE: Aabb = Aabb {x: interval::UNIVERSE, y: interval::UNIVERSE, z: interval::UNIVERSE};
@@ -0,0 +1,45 @@
+use std::sync::Arc;
+
+use crate::{
+    aabb::Aabb, 
+    animated_transform::AnimatedTransform, 
+    hit_record::HitRecord, 
+    hittable::Hittable, 
+    interval::Interval, 
+    ray::Ray, 
+    transformed::hit_transformed,
+};
+
+/// An instance of a shared object that moves along keyframes, placed at each ray's time.
+pub struct Animated<H: Hittable + ?Sized> {
+    object: Arc<H>,
+    transform: AnimatedTransform,
+    bbox: Aabb,
+}
+
+impl<H: Hittable + ?Sized> Animated<H> {
+    pub fn new(object: Arc<H>, transform: AnimatedTransform) -> Self {
+        let bbox = transform.bounding_box(&object.bounding_box());
+        Self {object, transform, bbox}
+    }
+
+    pub fn object(&self) -> &Arc<H> {
+        &self.object
+    }
+
+    pub fn transform(&self) -> &AnimatedTransform {
+        &self.transform
+    }
+}
+
+impl<H: Hittable + ?Sized> Hittable for Animated<H> {
+    fn hit(&self, ray: &Ray, ray_t: Interval, hit_record: &mut HitRecord) -> bool {
+        let transform = self.transform.at(ray.time);
+        hit_transformed(self.object.as_ref(), &transform, ray, ray_t, hit_record)
+    }
+
+    /// Covers every pose, so the BVH finds the object at any time.
+    fn bounding_box(&self) -> Aabb {
+        self.bbox
+    }
+}
@@ -0,0 +1,109 @@
+use crate::{
+    aabb::Aabb, 
+    interval::Interval, 
+    quaternion::Quaternion, 
+    transform::Transform, 
+    vec::Vec3,
+};
+
+/// Poses sampled between each pair of keyframes when bounding the motion.
+const BOUND_STEPS: usize = 32;
+
+/// The pose of an object at one moment: scaled, then rotated, then translated.
+#[derive(Clone, Copy)]
+pub struct Keyframe {
+    pub time: f32,
+    pub scale: Vec3,
+    pub rotation: Quaternion,
+    pub translation: Vec3,
+}
+
+impl Keyframe {
+    pub fn transform(&self) -> Transform {
+        let matrix = self.rotation.to_mat4();
+        let rotation = Transform {matrix, inverse: matrix.transpose()};
+        Transform::scale(&self.scale)
+            .then(&rotation)
+            .then(&Transform::translate(&self.translation))
+    }
+
+    /// Blends the parts of two poses separately, so rotations turn rather than shear.
+    fn lerp(&self, other: &Self, fraction: f32) -> Self {
+        Self {
+            time: self.time + fraction * (other.time - self.time),
+            scale: self.scale + fraction * (other.scale - self.scale),
+            rotation: self.rotation.slerp(&other.rotation, fraction),
+            translation: self.translation + fraction * (other.translation - self.translation),
+        }
+    }
+}
+
+/// A transform that changes over time by interpolating between keyframes. Before the first
+/// and after the last keyframe the pose holds still.
+#[derive(Clone)]
+pub struct AnimatedTransform {
+    keyframes: Vec<Keyframe>,
+}
+
+impl AnimatedTransform {
+    /// Returns `None` without keyframes. Every scale factor must be nonzero.
+    pub fn new(mut keyframes: Vec<Keyframe>) -> Option<Self> {
+        if keyframes.is_empty() {return None;}
+        keyframes.sort_by(|a, b| a.time.total_cmp(&b.time));
+        Some(Self {keyframes})
+    }
+
+    pub fn keyframes(&self) -> &[Keyframe] {
+        &self.keyframes
+    }
+
+    pub fn keyframe_at(&self, time: f32) -> Keyframe {
+        let next = self.keyframes.partition_point(|keyframe| keyframe.time <= time);
+        if next == 0 {return self.keyframes[0];}
+        if next == self.keyframes.len() {return self.keyframes[next - 1];}
+
+        let (previous, next) = (&self.keyframes[next - 1], &self.keyframes[next]);
+        previous.lerp(next, (time - previous.time) / (next.time - previous.time))
+    }
+
+    pub fn at(&self, time: f32) -> Transform {
+        self.keyframe_at(time).transform()
+    }
+
+    /// The box around `bbox` in every pose. Poses are sampled between keyframes, and the box is
+    /// grown by how far a rotating corner can bulge out between two samples.
+    pub fn bounding_box(&self, bbox: &Aabb) -> Aabb {
+        let first = self.keyframes[0].transform().bounding_box(bbox);
+        if bbox.is_empty() || !first.is_bounded() {return first;}
+
+        // The farthest any corner of `bbox` lies from the origin, before scaling
+        let extent = |axis: Interval| axis.min.abs().max(axis.max.abs());
+        let corner_distance = Vec3 {x: extent(bbox.x), y: extent(bbox.y), z: extent(bbox.z)};
+
+        let mut motion_box = first;
+        for pair in self.keyframes.windows(2) {
+            let (start, end) = (&pair[0], &pair[1]);
+            let mut largest_scale: f32 = 0.0;
+            for step in 0..=BOUND_STEPS {
+                let pose = start.lerp(end, step as f32 / BOUND_STEPS as f32);
+                largest_scale = largest_scale.max(pose.scale.x.abs())
+                    .max(pose.scale.y.abs())
+                    .max(pose.scale.z.abs());
+                motion_box = Aabb::enclosing(&motion_box, &pose.transform().bounding_box(bbox));
+            }
+
+            // A point at distance r turning by a small angle strays r(1 - cos(angle/2)) from
+            // the chord; doubled to also cover scaling while turning
+            let step_angle = start.rotation.angle_to(&end.rotation) / BOUND_STEPS as f32;
+            let radius = largest_scale * corner_distance.length();
+            let bulge = 2.0 * radius * (1.0 - (step_angle / 2.0).cos());
+            let grow = |axis: Interval| axis.expand(2.0 * bulge);
+            motion_box = Aabb {
+                x: grow(motion_box.x), 
+                y: grow(motion_box.y), 
+                z: grow(motion_box.z),
+            };
+        }
+        motion_box
+    }
+}
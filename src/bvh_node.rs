@@ -0,0 +1,128 @@
+use std::sync::Arc;
+
+use crate::{
+    aabb::Aabb, 
+    hittable::Hittable, 
+    hit_record::HitRecord, 
+    hittable_list::HittableList, 
+    interval::Interval, 
+    ray::Ray,
+};
+
+/// Number of centroid buckets tried along each axis when searching for a split.
+const SAH_BUCKETS: usize = 12;
+
+/// A bounding volume hierarchy, split with the surface area heuristic.
+pub struct BvhNode {
+    left: Arc<dyn Hittable>,
+    right: Option<Arc<dyn Hittable>>,
+    bbox: Aabb,
+}
+
+impl BvhNode {
+    pub fn new(list: HittableList) -> Self {
+        let objects = list.objects
+            .into_iter()
+            .map(|object| (object.bounding_box(), object))
+            .collect();
+        Self::from_boxed(objects)
+    }
+
+    fn from_boxed(mut objects: Vec<(Aabb, Arc<dyn Hittable>)>) -> Self {
+        let bbox = objects
+            .iter()
+            .fold(Aabb::default(), |bbox, (object_box, _)| Aabb::enclosing(&bbox, object_box));
+
+        match objects.len() {
+            0 => Self {left: Arc::<_>::new(HittableList::default()), right: None, bbox},
+            1 => Self {left: objects.remove(0).1, right: None, bbox},
+            2 => {
+                let (_, right) = objects.remove(1);
+                let (_, left) = objects.remove(0);
+                Self {left, right: Some(right), bbox}
+            },
+            _ => {
+                let right_objects = split_by_surface_area(objects.as_mut_slice());
+                let right_objects = objects.split_off(right_objects);
+                Self {
+                    left: Arc::<_>::new(Self::from_boxed(objects)),
+                    right: Some(Arc::<_>::new(Self::from_boxed(right_objects))),
+                    bbox,
+                }
+            },
+        }
+    }
+}
+
+/// Reorders `objects` so that the cheapest split under the surface area heuristic is at the
+/// returned index.
+fn split_by_surface_area(objects: &mut [(Aabb, Arc<dyn Hittable>)]) -> usize {
+    let centroid_bounds = objects.iter().fold(Aabb::default(), |bounds, (object_box, _)| {
+        let centroid = object_box.centroid();
+        Aabb::enclosing(&bounds, &Aabb::from_points(&centroid, &centroid))
+    });
+
+    let mut best: Option<(f32, usize, f32)> = None;
+    for axis in 0..3 {
+        let extent = *centroid_bounds.axis(axis);
+        if extent.size() <= 0.0 {continue;}
+
+        let bucket_of = |object_box: &Aabb| {
+            let offset = (object_box.centroid()[axis] - extent.min) / extent.size();
+            ((offset * SAH_BUCKETS as f32) as usize).min(SAH_BUCKETS - 1)
+        };
+
+        let mut buckets = [(0usize, Aabb::default()); SAH_BUCKETS];
+        for (object_box, _) in objects.iter() {
+            let bucket = &mut buckets[bucket_of(object_box)];
+            bucket.0 += 1;
+            bucket.1 = Aabb::enclosing(&bucket.1, object_box);
+        }
+
+        for split in 1..SAH_BUCKETS {
+            let side_cost = |side: &[(usize, Aabb)]| {
+                let (count, bbox) = side.iter().fold((0, Aabb::default()), |(count, bbox), bucket| {
+                    (count + bucket.0, Aabb::enclosing(&bbox, &bucket.1))
+                });
+                count as f32 * bbox.surface_area()
+            };
+            let cost = side_cost(&buckets[..split]) + side_cost(&buckets[split..]);
+            if best.is_none_or(|(best_cost, _, _)| cost < best_cost) {
+                let boundary = extent.min + extent.size() * split as f32 / SAH_BUCKETS as f32;
+                best = Some((cost, axis, boundary));
+            }
+        }
+    }
+
+    let middle = objects.len() / 2;
+    let Some((_, axis, boundary)) = best else {return middle;};
+
+    let mut left_count = 0;
+    for index in 0..objects.len() {
+        if objects[index].0.centroid()[axis] < boundary {
+            objects.swap(index, left_count);
+            left_count += 1;
+        }
+    }
+
+    // Buckets can put every centroid on one side when the boundary lands on a shared value
+    if left_count == 0 || left_count == objects.len() {middle} else {left_count}
+}
+
+impl Hittable for BvhNode {
+    fn hit(&self, ray: &Ray, ray_t: Interval, hit_record: &mut HitRecord) -> bool {
+        if !self.bbox.hit(ray, ray_t) {return false;}
+
+        let hit_left = self.left.hit(ray, ray_t, hit_record);
+        let closest_so_far = if hit_left {hit_record.t} else {ray_t.max};
+        let hit_right = self.right.as_ref().is_some_and(|right| {
+            right.hit(ray, Interval {min: ray_t.min, max: closest_so_far}, hit_record)
+        });
+
+        hit_left || hit_right
+    }
+
+    fn bounding_box(&self) -> Aabb {
+        self.bbox
+    }
+}
@@ -1,24 +1,55 @@
 use crate::{
+    environment::Environment, 
+    gradient_environment::GradientEnvironment, 
     ray::Ray, 
     hittable::Hittable, 
-    color::{Color, write_color}, 
+    color::Color, 
+    film::Film, 
     hit_record::HitRecord, 
+    integrator::Integrator, 
     interval::Interval, 
+    light::same_distance, 
+    light_list::LightList, 
+    material_arena::MaterialArena, 
     logger::{Logger, log},
-    vec::{Point3, Vec3, Vec2, random_in_unit_disk}, util::{random_double, degrees_to_radians},
+    sampler::{Sampler, entropy_seed}, 
+    vec::{Point3, Vec3, Vec2, random_in_unit_disk}, 
+    util::{degrees_to_radians, power_heuristic},
 };
+use std::sync::{Arc, atomic::{AtomicUsize, Ordering}, mpsc};
 
+struct Tile {
+    x0: i32,
+    y0: i32,
+    x1: i32,
+    y1: i32,
+}
+
+#[derive(Clone)]
 pub struct Camera {
     pub aspect_ratio: f32,
+    /// Image size in pixels. A height of 0 is derived from the width and `aspect_ratio`.
     pub image: Vec2<i32>,
     pub samples_per_pixel: i32,
     pub max_depth: i32,
+    /// Bounces before paths start being terminated at random by Russian roulette.
+    pub russian_roulette_depth: i32,
     pub vertical_field_of_view: f32,
     pub look_from: Point3,
     pub look_at: Point3,
     pub vertical_up: Vec3,
     pub defocus_angle: f32,
     pub focus_distance: f32,
+    /// The times the shutter opens and closes, within the 0 to 1 span moving spheres travel
+    /// over. Each ray samples a moment in between, which blurs moving objects.
+    pub shutter: Interval,
+    /// Lights rays that escape the scene.
+    pub environment: Arc<dyn Environment>,
+    pub thread_count: usize,
+    pub tile_size: i32,
+    /// Makes renders reproducible. Without one every render draws a fresh seed.
+    pub seed: Option<u64>,
+    pub integrator: Integrator,
     center: Point3,
     pixel00_loc: Point3,
     pixel_delta: Vec2<Vec3>,
@@ -36,12 +67,19 @@ impl Default for Camera {
             image: Vec2 {width: 100, height: 0},
             samples_per_pixel: 10,
             max_depth: 10,
+            russian_roulette_depth: 3,
             vertical_field_of_view: 90.0,
             look_from: Point3 {x: 0.0, y: 0.0, z: -1.0},
             look_at: Point3::default(),
             vertical_up: Vec3 {x: 0.0, y: 1.0, z: 0.0},
             defocus_angle: f32::default(),
             focus_distance: 10.0,
+            shutter: Interval {min: 0.0, max: 1.0},
+            environment: Arc::<GradientEnvironment>::default(),
+            thread_count: std::thread::available_parallelism().map_or(1, |count| count.get()),
+            tile_size: 16,
+            seed: None,
+            integrator: Integrator::default(),
             center: Point3::default(),
             pixel00_loc: Vec3::default(),
             pixel_delta: Vec2::default(),
@@ -55,36 +93,125 @@ impl Default for Camera {
 }
 
 impl Camera {
-    pub fn render(&mut self, logger: &mut Logger, world: &mut impl Hittable) {
-        self.initialize();
-
-        log(
-            &mut logger.stdout, 
-            format!("P3\n{} {}\n255\n", self.image.width, self.image.height)
-        );
-        
-        for j in 0..self.image.width {
-            log(
-                &mut logger.stderr, 
-                format!("\rScanlines remaining: {}", self.image.width - j),
-            );
-            for i in 0..self.image.width {
+    /// Renders `world`, whose hits refer to `materials`, sampling the registered `lights`
+    /// directly at every diffuse bounce. The camera itself is left untouched, so one camera and
+    /// scene can be shared between several renders at once.
+    pub fn render(
+        &self, 
+        logger: &mut Logger, 
+        world: &dyn Hittable, 
+        materials: &MaterialArena, 
+        lights: &LightList
+    ) -> Film {
+        let mut camera = self.clone();
+        camera.initialize();
+        camera.render_frame(logger, world, materials, lights)
+    }
+
+    /// Renders with the viewport already set up by `initialize`.
+    fn render_frame(
+        &self, 
+        logger: &mut Logger, 
+        world: &dyn Hittable, 
+        materials: &MaterialArena, 
+        lights: &LightList
+    ) -> Film {
+        let seed = self.seed.unwrap_or_else(entropy_seed);
+        let tiles = self.tiles();
+        let mut film = Film::new(self.image.width as usize, self.image.height as usize);
+        let next_tile = AtomicUsize::new(0);
+        let (sender, receiver) = mpsc::channel();
+
+        std::thread::scope(|scope| {
+            for _ in 0..self.thread_count.max(1) {
+                let sender = sender.clone();
+                let (tiles, next_tile, camera) = (&tiles, &next_tile, &*self);
+                scope.spawn(move || loop {
+                    let index = next_tile.fetch_add(1, Ordering::Relaxed);
+                    let Some(tile) = tiles.get(index) else {break;};
+                    let pixels = camera.render_tile(tile, seed, world, materials, lights);
+                    if sender.send((index, pixels)).is_err() {break;}
+                });
+            }
+            drop(sender);
+
+            for tiles_remaining in (0..tiles.len()).rev() {
+                let (index, pixels) = receiver.recv().expect("Render worker exited early");
+                let tile = &tiles[index];
+                let mut pixels = pixels.into_iter();
+                for y in tile.y0..tile.y1 {
+                    for x in tile.x0..tile.x1 {
+                        film.add_samples(
+                            x as usize, 
+                            y as usize, 
+                            pixels.next().expect("Tile is missing pixels"), 
+                            self.samples_per_pixel as u32,
+                        );
+                    }
+                }
+                log(
+                    &mut logger.stderr, 
+                    format!("\rTiles remaining: {}  ", tiles_remaining),
+                );
+            }
+        });
+
+        film
+    }
+
+    /// Splits the image into `tile_size` squares, clipped at the right and bottom edges.
+    fn tiles(&self) -> Vec<Tile> {
+        let tile_size = self.tile_size.max(1);
+        let mut tiles = vec![];
+        for y0 in (0..self.image.height).step_by(tile_size as usize) {
+            for x0 in (0..self.image.width).step_by(tile_size as usize) {
+                tiles.push(Tile {
+                    x0,
+                    y0,
+                    x1: (x0 + tile_size).min(self.image.width),
+                    y1: (y0 + tile_size).min(self.image.height),
+                });
+            }
+        }
+        tiles
+    }
+
+    /// Returns the summed samples of every pixel in the tile, in row-major order. Each sample
+    /// draws from its own sampler, so the output doesn't depend on which thread rendered it.
+    fn render_tile(
+        &self, 
+        tile: &Tile, 
+        seed: u64, 
+        world: &dyn Hittable, 
+        materials: &MaterialArena, 
+        lights: &LightList
+    ) -> Vec<Color> {
+        let mut pixels = Vec::with_capacity(((tile.x1 - tile.x0) * (tile.y1 - tile.y0)) as usize);
+        for j in tile.y0..tile.y1 {
+            for i in tile.x0..tile.x1 {
+                let pixel = j as u64 * self.image.width as u64 + i as u64;
                 let mut pixel_color = Color::default();
-                for _ in 0..self.samples_per_pixel {
-                    let ray = self.get_ray(i, j);
-                    pixel_color += self.ray_color(&ray, self.max_depth, world);
+                for sample in 0..self.samples_per_pixel {
+                    let mut sampler = Sampler::for_sample(seed, pixel, sample as u64);
+                    let ray = self.get_ray(i, j, &mut sampler);
+                    pixel_color += match self.integrator {
+                        Integrator::Path => {
+                            self.ray_color(&ray, world, materials, lights, &mut sampler)
+                        },
+                        Integrator::Normals => self.normal_color(&ray, world),
+                    };
                 }
-                write_color(&mut logger.stdout, pixel_color, self.samples_per_pixel);
+                pixels.push(pixel_color);
             }
         }
+        pixels
     }
 
     fn initialize(&mut self) {
         // Image
-        self.aspect_ratio = 16.0 / 9.0;
-        self.image.width = 400;
-        self.samples_per_pixel = 100;
-        self.image.height = ((self.image.width as f32 / self.aspect_ratio) as i32).max(1);
+        if self.image.height <= 0 {
+            self.image.height = ((self.image.width as f32 / self.aspect_ratio) as i32).max(1);
+        }
 
         self.center = self.look_from;
 
@@ -128,53 +255,148 @@ impl Camera {
         self.defocus_disk_v = self.basis_v * defocus_radius;
     }
     
-    fn ray_color(&mut self, ray: &Ray, depth: i32, world: &mut impl Hittable) -> Color {
-        let mut hit_record = HitRecord::default();
+    /// Traces a path from the camera, following one scattered ray per bounce. Once a path is
+    /// `russian_roulette_depth` bounces long it survives each further bounce with a probability
+    /// that follows its throughput, and survivors are scaled up to keep the estimate unbiased.
+    fn ray_color(
+        &self, 
+        ray: &Ray, 
+        world: &dyn Hittable, 
+        materials: &MaterialArena, 
+        lights: &LightList, 
+        sampler: &mut Sampler
+    ) -> Color {
+        let mut ray = Ray {origin: ray.origin, direction: ray.direction, time: ray.time};
+        let mut radiance = Color::default();
+        let mut throughput = Color {x: 1.0, y: 1.0, z: 1.0};
+        // The density the last bounce picked `ray` with, to weigh light the path finds against
+        // the light sampling done there. `None` for camera rays and after specular bounces.
+        let mut bsdf_pdf = None;
+
+        for depth in 0..self.max_depth {
+            let mut hit_record = HitRecord::default();
+            if !world.hit(&ray, Interval {min: 0.001, max: f32::INFINITY}, &mut hit_record) {
+                let weight = light_weight(lights, &ray, f32::INFINITY, bsdf_pdf);
+                radiance += throughput * weight * self.environment.radiance(&ray.direction);
+                break;
+            }
 
-        // If we've exceeded the ray bounce limit, no more light is gathered
-        if depth <= 0 {return Color {x: 0.0, y: 0.0, z: 0.0};}
-
-        if world.hit(ray, Interval {min: 0.001, max: f32::INFINITY}, &mut hit_record) {
-            let mut scattered = Ray::default();
-            let mut attenuation = Color::default();
-            if hit_record.material.scatter(
-                &ray, 
-                &hit_record, 
-                &mut attenuation, 
-                &mut scattered
-            ) {
-                return attenuation * self.ray_color(&scattered, depth - 1, world);
+            let material = &materials[hit_record.material];
+            if material.is_emissive() {
+                let weight = light_weight(lights, &ray, hit_record.t, bsdf_pdf);
+                radiance += throughput * weight * material.emitted(&ray, &hit_record);
             }
-            return Color {x: 0.0, y: 0.0, z: 0.0};
+
+            let Some(sample) = material.scatter(&ray, &hit_record, sampler) else {break;};
+            bsdf_pdf = if sample.is_specular {
+                None
+            } else {
+                radiance += throughput 
+                    * self.direct_light(&ray, &hit_record, world, materials, lights, sampler);
+                Some(sample.pdf)
+            };
+            throughput *= sample.weight;
+
+            if depth + 1 >= self.russian_roulette_depth {
+                let survival = throughput.x.max(throughput.y).max(throughput.z).min(1.0);
+                if sampler.next_f32() >= survival {break;}
+                throughput /= survival;
+            }
+            ray = Ray {origin: hit_record.point, direction: sample.direction, time: ray.time};
         }
-    
-        let unit_direction = ray.direction.unit_vector();
-        let alpha = 0.5 * (unit_direction.y + 1.0);
-        (1.0 - alpha) * Color {x: 1.0, y: 1.0, z: 1.0} + alpha * Color{x: 0.5, y: 0.7, z: 1.0}
+
+        radiance
     }
 
-    fn get_ray(&self, i: i32, j: i32) -> Ray {
+    /// Estimates the light arriving straight from one randomly picked light, traced with a
+    /// shadow ray and weighed against finding the same light by sampling the material. Only the
+    /// light that was sampled may contribute.
+    fn direct_light(
+        &self, 
+        ray: &Ray, 
+        hit_record: &HitRecord, 
+        world: &dyn Hittable, 
+        materials: &MaterialArena, 
+        lights: &LightList, 
+        sampler: &mut Sampler
+    ) -> Color {
+        let Some(sample) = lights.sample(
+            &hit_record.point, 
+            sampler.next_f32(), 
+            sampler.next_f32(), 
+            sampler.next_f32(),
+        ) else {
+            return Color::default();
+        };
+
+        let wo = -ray.direction.unit_vector();
+        let material = &materials[hit_record.material];
+        let bsdf = material.eval(hit_record, &sample.direction, &wo);
+        if bsdf.near_zero() {return Color::default();}
+
+        let shadow_ray = Ray {
+            origin: hit_record.point, 
+            direction: sample.direction, 
+            time: ray.time,
+        };
+        let mut shadow_hit = HitRecord::default();
+        let radiance = if world.hit(
+            &shadow_ray, 
+            Interval {min: 0.001, max: f32::INFINITY}, 
+            &mut shadow_hit
+        ) {
+            if !same_distance(shadow_hit.t, sample.distance) {return Color::default();}
+            materials[shadow_hit.material].emitted(&shadow_ray, &shadow_hit)
+        } else {
+            if sample.distance.is_finite() {return Color::default();}
+            self.environment.radiance(&sample.direction)
+        };
+
+        let bsdf_pdf = material.pdf(hit_record, &sample.direction, &wo);
+        power_heuristic(sample.pdf, bsdf_pdf) * bsdf * radiance / sample.pdf
+    }
+
+    fn normal_color(&self, ray: &Ray, world: &dyn Hittable) -> Color {
+        let mut hit_record = HitRecord::default();
+        if !world.hit(ray, Interval {min: 0.001, max: f32::INFINITY}, &mut hit_record) {
+            return Color::default();
+        }
+        0.5 * (hit_record.normal + Color {x: 1.0, y: 1.0, z: 1.0})
+    }
+
+    fn get_ray(&self, i: i32, j: i32, sampler: &mut Sampler) -> Ray {
         let pixel_center = self.pixel00_loc 
             + i as f32 * self.pixel_delta.width 
             + j as f32 * self.pixel_delta.height;
-        let pixel_sample = pixel_center + self.pixel_sample_square();
+        let pixel_sample = pixel_center + self.pixel_sample_square(sampler);
         let origin = if self.defocus_angle <= 0.0 {
             self.center
         } else {
-            self.defocus_disk_sample()
+            self.defocus_disk_sample(sampler)
         };
 
-        Ray {origin, direction: pixel_sample - origin}
+        let time = self.shutter.min + sampler.next_f32() * self.shutter.size();
+
+        Ray {origin, direction: pixel_sample - origin, time}
     }
 
-    fn pixel_sample_square(&self) -> Vec3 {
-        let px = -0.5 + random_double(None, None);
-        let py = -0.5 + random_double(None, None);
+    fn pixel_sample_square(&self, sampler: &mut Sampler) -> Vec3 {
+        let px = -0.5 + sampler.next_f32();
+        let py = -0.5 + sampler.next_f32();
         px * self.pixel_delta.width + py * self.pixel_delta.height
     }
 
-    fn defocus_disk_sample(&self) -> Vec3 {
-        let p = random_in_unit_disk();
+    fn defocus_disk_sample(&self, sampler: &mut Sampler) -> Vec3 {
+        let p = random_in_unit_disk(sampler);
         self.center + p.x * self.defocus_disk_u + p.y * self.defocus_disk_v
     }
-}
\ No newline at end of file
+}
+
+/// The multiple importance sampling weight for light found by a path whose last bounce picked
+/// `ray` with density `bsdf_pdf`, where the light met at `t` could also have been sampled.
+fn light_weight(lights: &LightList, ray: &Ray, t: f32, bsdf_pdf: Option<f32>) -> f32 {
+    match bsdf_pdf {
+        Some(bsdf_pdf) => power_heuristic(bsdf_pdf, lights.pdf(ray, t)),
+        None => 1.0,
+    }
+}
@@ -0,0 +1,29 @@
+use std::sync::Arc;
+
+use crate::{color::Color, solid_color::SolidColor, texture::Texture, vec::Point3};
+
+/// Alternates between two textures in a 3D grid of cubes with sides of length `1 / inverse_scale`.
+pub struct CheckerTexture {
+    pub inverse_scale: f32,
+    pub even: Arc<dyn Texture>,
+    pub odd: Arc<dyn Texture>,
+}
+
+impl CheckerTexture {
+    pub fn new(scale: f32, even: Color, odd: Color) -> Self {
+        Self {
+            inverse_scale: 1.0 / scale,
+            even: Arc::<_>::new(SolidColor {albedo: even}),
+            odd: Arc::<_>::new(SolidColor {albedo: odd}),
+        }
+    }
+}
+
+impl Texture for CheckerTexture {
+    fn value(&self, u: f32, v: f32, point: &Point3) -> Color {
+        let cell = |coordinate: f32| (self.inverse_scale * coordinate).floor() as i32;
+        let is_even = (cell(point.x) + cell(point.y) + cell(point.z)) % 2 == 0;
+
+        if is_even {self.even.value(u, v, point)} else {self.odd.value(u, v, point)}
+    }
+}
@@ -0,0 +1,163 @@
+use std::path::{Path, PathBuf};
+
+use raytracer::{
+    exr::ExrPixelType, 
+    image_format::ImageFormat, 
+    integrator::Integrator, 
+    png::PngBitDepth,
+};
+
+pub const USAGE: &str = "\
+Usage: raytracer [OPTIONS] [SCENE]
+
+Renders SCENE, a JSON scene description, or the random spheres scene if no
+scene is given. Options override the scene's camera settings.
+
+Options:
+  -o, --output FILE        Write the image to FILE instead of stdout
+  -f, --format FORMAT      Image format: ppm, png, pfm, exr or hdr [default: from
+                           the output extension, or ppm if there is none]
+      --bit-depth BITS     PNG bits per channel: 8 or 16 [default: 8]
+      --alpha              Add an alpha channel to PNG output
+      --half               Store EXR channels as 16-bit halves instead of 32-bit floats
+  -W, --width PIXELS       Image width
+  -H, --height PIXELS      Image height; derived from the aspect ratio if omitted
+  -s, --samples COUNT      Samples per pixel
+  -d, --max-depth COUNT    Maximum number of bounces per path
+      --roulette-depth COUNT
+                           Bounces before Russian roulette may end a path
+  -t, --threads COUNT      Number of render threads [default: available cores]
+      --seed SEED          Seed the random number generator for reproducible renders
+  -i, --integrator NAME    path or normals [default: path]
+  -h, --help               Print this help text
+
+Exit status is 0 on success, 1 if the scene could not be loaded or the image
+could not be rendered or written, and 2 for invalid arguments.
+";
+
+/// Command line settings. Anything left as `None` keeps the scene's own value.
+#[derive(Default, Debug)]
+pub struct Options {
+    pub scene: Option<PathBuf>,
+    pub output: Option<PathBuf>,
+    pub format: Option<ImageFormat>,
+    pub bit_depth: Option<PngBitDepth>,
+    pub alpha: bool,
+    pub half: bool,
+    pub width: Option<i32>,
+    pub height: Option<i32>,
+    pub samples_per_pixel: Option<i32>,
+    pub max_depth: Option<i32>,
+    pub russian_roulette_depth: Option<i32>,
+    pub thread_count: Option<usize>,
+    pub seed: Option<u64>,
+    pub integrator: Option<Integrator>,
+    pub help: bool,
+}
+
+impl Options {
+    /// Parses the arguments after the program name. Flags take their value either as the next
+    /// argument or after an `=`.
+    pub fn parse(args: impl IntoIterator<Item = String>) -> Result<Self, String> {
+        let mut options = Self::default();
+        let mut args = args.into_iter();
+
+        while let Some(arg) = args.next() {
+            if !arg.starts_with('-') || arg == "-" {
+                if options.scene.replace(PathBuf::from(&arg)).is_some() {
+                    return Err(format!("Unexpected argument '{}'", arg));
+                }
+                continue;
+            }
+
+            let (flag, inline_value) = match arg.split_once('=') {
+                Some((flag, value)) => (flag.to_string(), Some(value.to_string())),
+                None => (arg.clone(), None),
+            };
+            let mut value = || {
+                inline_value
+                    .clone()
+                    .or_else(|| args.next())
+                    .ok_or_else(|| format!("'{}' needs a value", flag))
+            };
+
+            match flag.as_str() {
+                "-h" | "--help" => options.help = true,
+                "-o" | "--output" => options.output = Some(PathBuf::from(value()?)),
+                "-f" | "--format" => {
+                    let name = value()?;
+                    let format = ImageFormat::from_name(&name)
+                        .ok_or_else(|| format!("Unknown format '{}'", name))?;
+                    options.format = Some(format);
+                },
+                "--bit-depth" => {
+                    let bits = value()?;
+                    options.bit_depth = match bits.as_str() {
+                        "8" => Some(PngBitDepth::Eight),
+                        "16" => Some(PngBitDepth::Sixteen),
+                        _ => return Err(format!("'{}' must be 8 or 16, got '{}'", flag, bits)),
+                    };
+                },
+                "--alpha" => options.alpha = true,
+                "--half" => options.half = true,
+                "-W" | "--width" => options.width = Some(positive(&flag, &value()?)?),
+                "-H" | "--height" => options.height = Some(positive(&flag, &value()?)?),
+                "-s" | "--samples" => options.samples_per_pixel = Some(positive(&flag, &value()?)?),
+                "-d" | "--max-depth" => options.max_depth = Some(positive(&flag, &value()?)?),
+                "--roulette-depth" => {
+                    options.russian_roulette_depth = Some(number(&flag, &value()?)?);
+                },
+                "-t" | "--threads" => options.thread_count = Some(positive(&flag, &value()?)?),
+                "--seed" => options.seed = Some(number(&flag, &value()?)?),
+                "-i" | "--integrator" => {
+                    let name = value()?;
+                    let integrator = Integrator::from_name(&name)
+                        .ok_or_else(|| format!("Unknown integrator '{}'", name))?;
+                    options.integrator = Some(integrator);
+                },
+                _ => return Err(format!("Unknown option '{}'", flag)),
+            }
+        }
+
+        // Guessing from an extension we don't know would write the wrong kind of file
+        let extension = options.output.as_deref().and_then(Path::extension);
+        if let Some(extension) = extension.filter(|_| options.format.is_none()) {
+            let extension = extension.to_string_lossy();
+            if ImageFormat::from_name(&extension).is_none() {
+                return Err(format!("Unknown output extension '.{}', use --format", extension));
+            }
+        }
+
+        Ok(options)
+    }
+
+    /// The format to write: `--format` if given, otherwise the one matching the output file's
+    /// extension. PPM is used for stdout and for files without an extension; `parse` has
+    /// already rejected extensions that match no format.
+    pub fn image_format(&self) -> ImageFormat {
+        let format = self.format
+            .or_else(|| self.output.as_deref().and_then(ImageFormat::from_path))
+            .unwrap_or(ImageFormat::Ppm);
+        match format {
+            ImageFormat::Png(mut png) => {
+                if let Some(bit_depth) = self.bit_depth {png.bit_depth = bit_depth;}
+                png.alpha |= self.alpha;
+                ImageFormat::Png(png)
+            },
+            ImageFormat::Exr(_) if self.half => ImageFormat::Exr(ExrPixelType::Half),
+            other => other,
+        }
+    }
+}
+
+fn number<T: std::str::FromStr>(flag: &str, value: &str) -> Result<T, String> {
+    value.parse().map_err(|_| format!("'{}' expects a number, got '{}'", flag, value))
+}
+
+fn positive<T>(flag: &str, value: &str) -> Result<T, String>
+where T: std::str::FromStr + Default + PartialOrd,
+{
+    let parsed: T = number(flag, value)?;
+    if parsed <= T::default() {return Err(format!("'{}' must be greater than zero", flag));}
+    Ok(parsed)
+}
@@ -3,35 +3,38 @@ use crate::interval::Interval;
 pub use super::vec::Vec3 as Color;
 
 fn color_to_u8(float: f32, interval: &Interval) -> u8 {
-    (((std::u8::MAX as f32 + 1.0) - std::f32::EPSILON) * interval.clamp(float)) as _
+    (((u8::MAX as f32 + 1.0) - f32::EPSILON) * interval.clamp(float)) as _
 }
 
 pub fn linear_to_gamma(linear_component: f32) -> f32 {
     linear_component.sqrt()
 }
 
-pub fn write_color(stream: &mut impl std::io::Write, pixel_color: Color, samples_per_pixel: i32) {
-    let mut red = pixel_color.x;
-    let mut green = pixel_color.y;
-    let mut blue = pixel_color.z;
+pub fn gamma_to_linear(gamma_component: f32) -> f32 {
+    gamma_component.powi(2)
+}
 
-    let scale = 1.0 / samples_per_pixel as f32;
-    red *= scale;
-    green *= scale;
-    blue *= scale;
+/// Relative luminance of a linear Rec. 709 color.
+pub fn luminance(color: &Color) -> f32 {
+    0.2126 * color.x + 0.7152 * color.y + 0.0722 * color.z
+}
 
-    red = linear_to_gamma(red);
-    green = linear_to_gamma(green);
-    blue = linear_to_gamma(blue);
+/// Applies the sRGB transfer curve, for formats that declare their pixels as sRGB.
+pub fn linear_to_srgb(linear_component: f32) -> f32 {
+    if linear_component <= 0.003_130_8 {
+        12.92 * linear_component
+    } else {
+        1.055 * linear_component.powf(1.0 / 2.4) - 0.055
+    }
+}
 
+/// Gamma corrects a linear color and quantizes each channel to a byte.
+pub fn to_rgb8(pixel_color: Color) -> [u8; 3] {
     let intensity = Interval {min: 0.0, max: 1.0 - f32::EPSILON};
 
-    super::logger::log(
-        stream, 
-        format!("{} {} {}\n", 
-            color_to_u8(red, &intensity), 
-            color_to_u8(green, &intensity), 
-            color_to_u8(blue, &intensity)
-        )
-    );
-}
\ No newline at end of file
+    [
+        color_to_u8(linear_to_gamma(pixel_color.x), &intensity),
+        color_to_u8(linear_to_gamma(pixel_color.y), &intensity),
+        color_to_u8(linear_to_gamma(pixel_color.z), &intensity),
+    ]
+}
@@ -0,0 +1,107 @@
+use std::f32::consts::PI;
+
+use crate::{
+    aabb::Aabb, 
+    frame::Frame, 
+    hit_record::HitRecord, 
+    hittable::Hittable, 
+    interval::Interval, 
+    material_arena::MaterialId, 
+    ray::Ray, 
+    util::nearest_root, 
+    vec::{Point3, Vec3},
+};
+
+/// A round cone narrowing from a base circle of `radius` to a point at `apex`, closed at the
+/// base when `capped`. Texture coordinates follow `Cylinder`, with `v` reaching 1 at the apex.
+#[derive(Clone)]
+pub struct Cone {
+    pub base: Point3,
+    pub apex: Point3,
+    pub radius: f32,
+    pub capped: bool,
+    pub material: MaterialId,
+    frame: Frame,
+    height: f32,
+}
+
+impl Cone {
+    pub fn new(
+        base: Point3, 
+        apex: Point3, 
+        radius: f32, 
+        capped: bool, 
+        material: MaterialId
+    ) -> Self {
+        let axis = apex - base;
+        let (frame, height) = (Frame::new(&axis), axis.length());
+        Self {base, apex, radius, capped, material, frame, height}
+    }
+}
+
+impl Hittable for Cone {
+    fn hit(&self, ray: &Ray, mut ray_t: Interval, hit_record: &mut HitRecord) -> bool {
+        // Along the axis, local Z runs from 0 at the base to `height` at the apex
+        let origin = self.frame.to_local(&(ray.origin - self.base));
+        let direction = self.frame.to_local(&ray.direction);
+        let mut on_cap = None;
+
+        // The side is x² + y² = (slope * (height - z))², clipped to the nappe below the apex
+        let slope_squared = (self.radius / self.height).powi(2);
+        let below_apex = self.height - origin.z;
+        let a = direction.x.powi(2) + direction.y.powi(2) - slope_squared * direction.z.powi(2);
+        let half_b = origin.x * direction.x + origin.y * direction.y
+            + slope_squared * below_apex * direction.z;
+        let c = origin.x.powi(2) + origin.y.powi(2) - slope_squared * below_apex.powi(2);
+        let along = Interval {min: 0.0, max: self.height};
+        let on_side = |t: f32| along.contains(origin.z + t * direction.z);
+        if let Some(t) = nearest_root(a, half_b, c, ray_t, on_side) {
+            ray_t.max = t;
+            on_cap = Some(false);
+        }
+
+        if self.capped && direction.z.abs() > 1e-8 {
+            let t = -origin.z / direction.z;
+            let radial = (origin.x + t * direction.x).powi(2)
+                + (origin.y + t * direction.y).powi(2);
+            if ray_t.surrounds(t) && radial <= self.radius.powi(2) {
+                ray_t.max = t;
+                on_cap = Some(true);
+            }
+        }
+
+        let Some(on_cap) = on_cap else {return false;};
+        let t = ray_t.max;
+        let local = origin + t * direction;
+        let (outward_normal, v) = if on_cap {
+            let distance = (local.x.powi(2) + local.y.powi(2)).sqrt();
+            (Vec3 {x: 0.0, y: 0.0, z: -1.0}, distance / self.radius)
+        } else {
+            // The gradient of the implicit surface, which vanishes at the apex itself
+            let gradient = Vec3 {
+                x: local.x,
+                y: local.y,
+                z: slope_squared * (self.height - local.z),
+            };
+            let normal = if gradient.length_squared() > 0.0 {
+                gradient.unit_vector()
+            } else {
+                Vec3 {x: 0.0, y: 0.0, z: 1.0}
+            };
+            (normal, local.z / self.height)
+        };
+
+        hit_record.t = t;
+        hit_record.point = ray.at(t);
+        hit_record.set_face_normal(ray, &self.frame.to_world(&outward_normal));
+        (hit_record.u, hit_record.v) = ((local.y.atan2(local.x) + PI) / (2.0 * PI), v);
+        hit_record.material = self.material;
+        true
+    }
+
+    fn bounding_box(&self) -> Aabb {
+        let extent = self.frame.disk_extent(self.radius);
+        let base = Aabb::from_points(&(self.base - extent), &(self.base + extent));
+        Aabb::enclosing(&base, &Aabb::from_points(&self.apex, &self.apex)).pad(1e-4)
+    }
+}
@@ -0,0 +1,11 @@
+use crate::{color::Color, environment::Environment, vec::Vec3};
+
+pub struct ConstantEnvironment {
+    pub color: Color,
+}
+
+impl Environment for ConstantEnvironment {
+    fn radiance(&self, _: &Vec3) -> Color {
+        self.color
+    }
+}
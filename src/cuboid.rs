@@ -0,0 +1,62 @@
+use crate::{
+    aabb::Aabb, 
+    hit_record::HitRecord, 
+    hittable::Hittable, 
+    interval::Interval, 
+    material_arena::MaterialId, 
+    quad::Quad, 
+    ray::Ray, 
+    vec::{Point3, Vec3},
+};
+
+/// An axis-aligned box made of six quads with outward normals. Each face keeps the quad's own
+/// texture coordinates, spanning [0,1] across the face.
+#[derive(Clone)]
+pub struct Cuboid {
+    faces: [Quad; 6],
+    bbox: Aabb,
+}
+
+impl Cuboid {
+    /// Takes any two opposite corners.
+    pub fn new(a: &Point3, b: &Point3, material: MaterialId) -> Self {
+        let bbox = Aabb::from_points(a, b);
+        let min = Point3 {x: bbox.x.min, y: bbox.y.min, z: bbox.z.min};
+        let max = Point3 {x: bbox.x.max, y: bbox.y.max, z: bbox.z.max};
+        let dx = Vec3 {x: max.x - min.x, y: 0.0, z: 0.0};
+        let dy = Vec3 {x: 0.0, y: max.y - min.y, z: 0.0};
+        let dz = Vec3 {x: 0.0, y: 0.0, z: max.z - min.z};
+
+        let faces = [
+            Quad::new(Point3 {x: min.x, y: min.y, z: max.z}, dx, dy, material),
+            Quad::new(Point3 {x: max.x, y: min.y, z: max.z}, -dz, dy, material),
+            Quad::new(Point3 {x: max.x, y: min.y, z: min.z}, -dx, dy, material),
+            Quad::new(Point3 {x: min.x, y: min.y, z: min.z}, dz, dy, material),
+            Quad::new(Point3 {x: min.x, y: max.y, z: max.z}, dx, -dz, material),
+            Quad::new(Point3 {x: min.x, y: min.y, z: min.z}, dx, dz, material),
+        ];
+        Self {faces, bbox: bbox.pad(1e-4)}
+    }
+
+    /// The front, right, back, left, top and bottom faces, in that order.
+    pub fn faces(&self) -> &[Quad; 6] {
+        &self.faces
+    }
+}
+
+impl Hittable for Cuboid {
+    fn hit(&self, ray: &Ray, mut ray_t: Interval, hit_record: &mut HitRecord) -> bool {
+        let mut hit_anything = false;
+        for face in &self.faces {
+            if face.hit(ray, ray_t, hit_record) {
+                hit_anything = true;
+                ray_t.max = hit_record.t;
+            }
+        }
+        hit_anything
+    }
+
+    fn bounding_box(&self) -> Aabb {
+        self.bbox
+    }
+}
@@ -0,0 +1,105 @@
+use std::f32::consts::PI;
+
+use crate::{
+    aabb::Aabb, 
+    frame::Frame, 
+    hit_record::HitRecord, 
+    hittable::Hittable, 
+    interval::Interval, 
+    material_arena::MaterialId, 
+    ray::Ray, 
+    util::nearest_root, 
+    vec::{Point3, Vec3},
+};
+
+/// A round cylinder from the center of `base` to the center of `top`, closed by flat caps when
+/// `capped`. On the side `u` is the angle around the axis and `v` the height, both scaled to
+/// [0,1]; on the caps `v` is the distance from the axis instead, as on a `Disk`.
+#[derive(Clone)]
+pub struct Cylinder {
+    pub base: Point3,
+    pub top: Point3,
+    pub radius: f32,
+    pub capped: bool,
+    pub material: MaterialId,
+    frame: Frame,
+    height: f32,
+}
+
+enum Surface {
+    Side,
+    Bottom,
+    Top,
+}
+
+impl Cylinder {
+    pub fn new(
+        base: Point3, 
+        top: Point3, 
+        radius: f32, 
+        capped: bool, 
+        material: MaterialId
+    ) -> Self {
+        let axis = top - base;
+        let (frame, height) = (Frame::new(&axis), axis.length());
+        Self {base, top, radius, capped, material, frame, height}
+    }
+}
+
+impl Hittable for Cylinder {
+    fn hit(&self, ray: &Ray, mut ray_t: Interval, hit_record: &mut HitRecord) -> bool {
+        // Along the axis, local Z runs from 0 at the base to `height` at the top
+        let origin = self.frame.to_local(&(ray.origin - self.base));
+        let direction = self.frame.to_local(&ray.direction);
+        let mut surface = None;
+
+        let a = direction.x.powi(2) + direction.y.powi(2);
+        let half_b = origin.x * direction.x + origin.y * direction.y;
+        let c = origin.x.powi(2) + origin.y.powi(2) - self.radius.powi(2);
+        let along = Interval {min: 0.0, max: self.height};
+        let on_side = |t: f32| along.contains(origin.z + t * direction.z);
+        if let Some(t) = nearest_root(a, half_b, c, ray_t, on_side) {
+            ray_t.max = t;
+            surface = Some(Surface::Side);
+        }
+
+        if self.capped && direction.z.abs() > 1e-8 {
+            for (height, cap) in [(0.0, Surface::Bottom), (self.height, Surface::Top)] {
+                let t = (height - origin.z) / direction.z;
+                let radial = (origin.x + t * direction.x).powi(2)
+                    + (origin.y + t * direction.y).powi(2);
+                if ray_t.surrounds(t) && radial <= self.radius.powi(2) {
+                    ray_t.max = t;
+                    surface = Some(cap);
+                }
+            }
+        }
+
+        let Some(surface) = surface else {return false;};
+        let t = ray_t.max;
+        let local = origin + t * direction;
+        let distance = (local.x.powi(2) + local.y.powi(2)).sqrt();
+        let (outward_normal, v) = match surface {
+            Surface::Side => {
+                let normal = Vec3 {x: local.x / distance, y: local.y / distance, z: 0.0};
+                (normal, local.z / self.height)
+            },
+            Surface::Bottom => (Vec3 {x: 0.0, y: 0.0, z: -1.0}, distance / self.radius),
+            Surface::Top => (Vec3 {x: 0.0, y: 0.0, z: 1.0}, distance / self.radius),
+        };
+
+        hit_record.t = t;
+        hit_record.point = ray.at(t);
+        hit_record.set_face_normal(ray, &self.frame.to_world(&outward_normal));
+        (hit_record.u, hit_record.v) = ((local.y.atan2(local.x) + PI) / (2.0 * PI), v);
+        hit_record.material = self.material;
+        true
+    }
+
+    fn bounding_box(&self) -> Aabb {
+        let extent = self.frame.disk_extent(self.radius);
+        let base = Aabb::from_points(&(self.base - extent), &(self.base + extent));
+        let top = Aabb::from_points(&(self.top - extent), &(self.top + extent));
+        Aabb::enclosing(&base, &top).pad(1e-4)
+    }
+}
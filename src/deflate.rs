@@ -0,0 +1,183 @@
+//! A small zlib/DEFLATE encoder: LZ77 matching over a 32 KiB window, coded with the fixed
+//! Huffman tables from RFC 1951, so no code tables need to be stored.
+
+const WINDOW_SIZE: usize = 1 << 15;
+const MIN_MATCH: usize = 3;
+const MAX_MATCH: usize = 258;
+const HASH_BITS: u32 = 15;
+const MAX_CHAIN: usize = 64;
+
+/// Base lengths for length codes 257..=285, followed by their extra bit counts.
+const LENGTH_BASES: [u16; 29] = [
+    3, 4, 5, 6, 7, 8, 9, 10, 11, 13, 15, 17, 19, 23, 27, 31, 
+    35, 43, 51, 59, 67, 83, 99, 115, 131, 163, 195, 227, 258,
+];
+const LENGTH_EXTRA_BITS: [u8; 29] = [
+    0, 0, 0, 0, 0, 0, 0, 0, 1, 1, 1, 1, 2, 2, 2, 2, 3, 3, 3, 3, 4, 4, 4, 4, 5, 5, 5, 5, 0,
+];
+/// Base distances for distance codes 0..=29, followed by their extra bit counts.
+const DISTANCE_BASES: [u16; 30] = [
+    1, 2, 3, 4, 5, 7, 9, 13, 17, 25, 33, 49, 65, 97, 129, 193, 
+    257, 385, 513, 769, 1025, 1537, 2049, 3073, 4097, 6145, 8193, 12289, 16385, 24577,
+];
+const DISTANCE_EXTRA_BITS: [u8; 30] = [
+    0, 0, 0, 0, 1, 1, 2, 2, 3, 3, 4, 4, 5, 5, 6, 6, 
+    7, 7, 8, 8, 9, 9, 10, 10, 11, 11, 12, 12, 13, 13,
+];
+
+/// Wraps `data` in a zlib stream holding one fixed-Huffman DEFLATE block.
+pub fn zlib_compress(data: &[u8]) -> Vec<u8> {
+    // CMF: deflate with a 32 KiB window; FLG: default level, with check bits making it % 31 == 0
+    let mut writer = BitWriter {bytes: vec![0x78, 0x9C], buffer: 0, bit_count: 0};
+
+    // BFINAL = 1, BTYPE = 01 (fixed Huffman codes)
+    writer.write_bits(1, 1);
+    writer.write_bits(1, 2);
+
+    let mut head = vec![usize::MAX; 1 << HASH_BITS];
+    let mut previous = vec![usize::MAX; WINDOW_SIZE];
+    let mut position = 0;
+
+    while position < data.len() {
+        let (length, distance) = longest_match(data, position, &head, &previous);
+        let step = if length >= MIN_MATCH {
+            write_match(&mut writer, length, distance);
+            length
+        } else {
+            write_literal_or_length(&mut writer, data[position] as u16);
+            1
+        };
+
+        for inserted in position..(position + step).min(data.len().saturating_sub(MIN_MATCH - 1)) {
+            let hash = hash(&data[inserted..]);
+            previous[inserted % WINDOW_SIZE] = head[hash];
+            head[hash] = inserted;
+        }
+        position += step;
+    }
+
+    // End of block
+    write_literal_or_length(&mut writer, 256);
+    writer.flush();
+
+    let mut bytes = writer.bytes;
+    bytes.extend_from_slice(&adler32(data).to_be_bytes());
+    bytes
+}
+
+pub fn adler32(data: &[u8]) -> u32 {
+    const MODULUS: u32 = 65521;
+    let (mut a, mut b) = (1u32, 0u32);
+    // 5552 is the most bytes that can be summed before b could overflow
+    for chunk in data.chunks(5552) {
+        for byte in chunk {
+            a += *byte as u32;
+            b += a;
+        }
+        a %= MODULUS;
+        b %= MODULUS;
+    }
+    b << 16 | a
+}
+
+fn hash(bytes: &[u8]) -> usize {
+    let value = (bytes[0] as u32) << 16 | (bytes[1] as u32) << 8 | bytes[2] as u32;
+    (value.wrapping_mul(0x9E37_79B1) >> (32 - HASH_BITS)) as usize
+}
+
+/// Follows the hash chain for `position` and returns the longest earlier match, if any.
+fn longest_match(
+    data: &[u8], 
+    position: usize, 
+    head: &[usize], 
+    previous: &[usize]
+) -> (usize, usize) {
+    if position + MIN_MATCH > data.len() {return (0, 0);}
+
+    let max_length = MAX_MATCH.min(data.len() - position);
+    let mut best = (0, 0);
+    let mut candidate = head[hash(&data[position..])];
+
+    for _ in 0..MAX_CHAIN {
+        if candidate == usize::MAX || position - candidate > WINDOW_SIZE {break;}
+
+        let length = data[candidate..]
+            .iter()
+            .zip(&data[position..position + max_length])
+            .take_while(|(a, b)| a == b)
+            .count();
+        if length > best.0 {
+            best = (length, position - candidate);
+            if length == max_length {break;}
+        }
+
+        let next = previous[candidate % WINDOW_SIZE];
+        // Older entries in the ring buffer may have been overwritten by newer positions
+        if next == usize::MAX || next >= candidate {break;}
+        candidate = next;
+    }
+    best
+}
+
+fn write_match(writer: &mut BitWriter, length: usize, distance: usize) {
+    let length_code = LENGTH_BASES.iter().rposition(|base| *base as usize <= length).unwrap_or(0);
+    write_literal_or_length(writer, 257 + length_code as u16);
+    writer.write_bits(
+        (length - LENGTH_BASES[length_code] as usize) as u32, 
+        LENGTH_EXTRA_BITS[length_code] as u32,
+    );
+
+    let distance_code = DISTANCE_BASES
+        .iter()
+        .rposition(|base| *base as usize <= distance)
+        .unwrap_or(0);
+    // Fixed distance codes are all 5 bits long
+    writer.write_huffman(distance_code as u32, 5);
+    writer.write_bits(
+        (distance - DISTANCE_BASES[distance_code] as usize) as u32, 
+        DISTANCE_EXTRA_BITS[distance_code] as u32,
+    );
+}
+
+/// Writes a symbol from the fixed literal/length alphabet (RFC 1951 section 3.2.6).
+fn write_literal_or_length(writer: &mut BitWriter, symbol: u16) {
+    let symbol = symbol as u32;
+    match symbol {
+        0..=143 => writer.write_huffman(0x30 + symbol, 8),
+        144..=255 => writer.write_huffman(0x190 + symbol - 144, 9),
+        256..=279 => writer.write_huffman(symbol - 256, 7),
+        _ => writer.write_huffman(0xC0 + symbol - 280, 8),
+    }
+}
+
+/// Packs bits least significant first, as DEFLATE requires.
+struct BitWriter {
+    bytes: Vec<u8>,
+    buffer: u64,
+    bit_count: u32,
+}
+
+impl BitWriter {
+    fn write_bits(&mut self, value: u32, count: u32) {
+        self.buffer |= (value as u64) << self.bit_count;
+        self.bit_count += count;
+        while self.bit_count >= 8 {
+            self.bytes.push(self.buffer as u8);
+            self.buffer >>= 8;
+            self.bit_count -= 8;
+        }
+    }
+
+    /// Huffman codes are defined most significant bit first, so they go in reversed.
+    fn write_huffman(&mut self, code: u32, length: u32) {
+        self.write_bits(code.reverse_bits() >> (32 - length), length);
+    }
+
+    fn flush(&mut self) {
+        if self.bit_count > 0 {
+            self.bytes.push(self.buffer as u8);
+            self.buffer = 0;
+            self.bit_count = 0;
+        }
+    }
+}
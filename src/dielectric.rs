@@ -1,4 +1,11 @@
-use crate::{material::Material, color::Color, vec::{refract, reflect}, ray::Ray, util::random_double};
+use crate::{
+    color::Color, 
+    hit_record::HitRecord, 
+    material::{BsdfSample, Material}, 
+    ray::Ray, 
+    sampler::Sampler, 
+    vec::{refract, reflect},
+};
 
 #[derive(Clone)]
 pub struct Dielectric {
@@ -13,14 +20,14 @@ fn reflectance(cosine: f32, ref_idx: f32) -> f32 {
 }
 
 impl Material for Dielectric {
+    /// Either reflects or refracts, picked by the Fresnel reflectance, so both are delta samples
+    /// that pass on all of the light.
     fn scatter(
-            &self,
-            in_ray: &crate::ray::Ray,
-            hit_record: &crate::hit_record::HitRecord,
-            attenuation: &mut crate::vec::Vec3,
-            scattered: &mut crate::ray::Ray
-        ) -> bool {
-        *attenuation = Color {x: 1.0, y: 1.0, z: 1.0};
+        &self,
+        in_ray: &Ray,
+        hit_record: &HitRecord,
+        sampler: &mut Sampler,
+    ) -> Option<BsdfSample> {
         let refraction_ratio = if hit_record.front_face {1.0 / self.ir} else {self.ir};
         let unit_direction = in_ray.direction.unit_vector();
         let cos_theta = -unit_direction.dot(&hit_record.normal).min(1.0);
@@ -29,12 +36,16 @@ impl Material for Dielectric {
         let cannot_refract = refraction_ratio * sin_theta > 1.0;
         let direction = if cannot_refract 
                 || reflectance(cos_theta, refraction_ratio) 
-                > random_double(None, None) {
+                > sampler.next_f32() {
             reflect(&unit_direction, &hit_record.normal)
         } else {
             refract(&unit_direction, &hit_record.normal, refraction_ratio)
         };
-        *scattered = Ray {origin: hit_record.point, direction};
-        true
+        Some(BsdfSample {
+            direction: direction.unit_vector(),
+            weight: Color {x: 1.0, y: 1.0, z: 1.0},
+            pdf: 1.0,
+            is_specular: true,
+        })
     }
-}
\ No newline at end of file
+}
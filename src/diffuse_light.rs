@@ -0,0 +1,41 @@
+use std::sync::Arc;
+
+use crate::{
+    color::Color, 
+    hit_record::HitRecord, 
+    material::{BsdfSample, Material}, 
+    ray::Ray, 
+    sampler::Sampler, 
+    solid_color::SolidColor, 
+    texture::Texture,
+};
+
+/// Emits light from a texture and absorbs every ray that hits it.
+#[derive(Clone)]
+pub struct DiffuseLight {
+    pub emit: Arc<dyn Texture>,
+}
+
+impl DiffuseLight {
+    pub fn new(emit: Color) -> Self {
+        Self::from_texture(Arc::<_>::new(SolidColor {albedo: emit}))
+    }
+
+    pub fn from_texture(emit: Arc<dyn Texture>) -> Self {
+        Self {emit}
+    }
+}
+
+impl Material for DiffuseLight {
+    fn scatter(&self, _: &Ray, _: &HitRecord, _: &mut Sampler) -> Option<BsdfSample> {
+        None
+    }
+
+    fn emitted(&self, _: &Ray, hit_record: &HitRecord) -> Color {
+        self.emit.value(hit_record.u, hit_record.v, &hit_record.point)
+    }
+
+    fn is_emissive(&self) -> bool {
+        true
+    }
+}
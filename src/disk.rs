@@ -0,0 +1,81 @@
+use std::f32::consts::PI;
+
+use crate::{
+    aabb::Aabb, 
+    hit_record::HitRecord, 
+    hittable::Hittable, 
+    interval::Interval, 
+    light::{Light, LightSample, area_to_solid_angle, same_distance}, 
+    material_arena::MaterialId, 
+    ray::Ray, 
+    vec::{Point3, Vec3, orthonormal_basis},
+};
+
+/// A flat circle. Texture coordinates are polar: `u` is the angle around the normal and `v` is
+/// the distance from the center, both scaled to [0,1].
+#[derive(Clone)]
+pub struct Disk {
+    pub center: Point3,
+    pub normal: Vec3,
+    pub radius: f32,
+    pub material: MaterialId,
+}
+
+impl Hittable for Disk {
+    fn hit(&self, ray: &Ray, ray_t: Interval, hit_record: &mut HitRecord) -> bool {
+        let normal = self.normal.unit_vector();
+        let denominator = normal.dot(&ray.direction);
+        if denominator.abs() < 1e-8 {return false;}
+
+        let t = (self.center - ray.origin).dot(&normal) / denominator;
+        if !ray_t.surrounds(t) {return false;}
+
+        let intersection = ray.at(t);
+        let offset = intersection - self.center;
+        if offset.length_squared() > self.radius.powi(2) {return false;}
+
+        let (tangent, bitangent) = orthonormal_basis(&normal);
+        let phi = offset.dot(&bitangent).atan2(offset.dot(&tangent)) + PI;
+        hit_record.t = t;
+        hit_record.point = intersection;
+        hit_record.set_face_normal(ray, &normal);
+        (hit_record.u, hit_record.v) = (phi / (2.0 * PI), offset.length() / self.radius);
+        hit_record.material = self.material;
+        true
+    }
+
+    fn bounding_box(&self) -> Aabb {
+        // Along each axis the rim reaches radius * sin of the angle between the axis and normal
+        let normal = self.normal.unit_vector();
+        let extent = |component: f32| self.radius * (1.0 - component.powi(2)).max(0.0).sqrt();
+        let half_size = Vec3 {x: extent(normal.x), y: extent(normal.y), z: extent(normal.z)};
+        Aabb::from_points(&(self.center - half_size), &(self.center + half_size)).pad(1e-4)
+    }
+}
+
+impl Light for Disk {
+    fn sample(&self, origin: &Point3, u: f32, v: f32) -> Option<LightSample> {
+        let normal = self.normal.unit_vector();
+        let (tangent, bitangent) = orthonormal_basis(&normal);
+        let (radius, phi) = (self.radius * u.sqrt(), 2.0 * PI * v);
+        let point = self.center + radius * (phi.cos() * tangent + phi.sin() * bitangent);
+
+        let to_light = point - *origin;
+        let area = PI * self.radius.powi(2);
+        let pdf = area_to_solid_angle(1.0 / area, &to_light, &normal);
+        if pdf <= 0.0 {return None;}
+        let distance = to_light.length();
+        Some(LightSample {direction: to_light / distance, distance, pdf})
+    }
+
+    fn pdf(&self, ray: &Ray, t: f32) -> f32 {
+        let mut hit_record = HitRecord::default();
+        let ray_t = Interval {min: 0.001, max: f32::INFINITY};
+        if !self.hit(ray, ray_t, &mut hit_record) || !same_distance(hit_record.t, t) {
+            return 0.0;
+        }
+        let area = PI * self.radius.powi(2);
+        let to_light = hit_record.point - ray.origin;
+        area_to_solid_angle(1.0 / area, &to_light, &self.normal.unit_vector())
+    }
+}
@@ -0,0 +1,84 @@
+//! Piecewise-constant distributions for importance sampling tabulated functions.
+
+/// A distribution over [0, 1) proportional to a step function with equally wide steps,
+/// sampled by inverting its CDF.
+pub struct Distribution1D {
+    pub function: Vec<f32>,
+    /// The integral of the function over [0, 1).
+    pub integral: f32,
+    cdf: Vec<f32>,
+}
+
+impl Distribution1D {
+    /// A function that is zero everywhere is sampled uniformly instead.
+    pub fn new(function: Vec<f32>) -> Self {
+        let count = function.len();
+        let mut cdf = vec![0.0; count + 1];
+        for index in 0..count {
+            cdf[index + 1] = cdf[index] + function[index].abs() / count as f32;
+        }
+
+        let integral = cdf[count];
+        for (index, value) in cdf.iter_mut().enumerate().skip(1) {
+            *value = if integral > 0.0 {*value / integral} else {index as f32 / count as f32};
+        }
+        Self {function, integral, cdf}
+    }
+
+    /// Maps a uniform number in [0, 1) to a sample, returning it with its density and the index
+    /// of the step it fell in.
+    pub fn sample(&self, u: f32) -> (f32, f32, usize) {
+        let count = self.function.len();
+        let index = self.cdf
+            .partition_point(|value| *value <= u)
+            .saturating_sub(1)
+            .min(count - 1);
+
+        // Place the sample within its step by how far u got through that step of the CDF
+        let step = self.cdf[index + 1] - self.cdf[index];
+        let offset = if step > 0.0 {(u - self.cdf[index]) / step} else {0.0};
+        let x = ((index as f32 + offset) / count as f32).min(1.0 - f32::EPSILON);
+        (x, self.step_pdf(index), index)
+    }
+
+    pub fn pdf(&self, x: f32) -> f32 {
+        let count = self.function.len();
+        self.step_pdf(((x * count as f32) as usize).min(count - 1))
+    }
+
+    fn step_pdf(&self, index: usize) -> f32 {
+        if self.integral > 0.0 {self.function[index].abs() / self.integral} else {1.0}
+    }
+}
+
+/// A distribution over [0, 1)^2 proportional to a function tabulated on a grid, sampled by
+/// picking a row from the marginal distribution and then a column within that row.
+pub struct Distribution2D {
+    rows: Vec<Distribution1D>,
+    marginal: Distribution1D,
+}
+
+impl Distribution2D {
+    /// Takes `width` * `height` values in row-major order, with rows along v.
+    pub fn new(function: &[f32], width: usize, height: usize) -> Self {
+        let rows: Vec<_> = function
+            .chunks_exact(width)
+            .take(height)
+            .map(|row| Distribution1D::new(row.to_vec()))
+            .collect();
+        let marginal = Distribution1D::new(rows.iter().map(|row| row.integral).collect());
+        Self {rows, marginal}
+    }
+
+    /// Maps two uniform numbers to a point `(u, v)` and its density.
+    pub fn sample(&self, u: f32, v: f32) -> ((f32, f32), f32) {
+        let (sampled_v, v_pdf, row) = self.marginal.sample(v);
+        let (sampled_u, u_pdf, _) = self.rows[row].sample(u);
+        ((sampled_u, sampled_v), u_pdf * v_pdf)
+    }
+
+    pub fn pdf(&self, u: f32, v: f32) -> f32 {
+        let row = ((v * self.rows.len() as f32) as usize).min(self.rows.len() - 1);
+        self.rows[row].pdf(u) * self.marginal.pdf(v)
+    }
+}
@@ -0,0 +1,19 @@
+use std::f32::consts::PI;
+
+use crate::{color::Color, vec::{Vec3, uniform_sphere_direction}};
+
+/// Light arriving from infinitely far away, seen by rays that escape the scene.
+pub trait Environment: Send + Sync {
+    fn radiance(&self, direction: &Vec3) -> Color;
+
+    /// Maps two uniform numbers in [0, 1) to a unit direction toward the environment, returning
+    /// it with its density per solid angle. Uniform over the sphere unless overridden.
+    fn sample(&self, u: f32, v: f32) -> (Vec3, f32) {
+        (uniform_sphere_direction(u, v), 1.0 / (4.0 * PI))
+    }
+
+    /// The density `sample` returns for `direction`.
+    fn pdf(&self, _direction: &Vec3) -> f32 {
+        1.0 / (4.0 * PI)
+    }
+}
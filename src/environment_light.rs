@@ -0,0 +1,26 @@
+use std::sync::Arc;
+
+use crate::{
+    environment::Environment, 
+    light::{Light, LightSample}, 
+    ray::Ray, 
+    vec::Point3,
+};
+
+/// Samples directions toward the environment by its own importance sampling.
+pub struct EnvironmentLight {
+    pub environment: Arc<dyn Environment>,
+}
+
+impl Light for EnvironmentLight {
+    fn sample(&self, _: &Point3, u: f32, v: f32) -> Option<LightSample> {
+        let (direction, pdf) = self.environment.sample(u, v);
+        if pdf <= 0.0 {return None;}
+        Some(LightSample {direction, distance: f32::INFINITY, pdf})
+    }
+
+    fn pdf(&self, ray: &Ray, t: f32) -> f32 {
+        if t.is_finite() {return 0.0;}
+        self.environment.pdf(&ray.direction)
+    }
+}
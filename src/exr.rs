@@ -0,0 +1,145 @@
+//! An uncompressed scanline OpenEXR writer.
+
+use crate::film::Film;
+
+const MAGIC: [u8; 4] = [0x76, 0x2F, 0x31, 0x01];
+
+/// The storage type of every channel in the file.
+#[derive(Clone, Copy, PartialEq, Eq, Debug, Default)]
+pub enum ExrPixelType {
+    /// 16-bit floats, half the size and enough precision for most grading.
+    Half,
+    #[default]
+    Float,
+}
+
+impl ExrPixelType {
+    fn id(&self) -> i32 {
+        match self {
+            Self::Half => 1,
+            Self::Float => 2,
+        }
+    }
+
+    fn size(&self) -> usize {
+        match self {
+            Self::Half => 2,
+            Self::Float => 4,
+        }
+    }
+}
+
+/// Writes the film as a single-part scanline OpenEXR image with B, G and R channels holding
+/// the linear radiance, unclamped.
+pub fn write_exr(
+    stream: &mut impl std::io::Write, 
+    film: &Film, 
+    pixel_type: ExrPixelType
+) -> std::io::Result<()> {
+    let mut header = Vec::new();
+    header.extend_from_slice(&MAGIC);
+    // Version 2, single-part scanline
+    header.extend_from_slice(&2_i32.to_le_bytes());
+
+    // Channels have to be listed in alphabetical order, and each scanline stores them that way
+    let mut channels = Vec::new();
+    for name in ["B", "G", "R"] {
+        channels.extend_from_slice(name.as_bytes());
+        channels.push(0);
+        channels.extend_from_slice(&pixel_type.id().to_le_bytes());
+        // pLinear and three reserved bytes, then the x and y sampling rates
+        channels.extend_from_slice(&[0; 4]);
+        channels.extend_from_slice(&1_i32.to_le_bytes());
+        channels.extend_from_slice(&1_i32.to_le_bytes());
+    }
+    channels.push(0);
+    write_attribute(&mut header, "channels", "chlist", &channels);
+
+    // No compression
+    write_attribute(&mut header, "compression", "compression", &[0]);
+    let mut window = Vec::new();
+    for bound in [0, 0, film.width as i32 - 1, film.height as i32 - 1] {
+        window.extend_from_slice(&bound.to_le_bytes());
+    }
+    write_attribute(&mut header, "dataWindow", "box2i", &window);
+    write_attribute(&mut header, "displayWindow", "box2i", &window);
+    // Increasing y
+    write_attribute(&mut header, "lineOrder", "lineOrder", &[0]);
+    write_attribute(&mut header, "pixelAspectRatio", "float", &1.0_f32.to_le_bytes());
+    write_attribute(&mut header, "screenWindowCenter", "v2f", &[0; 8]);
+    write_attribute(&mut header, "screenWindowWidth", "float", &1.0_f32.to_le_bytes());
+    header.push(0);
+
+    // Each scanline is its own chunk: y coordinate, data size, then every channel in turn
+    let data_size = film.width * 3 * pixel_type.size();
+    let chunk_size = 8 + data_size;
+    let table_end = header.len() + film.height * 8;
+    for y in 0..film.height {
+        header.extend_from_slice(&((table_end + y * chunk_size) as u64).to_le_bytes());
+    }
+    stream.write_all(&header)?;
+
+    let mut chunk = Vec::with_capacity(chunk_size);
+    for y in 0..film.height {
+        chunk.clear();
+        chunk.extend_from_slice(&(y as i32).to_le_bytes());
+        chunk.extend_from_slice(&(data_size as i32).to_le_bytes());
+        for channel in [2, 1, 0] {
+            for x in 0..film.width {
+                let pixel = film.pixel(x, y);
+                let value = [pixel.x, pixel.y, pixel.z][channel];
+                match pixel_type {
+                    ExrPixelType::Half => {
+                        chunk.extend_from_slice(&f32_to_half(value).to_le_bytes())
+                    },
+                    ExrPixelType::Float => chunk.extend_from_slice(&value.to_le_bytes()),
+                }
+            }
+        }
+        stream.write_all(&chunk)?;
+    }
+    Ok(())
+}
+
+fn write_attribute(header: &mut Vec<u8>, name: &str, kind: &str, value: &[u8]) {
+    header.extend_from_slice(name.as_bytes());
+    header.push(0);
+    header.extend_from_slice(kind.as_bytes());
+    header.push(0);
+    header.extend_from_slice(&(value.len() as i32).to_le_bytes());
+    header.extend_from_slice(value);
+}
+
+/// Converts to an IEEE 754 half, rounding to nearest even. Values too large for a half become
+/// infinity and values too small flush to zero through the subnormals.
+fn f32_to_half(value: f32) -> u16 {
+    let bits = value.to_bits();
+    let sign = ((bits >> 16) & 0x8000) as u16;
+    let exponent = ((bits >> 23) & 0xFF) as i32;
+    let mantissa = bits & 0x7F_FFFF;
+
+    if exponent == 0xFF {
+        let nan = if mantissa != 0 {0x200} else {0};
+        return sign | 0x7C00 | nan;
+    }
+
+    let exponent = exponent - 127 + 15;
+    if exponent >= 0x1F {return sign | 0x7C00;}
+    if exponent <= 0 {
+        if exponent < -10 {return sign;}
+        let mantissa = mantissa | 0x80_0000;
+        let shift = (14 - exponent) as u32;
+        return sign | round_shifted(mantissa, shift) as u16;
+    }
+
+    // A mantissa that rounds up carries into the exponent, which is still correct
+    sign | round_shifted((exponent as u32) << 23 | mantissa, 13) as u16
+}
+
+fn round_shifted(value: u32, shift: u32) -> u32 {
+    let truncated = value >> shift;
+    let remainder = value & ((1 << shift) - 1);
+    let midpoint = 1 << (shift - 1);
+    let round_up = remainder > midpoint || (remainder == midpoint && truncated & 1 == 1);
+    truncated + round_up as u32
+}
@@ -0,0 +1,50 @@
+use crate::color::Color;
+
+/// Accumulates linear radiance per pixel, stored in row-major order from the top-left corner.
+#[derive(Clone)]
+pub struct Film {
+    pub width: usize,
+    pub height: usize,
+    pub radiance: Vec<Color>,
+    pub sample_counts: Vec<u32>,
+}
+
+impl Film {
+    pub fn new(width: usize, height: usize) -> Self {
+        Self {
+            width,
+            height,
+            radiance: vec![Color::default(); width * height],
+            sample_counts: vec![0; width * height],
+        }
+    }
+
+    pub fn index(&self, x: usize, y: usize) -> usize {
+        y * self.width + x
+    }
+
+    pub fn add_sample(&mut self, x: usize, y: usize, radiance: Color) {
+        self.add_samples(x, y, radiance, 1);
+    }
+
+    /// Adds `sample_count` samples whose radiance sums to `radiance_sum`.
+    pub fn add_samples(&mut self, x: usize, y: usize, radiance_sum: Color, sample_count: u32) {
+        let index = self.index(x, y);
+        self.radiance[index] += radiance_sum;
+        self.sample_counts[index] += sample_count;
+    }
+
+    /// Returns the mean radiance of the pixel, or black if it has no samples.
+    pub fn pixel(&self, x: usize, y: usize) -> Color {
+        let index = self.index(x, y);
+        match self.sample_counts[index] {
+            0 => Color::default(),
+            count => self.radiance[index] / count as f32,
+        }
+    }
+
+    /// Iterates over the mean radiance of every pixel in row-major order.
+    pub fn pixels(&self) -> impl Iterator<Item = Color> + '_ {
+        (0..self.height).flat_map(move |y| (0..self.width).map(move |x| self.pixel(x, y)))
+    }
+}
@@ -0,0 +1,33 @@
+use crate::vec::{Vec3, orthonormal_basis};
+
+/// A right-handed orthonormal basis around `normal`, for shapes that are simplest to intersect
+/// in coordinates where their axis is Z.
+#[derive(Clone, Copy)]
+pub struct Frame {
+    pub tangent: Vec3,
+    pub bitangent: Vec3,
+    pub normal: Vec3,
+}
+
+impl Frame {
+    /// `normal` need not have unit length.
+    pub fn new(normal: &Vec3) -> Self {
+        let normal = normal.unit_vector();
+        let (tangent, bitangent) = orthonormal_basis(&normal);
+        Self {tangent, bitangent, normal}
+    }
+
+    pub fn to_local(&self, vec: &Vec3) -> Vec3 {
+        Vec3 {x: vec.dot(&self.tangent), y: vec.dot(&self.bitangent), z: vec.dot(&self.normal)}
+    }
+
+    pub fn to_world(&self, vec: &Vec3) -> Vec3 {
+        vec.x * self.tangent + vec.y * self.bitangent + vec.z * self.normal
+    }
+
+    /// Half the size along each world axis of a disk of `radius` lying across the normal.
+    pub fn disk_extent(&self, radius: f32) -> Vec3 {
+        let extent = |component: f32| radius * (1.0 - component.powi(2)).max(0.0).sqrt();
+        Vec3 {x: extent(self.normal.x), y: extent(self.normal.y), z: extent(self.normal.z)}
+    }
+}
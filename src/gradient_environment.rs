@@ -0,0 +1,21 @@
+use crate::{color::Color, environment::Environment, vec::Vec3};
+
+/// A vertical blend from `bottom` straight down to `top` straight up.
+pub struct GradientEnvironment {
+    pub bottom: Color,
+    pub top: Color,
+}
+
+impl Default for GradientEnvironment {
+    /// White at the bottom to light blue overhead.
+    fn default() -> Self {
+        Self {bottom: Color {x: 1.0, y: 1.0, z: 1.0}, top: Color {x: 0.5, y: 0.7, z: 1.0}}
+    }
+}
+
+impl Environment for GradientEnvironment {
+    fn radiance(&self, direction: &Vec3) -> Color {
+        let alpha = 0.5 * (direction.unit_vector().y + 1.0);
+        (1.0 - alpha) * self.bottom + alpha * self.top
+    }
+}
@@ -0,0 +1,254 @@
+//! Radiance RGBE (.hdr) images: one shared exponent byte per pixel, with run-length encoded
+//! scanlines.
+
+use std::{io::{BufRead, Error, ErrorKind}, path::Path};
+
+use crate::{color::Color, film::Film};
+
+/// Scanlines outside this range of widths can't be run-length encoded.
+const RLE_WIDTHS: std::ops::RangeInclusive<usize> = 8..=0x7FFF;
+/// Shorter runs cost more as a run than as literals.
+const MIN_RUN: usize = 4;
+/// An old-style repeat of 255 pixels in 4 bytes is the densest encoding short of chaining
+/// repeats. Rasters claiming more pixels than this per byte are rejected as truncated.
+const MAX_PIXELS_PER_BYTE: usize = 64;
+
+/// Writes the film's linear radiance as a run-length encoded Radiance HDR image. Negative
+/// components can't be stored and become zero.
+pub fn write_hdr(stream: &mut impl std::io::Write, film: &Film) -> std::io::Result<()> {
+    write!(
+        stream,
+        "#?RADIANCE\nFORMAT=32-bit_rle_rgbe\n\n-Y {} +X {}\n",
+        film.height,
+        film.width
+    )?;
+
+    let mut pixels = Vec::with_capacity(film.width);
+    let mut scanline = Vec::with_capacity(film.width * 4);
+    for y in 0..film.height {
+        pixels.clear();
+        pixels.extend((0..film.width).map(|x| color_to_rgbe(film.pixel(x, y))));
+
+        scanline.clear();
+        if RLE_WIDTHS.contains(&film.width) {
+            scanline.extend_from_slice(&[2, 2, (film.width >> 8) as u8, film.width as u8]);
+            for component in 0..4 {
+                let bytes: Vec<u8> = pixels.iter().map(|rgbe| rgbe[component]).collect();
+                encode_runs(&mut scanline, &bytes);
+            }
+        } else {
+            scanline.extend(pixels.iter().flatten());
+        }
+        stream.write_all(&scanline)?;
+    }
+    Ok(())
+}
+
+pub fn load_hdr(path: impl AsRef<Path>) -> std::io::Result<Film> {
+    read_hdr(std::io::BufReader::new(std::fs::File::open(path)?))
+}
+
+/// Reads a Radiance HDR image into a film with one sample per pixel. Flat, old-style and
+/// new-style run-length encoded scanlines are all accepted.
+pub fn read_hdr(mut reader: impl BufRead) -> std::io::Result<Film> {
+    let mut line = String::new();
+    reader.read_line(&mut line)?;
+    if !line.starts_with("#?") {
+        return Err(invalid_data("Radiance HDR images start with '#?'"));
+    }
+
+    // Header variables run up to a blank line
+    let mut exposure = 1.0;
+    loop {
+        line.clear();
+        if reader.read_line(&mut line)? == 0 {
+            return Err(invalid_data("HDR header ended early"));
+        }
+        let variable = line.trim();
+        if variable.is_empty() {break;}
+        if let Some(format) = variable.strip_prefix("FORMAT=") {
+            if format != "32-bit_rle_rgbe" {
+                return Err(invalid_data(&format!("Unsupported HDR format '{}'", format)));
+            }
+        } else if let Some(value) = variable.strip_prefix("EXPOSURE=") {
+            exposure *= value
+                .trim()
+                .parse::<f32>()
+                .map_err(|_| invalid_data("HDR exposure is not a number"))?;
+        }
+    }
+
+    line.clear();
+    reader.read_line(&mut line)?;
+    let (flip_y, height, width) = match line.split_whitespace().collect::<Vec<_>>()[..] {
+        [y_axis @ ("-Y" | "+Y"), height, "+X", width] => (y_axis == "+Y", height, width),
+        _ => return Err(invalid_data("Only -Y/+Y, +X ordered HDR images are supported")),
+    };
+    let parse = |value: &str| {
+        value.parse::<usize>().map_err(|_| invalid_data("HDR resolution is not a number"))
+    };
+    let (width, height) = (parse(width)?, parse(height)?);
+
+    let mut bytes = Vec::new();
+    reader.read_to_end(&mut bytes)?;
+    let raster_size = width
+        .checked_mul(height)
+        .and_then(|pixel_count| pixel_count.checked_mul(4))
+        .ok_or_else(|| invalid_data("HDR image is too large"))?;
+    // Check the resolution against the raster before allocating for it. Every scanline takes
+    // at least 4 bytes, and no accepted encoding is denser than MAX_PIXELS_PER_BYTE.
+    let densest = bytes.len().saturating_mul(MAX_PIXELS_PER_BYTE);
+    if bytes.len() / 4 < height || densest < raster_size / 4 {
+        return Err(invalid_data("HDR raster is too short for its resolution"));
+    }
+    let mut data = HdrData {bytes: &bytes, position: 0};
+
+    let mut film = Film::new(width, height);
+    let mut scanline = vec![[0; 4]; width];
+    for row in 0..height {
+        data.read_scanline(&mut scanline)?;
+        let y = if flip_y {height - 1 - row} else {row};
+        for (x, rgbe) in scanline.iter().enumerate() {
+            film.add_sample(x, y, rgbe_to_color(*rgbe) / exposure);
+        }
+    }
+    Ok(film)
+}
+
+/// Converts to RGBE, where each mantissa byte is scaled by two to the power of the exponent
+/// byte minus 136.
+fn color_to_rgbe(color: Color) -> [u8; 4] {
+    let [red, green, blue] = [color.x, color.y, color.z].map(|channel| channel.max(0.0));
+    let largest = red.max(green).max(blue);
+    if largest < 1e-32 {return [0; 4];}
+
+    // Split the largest component into a mantissa in [0.5, 1) and a power of two
+    let exponent = ((largest.to_bits() >> 23) & 0xFF) as i32 - 126;
+    let scale = 256.0 / 2.0_f32.powi(exponent);
+    [
+        (red * scale) as u8,
+        (green * scale) as u8,
+        (blue * scale) as u8,
+        (exponent + 128) as u8,
+    ]
+}
+
+fn rgbe_to_color(rgbe: [u8; 4]) -> Color {
+    if rgbe[3] == 0 {return Color::default();}
+    // Sample the middle of each mantissa step
+    let scale = 2.0_f32.powi(rgbe[3] as i32 - 136);
+    Color {
+        x: (rgbe[0] as f32 + 0.5) * scale,
+        y: (rgbe[1] as f32 + 0.5) * scale,
+        z: (rgbe[2] as f32 + 0.5) * scale,
+    }
+}
+
+/// Appends one component of a new-style scanline: runs of a repeated byte are stored as
+/// 128 plus their length followed by the byte, anything else as a count followed by literals.
+fn encode_runs(output: &mut Vec<u8>, bytes: &[u8]) {
+    let mut position = 0;
+    while position < bytes.len() {
+        let mut run_start = position;
+        let mut run_length = 0;
+        while run_start < bytes.len() {
+            run_length = bytes[run_start..]
+                .iter()
+                .take(127)
+                .take_while(|byte| **byte == bytes[run_start])
+                .count();
+            if run_length >= MIN_RUN {break;}
+            run_start += run_length;
+        }
+
+        while position < run_start {
+            let literal_count = (run_start - position).min(128);
+            output.push(literal_count as u8);
+            output.extend_from_slice(&bytes[position..position + literal_count]);
+            position += literal_count;
+        }
+        if run_length >= MIN_RUN {
+            output.push(128 + run_length as u8);
+            output.push(bytes[run_start]);
+            position = run_start + run_length;
+        }
+    }
+}
+
+fn invalid_data(message: &str) -> Error {
+    Error::new(ErrorKind::InvalidData, message)
+}
+
+/// The raster after the header, consumed one scanline at a time.
+struct HdrData<'a> {
+    bytes: &'a [u8],
+    position: usize,
+}
+
+impl HdrData<'_> {
+    fn next(&mut self) -> std::io::Result<u8> {
+        let byte = self.bytes.get(self.position).copied();
+        self.position += 1;
+        byte.ok_or_else(|| invalid_data("HDR image ended early"))
+    }
+
+    fn read_scanline(&mut self, scanline: &mut [[u8; 4]]) -> std::io::Result<()> {
+        let width = scanline.len();
+        let start = self.bytes.get(self.position..self.position + 4);
+        let is_new_rle = RLE_WIDTHS.contains(&width)
+            && start.is_some_and(|start| start[0] == 2 && start[1] == 2 && start[2] < 128);
+        if !is_new_rle {return self.read_old_scanline(scanline);}
+
+        self.position += 2;
+        let encoded_width = (self.next()? as usize) << 8 | self.next()? as usize;
+        if encoded_width != width {
+            return Err(invalid_data("HDR scanline width doesn't match the image"));
+        }
+
+        for component in 0..4 {
+            let mut x = 0;
+            while x < width {
+                let count = self.next()? as usize;
+                let (length, is_run) = if count > 128 {(count - 128, true)} else {(count, false)};
+                if length == 0 || x + length > width {
+                    return Err(invalid_data("HDR scanline run is out of bounds"));
+                }
+                if is_run {
+                    let value = self.next()?;
+                    scanline[x..x + length].iter_mut().for_each(|rgbe| rgbe[component] = value);
+                } else {
+                    for rgbe in &mut scanline[x..x + length] {
+                        rgbe[component] = self.next()?;
+                    }
+                }
+                x += length;
+            }
+        }
+        Ok(())
+    }
+
+    /// Reads flat pixels, where a pixel of (1, 1, 1, n) repeats the previous one n times, with
+    /// consecutive repeats counting in higher bytes.
+    fn read_old_scanline(&mut self, scanline: &mut [[u8; 4]]) -> std::io::Result<()> {
+        let mut x = 0;
+        let mut shift = 0;
+        while x < scanline.len() {
+            let rgbe = [self.next()?, self.next()?, self.next()?, self.next()?];
+            if rgbe[..3] == [1, 1, 1] && x > 0 {
+                let count = (rgbe[3] as usize) << shift;
+                if shift > 24 || x + count > scanline.len() {
+                    return Err(invalid_data("HDR scanline repeat is out of bounds"));
+                }
+                let previous = scanline[x - 1];
+                scanline[x..x + count].fill(previous);
+                x += count;
+                shift += 8;
+            } else {
+                scanline[x] = rgbe;
+                x += 1;
+                shift = 0;
+            }
+        }
+        Ok(())
+    }
+}
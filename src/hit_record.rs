@@ -1,30 +1,23 @@
 use crate::{
-    ray::Ray, 
-    material::Material, 
-    lambertian::Lambertian, 
     color::Color, 
+    ray::Ray, 
+    material_arena::MaterialId, 
     vec::{Point3, Vec3}, 
 };
 
-#[derive(Clone)]
+#[derive(Clone, Copy, Default)]
 pub struct HitRecord {
     pub point: Point3,
     pub normal: Vec3,
-    pub material: Box<dyn Material>,
-    pub time: f32,
+    /// Looked up in the scene's `MaterialArena`.
+    pub material: MaterialId,
+    pub t: f32,
+    pub u: f32,
+    pub v: f32,
     pub front_face: bool,
-}
-
-impl Default for HitRecord {
-    fn default() -> Self {
-        Self {
-            point: Point3::default(),
-            normal: Vec3::default(),
-            material: Box::<Lambertian>::new(Lambertian { albedo: Color {x: 0.0, y: 0.0, z: 0.0}}),
-            time: f32::default(),
-            front_face: bool::default(),
-        }
-    }
+    /// Blended from the corners by meshes with vertex colors, for their `VertexColor`
+    /// material. Other shapes leave it as it was.
+    pub vertex_color: Color,
 }
 
 impl HitRecord {
@@ -1,5 +1,7 @@
-use crate::{ray::Ray, hit_record::HitRecord, interval::Interval};
+use crate::{ray::Ray, hit_record::HitRecord, interval::Interval, aabb::Aabb};
 
-pub trait Hittable {
-    fn hit(&mut self, ray: &Ray, ray_time: Interval, hit_record: &mut HitRecord) -> bool;
-}
\ No newline at end of file
+pub trait Hittable: Send + Sync {
+    fn hit(&self, ray: &Ray, ray_t: Interval, hit_record: &mut HitRecord) -> bool;
+
+    fn bounding_box(&self) -> Aabb;
+}
@@ -1,39 +1,74 @@
-use crate::{hittable::Hittable, hit_record::HitRecord, interval::Interval};
+use std::sync::Arc;
 
+use crate::{
+    aabb::Aabb, 
+    bvh_node::BvhNode, 
+    hittable::Hittable, 
+    hit_record::HitRecord, 
+    interval::Interval,
+};
+
+/// Lists with more objects than this are wrapped in a `BvhNode` by `HittableList::build`.
+pub const BVH_THRESHOLD: usize = 4;
+
+/// Objects are held by `Arc`, so one object can be part of several lists and lights at once.
+#[derive(Default)]
 pub struct HittableList {
-    pub objects: std::vec::Vec<Box<dyn Hittable>>,
+    pub objects: std::vec::Vec<Arc<dyn Hittable>>,
 }
 
 impl HittableList {
-    pub fn add(&mut self, object: Box<dyn Hittable>) {
+    pub fn add(&mut self, object: Arc<dyn Hittable>) {
         self.objects.insert(0, object);
     }
+
+    /// Finishes the list, building a `BvhNode` over it once it is large enough that
+    /// traversing a tree beats testing every object. Unbounded objects such as planes stay
+    /// outside the tree.
+    pub fn build(self) -> Arc<dyn Hittable> {
+        if self.objects.len() <= BVH_THRESHOLD {return Arc::<_>::new(self);}
+
+        let (bounded, unbounded): (Vec<_>, Vec<_>) = self.objects
+            .into_iter()
+            .partition(|object| object.bounding_box().is_bounded());
+        let tree = Arc::<_>::new(BvhNode::new(Self {objects: bounded}));
+        if unbounded.is_empty() {return tree;}
+
+        let mut list = Self {objects: unbounded};
+        list.add(tree);
+        Arc::<_>::new(list)
+    }
 }
 
 impl Hittable for HittableList {
     fn hit(
-        &mut self, 
+        &self, 
         ray: &crate::ray::Ray, 
-        ray_time: Interval,
+        ray_t: Interval,
         hit_record: &mut HitRecord
     ) -> bool {
-        let temp_hit_record = HitRecord::default();    
+        let mut temp_hit_record = HitRecord::default();
         let mut hit_anything = false;
-        let mut closest_so_far = ray_time.max;
+        let mut closest_so_far = ray_t.max;
 
-        for object in self.objects.iter_mut() {
-            let mut temp_hit_record_clone = temp_hit_record.clone();
+        for object in self.objects.iter() {
             if object.hit(
                 ray, 
-                Interval {min: ray_time.min, max: closest_so_far}, 
-                &mut temp_hit_record_clone,
+                Interval {min: ray_t.min, max: closest_so_far}, 
+                &mut temp_hit_record,
             ) {
                 hit_anything = true;
-                closest_so_far = temp_hit_record_clone.time;
-                *hit_record = temp_hit_record_clone;
+                closest_so_far = temp_hit_record.t;
+                *hit_record = temp_hit_record;
             }
         }
         
         hit_anything
     }
-}
\ No newline at end of file
+
+    fn bounding_box(&self) -> Aabb {
+        self.objects
+            .iter()
+            .fold(Aabb::default(), |bbox, object| Aabb::enclosing(&bbox, &object.bounding_box()))
+    }
+}
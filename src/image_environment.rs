@@ -0,0 +1,101 @@
+use std::f32::consts::PI;
+
+use crate::{
+    color::{Color, luminance}, 
+    distribution::Distribution2D, 
+    environment::Environment, 
+    image_texture::ImageTexture, 
+    util::degrees_to_radians, 
+    vec::Vec3,
+};
+
+/// An equirectangular image wrapped around the scene, with +y at the top row and -z at the
+/// center column. Directions are importance sampled by the image's luminance.
+pub struct ImageEnvironment {
+    /// Turns the image counterclockwise about +y, in degrees.
+    pub rotation: f32,
+    /// Scales the image's radiance.
+    pub intensity: f32,
+    image: ImageTexture,
+    distribution: Distribution2D,
+}
+
+impl ImageEnvironment {
+    pub fn new(image: ImageTexture, rotation: f32, intensity: f32) -> Self {
+        // Rows near the poles cover less solid angle, so weight them by sin(theta)
+        let mut weights = Vec::with_capacity(image.pixels.len());
+        for (index, pixel) in image.pixels.iter().enumerate() {
+            let row = index / image.width;
+            let sin_theta = (PI * (row as f32 + 0.5) / image.height as f32).sin();
+            weights.push(luminance(pixel) * sin_theta);
+        }
+        let distribution = Distribution2D::new(&weights, image.width, image.height);
+        Self {rotation, intensity, image, distribution}
+    }
+
+    pub fn load(
+        path: impl AsRef<std::path::Path>, 
+        rotation: f32, 
+        intensity: f32
+    ) -> std::io::Result<Self> {
+        let image = ImageTexture::load(path)?;
+        if image.pixels.is_empty() {
+            return Err(std::io::Error::new(std::io::ErrorKind::InvalidData, "Image is empty"));
+        }
+        Ok(Self::new(image, rotation, intensity))
+    }
+
+    /// Returns image coordinates in [0, 1)^2, with v increasing downward.
+    fn direction_to_uv(&self, direction: &Vec3) -> (f32, f32) {
+        let direction = rotate_y(&direction.unit_vector(), -degrees_to_radians(self.rotation));
+        let theta = direction.y.clamp(-1.0, 1.0).acos();
+        let phi = direction.x.atan2(-direction.z);
+        (0.5 + phi / (2.0 * PI), theta / PI)
+    }
+
+    fn uv_to_direction(&self, u: f32, v: f32) -> Vec3 {
+        let theta = v * PI;
+        let phi = (u - 0.5) * 2.0 * PI;
+        let direction = Vec3 {
+            x: theta.sin() * phi.sin(),
+            y: theta.cos(),
+            z: -theta.sin() * phi.cos(),
+        };
+        rotate_y(&direction, degrees_to_radians(self.rotation))
+    }
+}
+
+impl Environment for ImageEnvironment {
+    fn radiance(&self, direction: &Vec3) -> Color {
+        let (u, v) = self.direction_to_uv(direction);
+        let i = ((u * self.image.width as f32) as usize).min(self.image.width - 1);
+        let j = ((v * self.image.height as f32) as usize).min(self.image.height - 1);
+        self.intensity * self.image.pixels[j * self.image.width + i]
+    }
+
+    fn sample(&self, u: f32, v: f32) -> (Vec3, f32) {
+        let ((u, v), uv_pdf) = self.distribution.sample(u, v);
+        let direction = self.uv_to_direction(u, v);
+
+        // Convert from density over the image to density over solid angle
+        let sin_theta = (v * PI).sin();
+        if sin_theta <= 0.0 {return (direction, 0.0);}
+        (direction, uv_pdf / (2.0 * PI * PI * sin_theta))
+    }
+
+    fn pdf(&self, direction: &Vec3) -> f32 {
+        let (u, v) = self.direction_to_uv(direction);
+        let sin_theta = (v * PI).sin();
+        if sin_theta <= 0.0 {return 0.0;}
+        self.distribution.pdf(u, v) / (2.0 * PI * PI * sin_theta)
+    }
+}
+
+fn rotate_y(direction: &Vec3, angle: f32) -> Vec3 {
+    let (sin, cos) = angle.sin_cos();
+    Vec3 {
+        x: cos * direction.x + sin * direction.z,
+        y: direction.y,
+        z: -sin * direction.x + cos * direction.z,
+    }
+}
@@ -0,0 +1,51 @@
+use std::path::Path;
+
+use crate::{
+    exr::{ExrPixelType, write_exr}, 
+    film::Film, 
+    hdr::write_hdr, 
+    pfm::write_pfm, 
+    png::{PngOptions, write_png}, 
+    ppm::write_ppm,
+};
+
+/// The file formats a finished film can be saved in.
+#[derive(Clone, Copy, PartialEq, Eq, Debug)]
+pub enum ImageFormat {
+    Ppm,
+    Png(PngOptions),
+    /// Linear radiance as 32-bit floats, unclamped.
+    Pfm,
+    /// Linear radiance, unclamped.
+    Exr(ExrPixelType),
+    /// Linear radiance in Radiance RGBE, unclamped.
+    Hdr,
+}
+
+impl ImageFormat {
+    pub fn from_name(name: &str) -> Option<Self> {
+        match name.to_ascii_lowercase().as_str() {
+            "ppm" => Some(Self::Ppm),
+            "png" => Some(Self::Png(PngOptions::default())),
+            "pfm" => Some(Self::Pfm),
+            "exr" => Some(Self::Exr(ExrPixelType::default())),
+            "hdr" => Some(Self::Hdr),
+            _ => None,
+        }
+    }
+
+    /// Picks the format matching the file extension.
+    pub fn from_path(path: &Path) -> Option<Self> {
+        Self::from_name(path.extension()?.to_str()?)
+    }
+
+    pub fn write(&self, stream: &mut impl std::io::Write, film: &Film) -> std::io::Result<()> {
+        match self {
+            Self::Ppm => write_ppm(stream, film),
+            Self::Png(options) => write_png(stream, film, options),
+            Self::Pfm => write_pfm(stream, film),
+            Self::Exr(pixel_type) => write_exr(stream, film, *pixel_type),
+            Self::Hdr => write_hdr(stream, film),
+        }
+    }
+}
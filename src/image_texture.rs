@@ -0,0 +1,134 @@
+use std::io::{Error, ErrorKind};
+
+use crate::{
+    color::{Color, gamma_to_linear}, 
+    film::Film, 
+    hdr::load_hdr, 
+    interval::Interval, 
+    texture::Texture, 
+    vec::Point3,
+};
+
+/// A texture backed by an image, stored as linear colors in row-major order from the top-left.
+pub struct ImageTexture {
+    pub width: usize,
+    pub height: usize,
+    pub pixels: Vec<Color>,
+}
+
+impl ImageTexture {
+    pub fn from_film(film: &Film) -> Self {
+        Self {width: film.width, height: film.height, pixels: film.pixels().collect()}
+    }
+
+    /// Loads a Radiance HDR image as is, or an ASCII (P3) or binary (P6) PPM image, undoing its
+    /// gamma.
+    pub fn load(path: impl AsRef<std::path::Path>) -> std::io::Result<Self> {
+        let path = path.as_ref();
+        if path.extension().is_some_and(|extension| extension.eq_ignore_ascii_case("hdr")) {
+            return Ok(Self::from_film(&load_hdr(path)?));
+        }
+
+        let bytes = std::fs::read(path)?;
+        let mut tokens = PpmTokens {bytes: &bytes, position: 0};
+
+        let magic = tokens.next_token()?;
+        let width = tokens.next_number()?;
+        let height = tokens.next_number()?;
+        let max_value = tokens.next_number()?;
+        if max_value == 0 || max_value > u16::MAX as usize {
+            return Err(invalid_data("PPM maximum value must be between 1 and 65535"));
+        }
+
+        let sample_count = width
+            .checked_mul(height)
+            .and_then(|pixel_count| pixel_count.checked_mul(3))
+            .ok_or_else(|| invalid_data("PPM image is too large"))?;
+        let samples = match magic {
+            b"P3" => {
+                (0..sample_count).map(|_| tokens.next_number()).collect::<Result<Vec<_>, _>>()?
+            },
+            b"P6" => {
+                // A single whitespace byte separates the header from the raster
+                let raster = bytes.get(tokens.position + 1..).unwrap_or_default();
+                let bytes_per_sample = if max_value > u8::MAX as usize {2} else {1};
+                if raster.len() / bytes_per_sample < sample_count {
+                    return Err(invalid_data("PPM raster is truncated"));
+                }
+                raster
+                    .chunks_exact(bytes_per_sample)
+                    .take(sample_count)
+                    .map(|sample| sample.iter().fold(0, |value, byte| value << 8 | *byte as usize))
+                    .collect()
+            },
+            _ => return Err(invalid_data("Only P3 and P6 PPM images are supported")),
+        };
+
+        let pixels = samples
+            .chunks_exact(3)
+            .map(|rgb| Color {
+                x: gamma_to_linear(rgb[0] as f32 / max_value as f32),
+                y: gamma_to_linear(rgb[1] as f32 / max_value as f32),
+                z: gamma_to_linear(rgb[2] as f32 / max_value as f32),
+            })
+            .collect();
+
+        Ok(Self {width, height, pixels})
+    }
+}
+
+impl Texture for ImageTexture {
+    fn value(&self, u: f32, v: f32, _: &Point3) -> Color {
+        // With no image data, return solid cyan as a debugging aid
+        if self.pixels.is_empty() {return Color {x: 0.0, y: 1.0, z: 1.0};}
+
+        // Clamp input texture coordinates to [0,1] x [1,0], flipping v to image coordinates
+        let unit = Interval {min: 0.0, max: 1.0};
+        let u = unit.clamp(u);
+        let v = 1.0 - unit.clamp(v);
+
+        let i = ((u * self.width as f32) as usize).min(self.width - 1);
+        let j = ((v * self.height as f32) as usize).min(self.height - 1);
+        self.pixels[j * self.width + i]
+    }
+}
+
+fn invalid_data(message: &str) -> Error {
+    Error::new(ErrorKind::InvalidData, message)
+}
+
+/// Splits a PPM header and ASCII raster into whitespace separated tokens, skipping comments.
+struct PpmTokens<'a> {
+    bytes: &'a [u8],
+    position: usize,
+}
+
+impl<'a> PpmTokens<'a> {
+    fn next_token(&mut self) -> std::io::Result<&'a [u8]> {
+        loop {
+            match self.bytes.get(self.position) {
+                Some(b'#') => {
+                    while self.bytes.get(self.position).is_some_and(|byte| *byte != b'\n') {
+                        self.position += 1;
+                    }
+                },
+                Some(byte) if byte.is_ascii_whitespace() => self.position += 1,
+                Some(_) => break,
+                None => return Err(invalid_data("PPM image ended early")),
+            }
+        }
+
+        let start = self.position;
+        while self.bytes.get(self.position).is_some_and(|byte| !byte.is_ascii_whitespace()) {
+            self.position += 1;
+        }
+        Ok(&self.bytes[start..self.position])
+    }
+
+    fn next_number(&mut self) -> std::io::Result<usize> {
+        std::str::from_utf8(self.next_token()?)
+            .ok()
+            .and_then(|token| token.parse().ok())
+            .ok_or_else(|| invalid_data("PPM image contains a malformed number"))
+    }
+}
@@ -0,0 +1,20 @@
+/// How `Camera::render` turns a camera ray into a color.
+#[derive(Clone, Copy, Default, PartialEq, Eq, Debug)]
+pub enum Integrator {
+    /// Full path tracing with every bounce up to `max_depth`.
+    #[default]
+    Path,
+    /// Shades each hit by its surface normal, mapped from [-1,1] to [0,1]. Useful for checking
+    /// geometry without waiting for light transport.
+    Normals,
+}
+
+impl Integrator {
+    pub fn from_name(name: &str) -> Option<Self> {
+        match name {
+            "path" => Some(Self::Path),
+            "normals" => Some(Self::Normals),
+            _ => None,
+        }
+    }
+}
@@ -14,6 +14,26 @@ impl Default for Interval {
 }
 
 impl Interval {
+    /// Returns the tightest interval containing both `a` and `b`.
+    pub fn enclosing(a: &Interval, b: &Interval) -> Self {
+        Self {
+            min: a.min.min(b.min),
+            max: a.max.max(b.max),
+        }
+    }
+
+    pub fn size(&self) -> f32 {
+        self.max - self.min
+    }
+
+    pub fn expand(&self, delta: f32) -> Self {
+        let padding = delta / 2.0;
+        Self {
+            min: self.min - padding,
+            max: self.max + padding,
+        }
+    }
+
     pub fn contains(&self, x: f32) -> bool {
         (self.min..=self.max).contains(&x)
     }
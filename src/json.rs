@@ -0,0 +1,221 @@
+/// A parsed JSON value, tagged with the line it starts on so callers can point at mistakes.
+#[derive(Debug, Clone)]
+pub struct Json {
+    pub line: usize,
+    pub value: JsonValue,
+}
+
+#[derive(Debug, Clone)]
+pub enum JsonValue {
+    Null,
+    Bool(bool),
+    Number(f64),
+    String(String),
+    Array(Vec<Json>),
+    /// Keys keep their source order, and each key carries the line it appears on.
+    Object(Vec<(String, usize, Json)>),
+}
+
+#[derive(Debug)]
+pub struct JsonError {
+    pub line: usize,
+    pub message: String,
+}
+
+impl std::fmt::Display for JsonError {
+    fn fmt(&self, f: &mut std::fmt::Formatter<'_>) -> std::fmt::Result {
+        write!(f, "line {}: {}", self.line, self.message)
+    }
+}
+
+impl std::error::Error for JsonError {}
+
+impl JsonValue {
+    /// Names the kind of value for error messages.
+    pub fn kind(&self) -> &'static str {
+        match self {
+            Self::Null => "null",
+            Self::Bool(_) => "a boolean",
+            Self::Number(_) => "a number",
+            Self::String(_) => "a string",
+            Self::Array(_) => "an array",
+            Self::Object(_) => "an object",
+        }
+    }
+}
+
+pub fn parse(source: &str) -> Result<Json, JsonError> {
+    let mut parser = Parser {bytes: source.as_bytes(), position: 0, line: 1};
+    let value = parser.value(0)?;
+    parser.skip_whitespace();
+    if parser.position < parser.bytes.len() {
+        return Err(parser.error("Unexpected characters after the top-level value"));
+    }
+    Ok(value)
+}
+
+struct Parser<'a> {
+    bytes: &'a [u8],
+    position: usize,
+    line: usize,
+}
+
+impl Parser<'_> {
+    fn error(&self, message: impl Into<String>) -> JsonError {
+        JsonError {line: self.line, message: message.into()}
+    }
+
+    fn peek(&self) -> Option<u8> {
+        self.bytes.get(self.position).copied()
+    }
+
+    fn skip_whitespace(&mut self) {
+        while let Some(byte) = self.peek() {
+            match byte {
+                b'\n' => self.line += 1,
+                b' ' | b'\t' | b'\r' => {},
+                _ => return,
+            }
+            self.position += 1;
+        }
+    }
+
+    fn expect(&mut self, expected: u8) -> Result<(), JsonError> {
+        self.skip_whitespace();
+        if self.peek() != Some(expected) {
+            return Err(self.error(format!("Expected '{}'", expected as char)));
+        }
+        self.position += 1;
+        Ok(())
+    }
+
+    /// `depth` counts the arrays and objects around the value, which recurse on the stack.
+    fn value(&mut self, depth: usize) -> Result<Json, JsonError> {
+        self.skip_whitespace();
+        let line = self.line;
+        if depth >= MAX_DEPTH && matches!(self.peek(), Some(b'{' | b'[')) {
+            return Err(self.error(format!("Nested more than {} levels deep", MAX_DEPTH)));
+        }
+        let value = match self.peek() {
+            Some(b'{') => self.object(depth)?,
+            Some(b'[') => self.array(depth)?,
+            Some(b'"') => JsonValue::String(self.string()?),
+            Some(b'-' | b'0'..=b'9') => self.number()?,
+            Some(b't') => self.literal("true", JsonValue::Bool(true))?,
+            Some(b'f') => self.literal("false", JsonValue::Bool(false))?,
+            Some(b'n') => self.literal("null", JsonValue::Null)?,
+            Some(byte) => return Err(self.error(format!("Unexpected '{}'", byte as char))),
+            None => return Err(self.error("Unexpected end of file")),
+        };
+        Ok(Json {line, value})
+    }
+
+    fn literal(&mut self, word: &str, value: JsonValue) -> Result<JsonValue, JsonError> {
+        if !self.bytes[self.position..].starts_with(word.as_bytes()) {
+            return Err(self.error(format!("Expected '{}'", word)));
+        }
+        self.position += word.len();
+        Ok(value)
+    }
+
+    fn number(&mut self) -> Result<JsonValue, JsonError> {
+        let start = self.position;
+        while self.peek().is_some_and(|byte| matches!(byte, b'-' | b'+' | b'.' | b'e' | b'E' | b'0'..=b'9')) {
+            self.position += 1;
+        }
+        let text = std::str::from_utf8(&self.bytes[start..self.position]).unwrap_or_default();
+        text.parse()
+            .map(JsonValue::Number)
+            .map_err(|_| self.error(format!("Invalid number '{}'", text)))
+    }
+
+    fn string(&mut self) -> Result<String, JsonError> {
+        self.expect(b'"')?;
+        let mut bytes = vec![];
+        loop {
+            let Some(byte) = self.peek() else {return Err(self.error("Unterminated string"));};
+            self.position += 1;
+            match byte {
+                b'"' => break,
+                b'\n' => return Err(self.error("Unterminated string")),
+                b'\\' => {
+                    let Some(escaped) = self.peek() else {return Err(self.error("Unterminated string"));};
+                    self.position += 1;
+                    let character = match escaped {
+                        b'"' => '"',
+                        b'\\' => '\\',
+                        b'/' => '/',
+                        b'b' => '\u{8}',
+                        b'f' => '\u{c}',
+                        b'n' => '\n',
+                        b'r' => '\r',
+                        b't' => '\t',
+                        b'u' => {
+                            let digits = self.bytes
+                                .get(self.position..self.position + 4)
+                                .and_then(|digits| std::str::from_utf8(digits).ok())
+                                .and_then(|digits| u32::from_str_radix(digits, 16).ok())
+                                .ok_or_else(|| self.error("Invalid unicode escape"))?;
+                            self.position += 4;
+                            char::from_u32(digits).unwrap_or(char::REPLACEMENT_CHARACTER)
+                        },
+                        _ => return Err(self.error("Invalid escape sequence")),
+                    };
+                    bytes.extend_from_slice(character.encode_utf8(&mut [0; 4]).as_bytes());
+                },
+                _ => bytes.push(byte),
+            }
+        }
+        String::from_utf8(bytes).map_err(|_| self.error("String is not valid UTF-8"))
+    }
+
+    fn array(&mut self, depth: usize) -> Result<JsonValue, JsonError> {
+        self.expect(b'[')?;
+        let mut items = vec![];
+        self.skip_whitespace();
+        if self.peek() == Some(b']') {
+            self.position += 1;
+            return Ok(JsonValue::Array(items));
+        }
+        loop {
+            items.push(self.value(depth + 1)?);
+            self.skip_whitespace();
+            match self.peek() {
+                Some(b',') => self.position += 1,
+                Some(b']') => {
+                    self.position += 1;
+                    return Ok(JsonValue::Array(items));
+                },
+                _ => return Err(self.error("Expected ',' or ']'")),
+            }
+        }
+    }
+
+    fn object(&mut self, depth: usize) -> Result<JsonValue, JsonError> {
+        self.expect(b'{')?;
+        let mut entries = vec![];
+        self.skip_whitespace();
+        if self.peek() == Some(b'}') {
+            self.position += 1;
+            return Ok(JsonValue::Object(entries));
+        }
+        loop {
+            self.skip_whitespace();
+            let line = self.line;
+            let key = self.string()?;
+            self.expect(b':')?;
+            entries.push((key, line, self.value(depth + 1)?));
+            self.skip_whitespace();
+            match self.peek() {
+                Some(b',') => self.position += 1,
+                Some(b'}') => {
+                    self.position += 1;
+                    return Ok(JsonValue::Object(entries));
+                },
+                _ => return Err(self.error("Expected ',' or '}'")),
+            }
+        }
+    }
+}
+
+const MAX_DEPTH: usize = 128;
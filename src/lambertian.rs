@@ -1,28 +1,74 @@
+use std::{f32::consts::PI, sync::Arc};
+
 use crate::{
     color::Color, 
-    material::Material, 
-    vec::random_unit_vector, 
+    material::{BsdfSample, Material}, 
+    vec::{Vec3, random_unit_vector}, 
     ray::Ray, 
-    hit_record::HitRecord
+    hit_record::HitRecord,
+    sampler::Sampler,
+    solid_color::SolidColor,
+    texture::Texture,
 };
 
 #[derive(Clone)]
 pub struct Lambertian {
-    pub albedo: Color,
+    pub albedo: Arc<dyn Texture>,
+}
+
+impl Lambertian {
+    pub fn new(albedo: Color) -> Self {
+        Self::from_texture(Arc::<_>::new(SolidColor {albedo}))
+    }
+
+    pub fn from_texture(albedo: Arc<dyn Texture>) -> Self {
+        Self {albedo}
+    }
 }
 
 impl Material for Lambertian {
     fn scatter(
-            &self,
-            _: &Ray,
-            hit_record: &HitRecord,
-            attenuation: &mut Color,
-            scattered: &mut Ray
-        ) -> bool {
-        let mut scatter_direction = hit_record.normal + random_unit_vector();
-        if scatter_direction.near_zero() {scatter_direction = hit_record.normal;}
-        *scattered = Ray {origin: hit_record.point, direction: scatter_direction};
-        *attenuation = self.albedo;
-        true
+        &self,
+        _: &Ray,
+        hit_record: &HitRecord,
+        sampler: &mut Sampler,
+    ) -> Option<BsdfSample> {
+        let albedo = self.albedo.value(hit_record.u, hit_record.v, &hit_record.point);
+        Some(diffuse_scatter(albedo, hit_record, sampler))
+    }
+
+    fn eval(&self, hit_record: &HitRecord, wi: &Vec3, _: &Vec3) -> Color {
+        let albedo = self.albedo.value(hit_record.u, hit_record.v, &hit_record.point);
+        diffuse_eval(albedo, hit_record, wi)
+    }
+
+    fn pdf(&self, hit_record: &HitRecord, wi: &Vec3, _: &Vec3) -> f32 {
+        diffuse_pdf(hit_record, wi)
     }
-}
\ No newline at end of file
+}
+
+/// Offsetting the normal by a random unit vector picks directions by their cosine, which
+/// cancels the BSDF's cosine and leaves just the albedo.
+pub fn diffuse_scatter(
+    albedo: Color, 
+    hit_record: &HitRecord, 
+    sampler: &mut Sampler
+) -> BsdfSample {
+    let mut direction = hit_record.normal + random_unit_vector(sampler);
+    if direction.near_zero() {direction = hit_record.normal;}
+    let direction = direction.unit_vector();
+    BsdfSample {
+        direction,
+        weight: albedo,
+        pdf: diffuse_pdf(hit_record, &direction),
+        is_specular: false,
+    }
+}
+
+pub fn diffuse_eval(albedo: Color, hit_record: &HitRecord, wi: &Vec3) -> Color {
+    albedo * hit_record.normal.dot(wi).max(0.0) / PI
+}
+
+pub fn diffuse_pdf(hit_record: &HitRecord, wi: &Vec3) -> f32 {
+    hit_record.normal.dot(wi).max(0.0) / PI
+}
@@ -1,15 +1,70 @@
+pub mod aabb;
+pub mod animated;
+pub mod animated_transform;
+pub mod bvh_node;
 pub mod camera;
+pub mod checker_texture;
 pub mod color;
+pub mod cone;
+pub mod constant_environment;
+pub mod cuboid;
+pub mod cylinder;
+pub mod deflate;
 pub mod dielectric;
+pub mod diffuse_light;
+pub mod disk;
+pub mod distribution;
+pub mod environment;
+pub mod environment_light;
+pub mod exr;
+pub mod film;
+pub mod frame;
+pub mod gradient_environment;
+pub mod hdr;
 pub mod hit_record;
 pub mod hittable;
 pub mod hittable_list;
+pub mod image_environment;
+pub mod image_format;
+pub mod image_texture;
+pub mod integrator;
 pub mod interval;
+pub mod json;
 pub mod lambertian;
+pub mod light;
+pub mod light_list;
 pub mod logger;
+pub mod marble_texture;
+pub mod mat4;
 pub mod material;
+pub mod material_arena;
 pub mod metal;
+pub mod moving_sphere;
+pub mod noise_texture;
+pub mod obj;
+pub mod perlin;
+pub mod pfm;
+pub mod plane;
+pub mod ply;
+pub mod png;
+pub mod polynomial;
+pub mod ppm;
+pub mod quad;
+pub mod quaternion;
 pub mod ray;
+pub mod rounded_box;
+pub mod sampler;
+pub mod scene;
+pub mod solid_color;
 pub mod sphere;
+pub mod stl;
+pub mod texture;
+pub mod torus;
+pub mod transform;
+pub mod transformed;
+pub mod triangle;
+pub mod triangle_mesh;
 pub mod util;
-pub mod vec;
\ No newline at end of file
+pub mod vec;
+pub mod vertex_color;
+pub mod wood_texture;
\ No newline at end of file
@@ -0,0 +1,36 @@
+use crate::{ray::Ray, vec::{Point3, Vec3}};
+
+/// A direction toward a light, picked by `Light::sample`.
+pub struct LightSample {
+    /// Unit direction from the shading point to the light.
+    pub direction: Vec3,
+    /// Distance to the sampled point, or infinity for lights at infinity.
+    pub distance: f32,
+    /// Density per solid angle of picking `direction`.
+    pub pdf: f32,
+}
+
+/// Something that gives off light and can be sampled directly, so paths don't have to find it by
+/// chance. The radiance itself comes from whatever a ray toward the light hits.
+pub trait Light: Send + Sync {
+    /// Maps two uniform numbers in [0, 1) to a direction from `origin` toward the light.
+    fn sample(&self, origin: &Point3, u: f32, v: f32) -> Option<LightSample>;
+
+    /// The density `sample` would give for reaching this light at parameter `t` along `ray`, or
+    /// zero if the ray doesn't meet the light there. Use infinity for rays that escape the scene.
+    fn pdf(&self, ray: &Ray, t: f32) -> f32;
+}
+
+/// Converts a density per unit area at the end of `to_light` into a density per solid angle
+/// seen from its start.
+pub fn area_to_solid_angle(area_pdf: f32, to_light: &Vec3, normal: &Vec3) -> f32 {
+    let distance_squared = to_light.length_squared();
+    let cosine = to_light.dot(normal).abs() / distance_squared.sqrt();
+    if cosine < 1e-6 {return 0.0;}
+    area_pdf * distance_squared / cosine
+}
+
+/// Whether two distances along a ray refer to the same hit, allowing for rounding.
+pub fn same_distance(a: f32, b: f32) -> bool {
+    (a - b).abs() <= 1e-3 * a.abs().max(b.abs())
+}
@@ -0,0 +1,37 @@
+use std::sync::Arc;
+
+use crate::{light::{Light, LightSample}, ray::Ray, vec::Point3};
+
+/// The lights sampled directly at every bounce, kept next to the `HittableList` they're part of.
+#[derive(Default)]
+pub struct LightList {
+    pub lights: Vec<Arc<dyn Light>>,
+}
+
+impl LightList {
+    pub fn add(&mut self, light: Arc<dyn Light>) {
+        self.lights.push(light);
+    }
+
+    pub fn is_empty(&self) -> bool {
+        self.lights.is_empty()
+    }
+
+    /// Picks one light uniformly with `select` and samples it with `u` and `v`. The returned
+    /// density includes the odds of picking that light.
+    pub fn sample(&self, origin: &Point3, select: f32, u: f32, v: f32) -> Option<LightSample> {
+        let count = self.lights.len();
+        let index = ((select * count as f32) as usize).min(count.checked_sub(1)?);
+        let light = &self.lights[index];
+        let mut sample = light.sample(origin, u, v)?;
+        sample.pdf /= count as f32;
+        Some(sample)
+    }
+
+    /// The density `sample` would give for the light met at parameter `t` along `ray`.
+    pub fn pdf(&self, ray: &Ray, t: f32) -> f32 {
+        if self.lights.is_empty() {return 0.0;}
+        let total: f32 = self.lights.iter().map(|light| light.pdf(ray, t)).sum();
+        total / self.lights.len() as f32
+    }
+}
@@ -4,5 +4,5 @@ pub struct Logger<'a> {
 }
 
 pub fn log(stream: &mut impl std::io::Write, message: String) {
-    stream.write(message.as_bytes()).expect("Failed to write");
+    stream.write_all(message.as_bytes()).expect("Failed to write");
 }
\ No newline at end of file
@@ -6,22 +6,110 @@ use raytracer::{
     lambertian::Lambertian,
     color::Color, 
     metal::Metal,
-    dielectric::Dielectric, util::random_double,
+    dielectric::Dielectric, 
+    sampler::{Sampler, entropy_seed}, 
+    scene::Scene,
+    light_list::LightList,
+    material_arena::MaterialArena,
+    logger::log,
+    vec::Vec2,
 };
+use std::{fs::File, io::{BufWriter, Write}, process::ExitCode, sync::Arc};
 
+mod cli;
 
-fn main() {
+fn main() -> ExitCode {
     let mut logger = raytracer::logger::Logger {
         stdout: std::io::stdout().lock(),
         stderr: std::io::stderr().lock(),
     };
 
-    let mut world = HittableList {objects: vec![],};
+    let options = match cli::Options::parse(std::env::args().skip(1)) {
+        Ok(options) => options,
+        Err(message) => {
+            log(&mut logger.stderr, format!("{}\nRun 'raytracer --help' for usage.\n", message));
+            return ExitCode::from(2);
+        },
+    };
+    if options.help {
+        log(&mut logger.stdout, cli::USAGE.to_string());
+        return ExitCode::SUCCESS;
+    }
+
+    let scene = match &options.scene {
+        Some(path) => match Scene::load(path) {
+            Ok(scene) => scene,
+            Err(error) => {
+                log(&mut logger.stderr, format!("Failed to load {}: {}\n", path.display(), error));
+                return ExitCode::FAILURE;
+            },
+        },
+        // The seed lays out the random spheres too, so seeded renders are reproducible
+        None => random_spheres(&mut Sampler::new(options.seed.unwrap_or_else(entropy_seed), 0)),
+    };
+    for warning in &scene.warnings {
+        log(&mut logger.stderr, format!("Warning: {}\n", warning));
+    }
+
+    // Open the output before rendering so a bad path fails fast
+    let output = match options.output.as_ref().map(File::create).transpose() {
+        Ok(output) => output,
+        Err(error) => {
+            log(&mut logger.stderr, format!("Failed to create output file: {}\n", error));
+            return ExitCode::FAILURE;
+        },
+    };
+
+    let mut camera = scene.camera;
+    apply_options(&mut camera, &options);
+    let world = scene.world.build();
+    let film = camera.render(&mut logger, world.as_ref(), &scene.materials, &scene.lights);
+    log(&mut logger.stderr, "\nDone.\n".to_string());
+
+    let format = options.image_format();
+    let written = match output {
+        Some(file) => {
+            let mut stream = BufWriter::new(file);
+            format.write(&mut stream, &film).and_then(|_| stream.flush())
+        },
+        None => format.write(&mut logger.stdout, &film),
+    };
+    if let Err(error) = written {
+        log(&mut logger.stderr, format!("Failed to write image: {}\n", error));
+        return ExitCode::FAILURE;
+    }
+    ExitCode::SUCCESS
+}
+
+fn apply_options(camera: &mut Camera, options: &cli::Options) {
+    match (options.width, options.height) {
+        (Some(width), Some(height)) => camera.image = Vec2 {width, height},
+        (Some(width), None) => camera.image = Vec2 {width, height: 0},
+        (None, Some(height)) => camera.image = Vec2 {
+            width: ((height as f32 * camera.aspect_ratio) as i32).max(1), 
+            height,
+        },
+        (None, None) => {},
+    }
+    if let Some(samples_per_pixel) = options.samples_per_pixel {
+        camera.samples_per_pixel = samples_per_pixel;
+    }
+    if let Some(max_depth) = options.max_depth {camera.max_depth = max_depth;}
+    if let Some(depth) = options.russian_roulette_depth {camera.russian_roulette_depth = depth;}
+    if let Some(thread_count) = options.thread_count {camera.thread_count = thread_count;}
+    if let Some(seed) = options.seed {camera.seed = Some(seed);}
+    if let Some(integrator) = options.integrator {camera.integrator = integrator;}
+}
+
+/// The final scene of Ray Tracing in One Weekend: three large spheres among many small ones.
+fn random_spheres(sampler: &mut Sampler) -> Scene {
+    let mut world = HittableList::default();
+    let mut materials = MaterialArena::default();
 
-    let ground_material = Box::<_>::new(
-        Lambertian {albedo: Color {x: 0.5, y: 0.5, z: 0.5,}}
-    );
-    world.add(Box::<_>::new(Sphere {
+    let ground_material = materials.add(Box::<_>::new(
+        Lambertian::new(Color {x: 0.5, y: 0.5, z: 0.5,})
+    ));
+    world.add(Arc::<_>::new(Sphere {
         center: Point3 {x: 0.0, y: -1000.0, z: 0.0},
         radius: 1000.0,
         material: ground_material,
@@ -29,45 +117,53 @@ fn main() {
 
     for a in -11..11 {
         for b in -11..11 {
-            let material_rng = random_double(None, None);
+            let material_rng = sampler.next_f32();
             let center = Point3 {
-                x: a as f32 + 0.9 * random_double(None, None),
+                x: a as f32 + 0.9 * sampler.next_f32(),
                 y: 0.2,
-                z: b as f32 + 0.9 * random_double(None, None),
+                z: b as f32 + 0.9 * sampler.next_f32(),
             };
 
             if (center - Point3 {x: 4.0, y: 0.2, z: 0.0}).length() > 0.9 {
-                choose_material_from_rng(&mut world, material_rng, &center);
+                choose_material_from_rng(
+                    &mut world, 
+                    &mut materials, 
+                    sampler, 
+                    material_rng, 
+                    &center
+                );
             }
         }
     }
 
-    let big_dielectric_sphere_material = Box::<_>::new(Dielectric {ir: 1.5});
-    world.add(Box::<_>::new(Sphere {
+    let big_dielectric_sphere_material = materials.add(Box::<_>::new(Dielectric {ir: 1.5}));
+    world.add(Arc::<_>::new(Sphere {
         center: Point3 {x: 0.0, y: 1.0, z: 0.0}, 
         radius: 1.0, 
         material: big_dielectric_sphere_material
     }));
 
-    let big_lambertian_sphere_material = Box::<_>::new(
-        Lambertian {albedo: Color {x: 0.4, y: 0.2, z: 0.1}}
-    );
-    world.add(Box::<_>::new(Sphere {
+    let big_lambertian_sphere_material = materials.add(Box::<_>::new(
+        Lambertian::new(Color {x: 0.4, y: 0.2, z: 0.1})
+    ));
+    world.add(Arc::<_>::new(Sphere {
         center: Point3 {x: -4.0, y: 1.0, z: 0.0}, 
         radius: 1.0, 
         material: big_lambertian_sphere_material
     }));
 
-    let big_metal_sphere_material = Box::<_>::new(
-        Metal {albedo: Color {x: 0.7, y: 0.6, z: 0.5}, fuzz: 0.0}
-    );
-    world.add(Box::<_>::new(Sphere {
+    let big_metal_sphere_material = materials.add(Box::<_>::new(
+        Metal::new(&Color {x: 0.7, y: 0.6, z: 0.5}, 0.0)
+    ));
+    world.add(Arc::<_>::new(Sphere {
         center: Point3 {x: 4.0, y: 1.0, z: 0.0}, 
         radius: 1.0, 
         material: big_metal_sphere_material
     }));
 
     let mut camera = Camera::default();
+    camera.aspect_ratio = 16.0 / 9.0;
+    camera.image.width = 400;
     camera.samples_per_pixel = 100;
     camera.max_depth = 50;
     camera.vertical_field_of_view = 20.0;
@@ -75,30 +171,36 @@ fn main() {
     camera.look_at = Point3 {x: 0.0, y: 0.0, z: 0.0};
     camera.defocus_angle = 0.6;
     camera.focus_distance = 10.0;
-    camera.render(&mut logger, &mut world);
+    Scene {camera, world, materials, lights: LightList::default(), warnings: vec![]}
 }
 
-fn choose_material_from_rng(world: &mut HittableList, material_rng: f32, center: &Point3) {
+fn choose_material_from_rng(
+    world: &mut HittableList, 
+    materials: &mut MaterialArena, 
+    sampler: &mut Sampler, 
+    material_rng: f32, 
+    center: &Point3
+) {
     if material_rng < 0.8 {
-        let albedo = Color::random(None, None);
-        let sphere_material = Box::<_>::new(Lambertian {albedo});
-        world.add(Box::<_>::new(Sphere {
+        let albedo = Color::random(sampler, 0.0, 1.0);
+        let sphere_material = materials.add(Box::<_>::new(Lambertian::new(albedo)));
+        world.add(Arc::<_>::new(Sphere {
             center: *center, 
             radius: 0.2, 
             material: sphere_material
         }));
     } else if material_rng < 0.95 {
-        let albedo = Color::random(Some(0.5), Some(1.0));
-        let fuzz = random_double(Some(0.0), Some(0.5));
-        let sphere_material = Box::<_>::new(Metal {albedo, fuzz});
-        world.add(Box::<_>::new(Sphere {
+        let albedo = Color::random(sampler, 0.5, 1.0);
+        let fuzz = sampler.range(0.0, 0.5);
+        let sphere_material = materials.add(Box::<_>::new(Metal::new(&albedo, fuzz)));
+        world.add(Arc::<_>::new(Sphere {
             center: *center, 
             radius: 0.2, 
             material: sphere_material
         }));
     } else {
-        let sphere_material = Box::<_>::new(Dielectric {ir: 1.5});
-        world.add(Box::<_>::new(Sphere {
+        let sphere_material = materials.add(Box::<_>::new(Dielectric {ir: 1.5}));
+        world.add(Arc::<_>::new(Sphere {
             center: *center, 
             radius: 0.2, 
             material: sphere_material
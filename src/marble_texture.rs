@@ -0,0 +1,22 @@
+use crate::{color::Color, perlin::Perlin, texture::Texture, vec::Point3};
+
+/// Veins of `color` running across the Z axis, phase shifted by turbulence.
+pub struct MarbleTexture {
+    pub noise: Perlin,
+    pub color: Color,
+    pub scale: f32,
+    pub turbulence_depth: usize,
+}
+
+impl MarbleTexture {
+    pub fn new(color: Color, scale: f32) -> Self {
+        Self {noise: Perlin::default(), color, scale, turbulence_depth: 7}
+    }
+}
+
+impl Texture for MarbleTexture {
+    fn value(&self, _: f32, _: f32, point: &Point3) -> Color {
+        let turbulence = self.noise.turbulence(point, self.turbulence_depth);
+        self.color * 0.5 * (1.0 + (self.scale * point.z + 10.0 * turbulence).sin())
+    }
+}
@@ -0,0 +1,116 @@
+use crate::vec::{Point3, Vec3};
+
+/// A 4x4 matrix in row-major order that multiplies column vectors. Only affine matrices, whose
+/// bottom row is 0 0 0 1, are produced by this crate.
+#[derive(Clone, Copy, Debug, PartialEq)]
+pub struct Mat4 {
+    pub rows: [[f32; 4]; 4],
+}
+
+impl Default for Mat4 {
+    fn default() -> Self {
+        IDENTITY
+    }
+}
+
+impl Mat4 {
+    pub fn translation(offset: &Vec3) -> Self {
+        let mut matrix = IDENTITY;
+        for axis in 0..3 {matrix.rows[axis][3] = offset[axis];}
+        matrix
+    }
+
+    pub fn scaling(factors: &Vec3) -> Self {
+        let mut matrix = IDENTITY;
+        for axis in 0..3 {matrix.rows[axis][axis] = factors[axis];}
+        matrix
+    }
+
+    /// A counterclockwise rotation by `degrees` about `axis` when looking down the axis toward
+    /// the origin (Rodrigues' formula).
+    pub fn rotation(axis: &Vec3, degrees: f32) -> Self {
+        let Vec3 {x, y, z} = axis.unit_vector();
+        let (sin, cos) = degrees.to_radians().sin_cos();
+        let t = 1.0 - cos;
+        Self {
+            rows: [
+                [t * x * x + cos, t * x * y - sin * z, t * x * z + sin * y, 0.0],
+                [t * x * y + sin * z, t * y * y + cos, t * y * z - sin * x, 0.0],
+                [t * x * z - sin * y, t * y * z + sin * x, t * z * z + cos, 0.0],
+                [0.0, 0.0, 0.0, 1.0],
+            ],
+        }
+    }
+
+    pub fn transpose(&self) -> Self {
+        let mut transposed = *self;
+        for (i, row) in transposed.rows.iter_mut().enumerate() {
+            for (j, value) in row.iter_mut().enumerate() {*value = self.rows[j][i];}
+        }
+        transposed
+    }
+
+    /// Inverts an affine matrix by inverting its linear part with cofactors and undoing the
+    /// translation. Returns `None` if the matrix collapses space onto a plane or line.
+    pub fn affine_inverse(&self) -> Option<Self> {
+        let m = &self.rows;
+        let cofactor = |r0: usize, r1: usize, c0: usize, c1: usize| {
+            m[r0][c0] * m[r1][c1] - m[r0][c1] * m[r1][c0]
+        };
+        // The adjugate of the upper 3x3 block, which is the transpose of its cofactors
+        let adjugate = [
+            [cofactor(1, 2, 1, 2), -cofactor(0, 2, 1, 2), cofactor(0, 1, 1, 2)],
+            [-cofactor(1, 2, 0, 2), cofactor(0, 2, 0, 2), -cofactor(0, 1, 0, 2)],
+            [cofactor(1, 2, 0, 1), -cofactor(0, 2, 0, 1), cofactor(0, 1, 0, 1)],
+        ];
+        let determinant = (0..3).map(|k| m[0][k] * adjugate[k][0]).sum::<f32>();
+        if determinant.abs() <= f32::MIN_POSITIVE {return None;}
+
+        let mut inverse = IDENTITY;
+        for (row, adjugate_row) in inverse.rows.iter_mut().zip(adjugate) {
+            for (value, entry) in row.iter_mut().zip(adjugate_row) {
+                *value = entry / determinant;
+            }
+        }
+        let translation = Vec3 {x: m[0][3], y: m[1][3], z: m[2][3]};
+        let offset = -inverse.transform_vector(&translation);
+        for axis in 0..3 {inverse.rows[axis][3] = offset[axis];}
+        Some(inverse)
+    }
+
+    pub fn transform_point(&self, point: &Point3) -> Point3 {
+        let translation = Vec3 {x: self.rows[0][3], y: self.rows[1][3], z: self.rows[2][3]};
+        self.transform_vector(point) + translation
+    }
+
+    /// Applies only the linear part, as directions are unaffected by translation.
+    pub fn transform_vector(&self, vector: &Vec3) -> Vec3 {
+        let row = |i: usize| {
+            self.rows[i][0] * vector.x + self.rows[i][1] * vector.y + self.rows[i][2] * vector.z
+        };
+        Vec3 {x: row(0), y: row(1), z: row(2)}
+    }
+}
+
+impl std::ops::Mul for Mat4 {
+    type Output = Self;
+
+    fn mul(self, rhs: Self) -> Self::Output {
+        let mut product = Self {rows: [[0.0; 4]; 4]};
+        for (i, row) in product.rows.iter_mut().enumerate() {
+            for (j, value) in row.iter_mut().enumerate() {
+                *value = (0..4).map(|k| self.rows[i][k] * rhs.rows[k][j]).sum();
+            }
+        }
+        product
+    }
+}
+
+pub const IDENTITY: Mat4 = Mat4 {
+    rows: [
+        [1.0, 0.0, 0.0, 0.0],
+        [0.0, 1.0, 0.0, 0.0],
+        [0.0, 0.0, 1.0, 0.0],
+        [0.0, 0.0, 0.0, 1.0],
+    ],
+};
@@ -1,14 +1,49 @@
-use crate::{ray::Ray, hit_record::HitRecord, color::Color};
-use dyn_clone::DynClone;
+use crate::{ray::Ray, hit_record::HitRecord, color::Color, sampler::Sampler, vec::Vec3};
 
-dyn_clone::clone_trait_object!(Material);
+/// A direction picked by `Material::scatter`.
+pub struct BsdfSample {
+    /// Unit direction the light continues in, away from the surface.
+    pub direction: Vec3,
+    /// The BSDF times the cosine, divided by `pdf`: what the light arriving from `direction`
+    /// is multiplied by.
+    pub weight: Color,
+    /// Density per solid angle of picking `direction`. Meaningless for specular samples.
+    pub pdf: f32,
+    /// Whether the direction came from a delta distribution, such as a mirror reflection, that
+    /// `eval` and `pdf` can't represent and light sampling can never hit.
+    pub is_specular: bool,
+}
 
-pub trait Material: DynClone {
+/// Directions are unit vectors pointing away from the surface: `wo` back toward where the light
+/// ends up and `wi` toward where it comes from.
+pub trait Material: Send + Sync {
+    /// Picks the direction light arriving along `in_ray` scatters from, or `None` if it's
+    /// absorbed.
     fn scatter(
         &self,
         in_ray: &Ray,
         hit_record: &HitRecord,
-        attenuation: &mut Color,
-        scattered: &mut Ray
-    ) -> bool;
-}
\ No newline at end of file
+        sampler: &mut Sampler,
+    ) -> Option<BsdfSample>;
+
+    /// The BSDF times the cosine of `wi` with the normal. Zero for specular materials.
+    fn eval(&self, _hit_record: &HitRecord, _wi: &Vec3, _wo: &Vec3) -> Color {
+        Color::default()
+    }
+
+    /// The density per solid angle with which `scatter` picks `wi` when seen from `wo`. Zero for
+    /// specular materials.
+    fn pdf(&self, _hit_record: &HitRecord, _wi: &Vec3, _wo: &Vec3) -> f32 {
+        0.0
+    }
+
+    /// Radiance given off by the surface itself, which is black for anything but lights.
+    fn emitted(&self, _in_ray: &Ray, _hit_record: &HitRecord) -> Color {
+        Color::default()
+    }
+
+    /// Whether shapes made of this material should be registered as lights.
+    fn is_emissive(&self) -> bool {
+        false
+    }
+}
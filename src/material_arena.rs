@@ -0,0 +1,37 @@
+use crate::material::Material;
+
+/// A handle to a material owned by a `MaterialArena`. Hits carry this instead of the material
+/// itself, so finding one never allocates.
+#[derive(Clone, Copy, Debug, Default, PartialEq, Eq, Hash)]
+pub struct MaterialId(u32);
+
+/// Owns every material of a scene, each stored once however many objects share it.
+#[derive(Default)]
+pub struct MaterialArena {
+    materials: Vec<Box<dyn Material>>,
+}
+
+impl MaterialArena {
+    pub fn add(&mut self, material: Box<dyn Material>) -> MaterialId {
+        let id = u32::try_from(self.materials.len()).expect("Too many materials");
+        self.materials.push(material);
+        MaterialId(id)
+    }
+
+    pub fn len(&self) -> usize {
+        self.materials.len()
+    }
+
+    pub fn is_empty(&self) -> bool {
+        self.materials.is_empty()
+    }
+}
+
+/// Panics if the id came from a different arena with fewer materials.
+impl std::ops::Index<MaterialId> for MaterialArena {
+    type Output = dyn Material;
+
+    fn index(&self, id: MaterialId) -> &Self::Output {
+        self.materials[id.0 as usize].as_ref()
+    }
+}
@@ -1,34 +1,88 @@
-use crate::{color::Color, material::Material, vec::{reflect, random_unit_vector}, ray::Ray,};
+use std::{f32::consts::PI, sync::Arc};
+
+use crate::{
+    color::Color, 
+    hit_record::HitRecord, 
+    material::{BsdfSample, Material}, 
+    vec::{Vec3, reflect, random_unit_vector}, 
+    ray::Ray, 
+    sampler::Sampler, 
+    solid_color::SolidColor, 
+    texture::Texture,
+};
 
 #[derive(Clone)]
 pub struct Metal {
-    pub albedo: Color,
+    pub albedo: Arc<dyn Texture>,
     pub fuzz: f32,
 }
 
 impl Metal {
     pub fn new(color: &Color, fuzz: f32) -> Self {
+        Self::from_texture(Arc::<_>::new(SolidColor {albedo: *color}), fuzz)
+    }
+
+    pub fn from_texture(albedo: Arc<dyn Texture>, fuzz: f32) -> Self {
         Self {
-            albedo: *color,
+            albedo,
             fuzz: fuzz.min(1.0),
         }
     }
+
+    /// Only a perfect mirror is specular; fuzzy reflections spread over a cone of directions.
+    fn is_specular(&self) -> bool {
+        self.fuzz <= 0.0
+    }
 }
 
 impl Material for Metal {
+    /// Reflects about the normal, then moves the direction to a random point on a sphere of
+    /// radius `fuzz`. Directions that end up below the surface are absorbed.
     fn scatter(
-            &self,
-            in_ray: &crate::ray::Ray,
-            hit_record: &crate::hit_record::HitRecord,
-            attenuation: &mut Color,
-            scattered: &mut crate::ray::Ray
-        ) -> bool {
+        &self,
+        in_ray: &Ray,
+        hit_record: &HitRecord,
+        sampler: &mut Sampler,
+    ) -> Option<BsdfSample> {
         let reflected = reflect(&in_ray.direction.unit_vector(), &hit_record.normal);
-        *scattered = Ray {
-            origin: hit_record.point, 
-            direction: reflected + self.fuzz * random_unit_vector()
-        };
-        *attenuation = self.albedo;
-        scattered.direction.dot(&hit_record.normal) > 0.0
+        let direction = (reflected + self.fuzz * random_unit_vector(sampler)).unit_vector();
+        if direction.dot(&hit_record.normal) <= 0.0 {return None;}
+
+        let wo = -in_ray.direction.unit_vector();
+        Some(BsdfSample {
+            direction,
+            weight: self.albedo.value(hit_record.u, hit_record.v, &hit_record.point),
+            pdf: if self.is_specular() {1.0} else {self.pdf(hit_record, &direction, &wo)},
+            is_specular: self.is_specular(),
+        })
     }
-}
\ No newline at end of file
+
+    /// Scattering always passes on the albedo, so the BSDF is the albedo times the density of
+    /// the fuzzed reflection, minus whatever would go below the surface.
+    fn eval(&self, hit_record: &HitRecord, wi: &Vec3, wo: &Vec3) -> Color {
+        if wi.dot(&hit_record.normal) <= 0.0 {return Color::default();}
+        let albedo = self.albedo.value(hit_record.u, hit_record.v, &hit_record.point);
+        albedo * self.pdf(hit_record, wi, wo)
+    }
+
+    /// A direction's density comes from where its ray pierces the fuzz sphere around the mirror
+    /// direction.
+    fn pdf(&self, hit_record: &HitRecord, wi: &Vec3, wo: &Vec3) -> f32 {
+        if self.is_specular() {return 0.0;}
+        let reflected = reflect(&-*wo, &hit_record.normal);
+
+        // Solve |t * wi - reflected| = fuzz for the distances t along wi
+        let b = wi.dot(&reflected);
+        let discriminant = b * b - 1.0 + self.fuzz * self.fuzz;
+        if discriminant <= 0.0 {return 0.0;}
+        let root = discriminant.sqrt();
+
+        // Each piercing point spreads the sphere's uniform area density over solid angle by
+        // t^2 over the cosine between the ray and the sphere
+        [b - root, b + root]
+            .iter()
+            .filter(|t| **t > 0.0)
+            .map(|t| t * t / (4.0 * PI * self.fuzz * root))
+            .sum()
+    }
+}
@@ -0,0 +1,42 @@
+use crate::{
+    aabb::Aabb, 
+    hit_record::HitRecord, 
+    hittable::Hittable, 
+    interval::Interval, 
+    material_arena::MaterialId, 
+    ray::Ray, 
+    sphere::hit_sphere, 
+    vec::{Point3, Vec3},
+};
+
+/// A sphere whose center moves in a straight line from `start_center` at time 0 to
+/// `end_center` at time 1, resting at either end outside that span.
+pub struct MovingSphere {
+    pub start_center: Point3,
+    pub end_center: Point3,
+    pub radius: f32,
+    pub material: MaterialId,
+}
+
+impl MovingSphere {
+    pub fn center(&self, time: f32) -> Point3 {
+        self.start_center + time.clamp(0.0, 1.0) * (self.end_center - self.start_center)
+    }
+}
+
+impl Hittable for MovingSphere {
+    fn hit(&self, ray: &Ray, ray_t: Interval, hit_record: &mut HitRecord) -> bool {
+        if !hit_sphere(&self.center(ray.time), self.radius, ray, ray_t, hit_record) {
+            return false;
+        }
+        hit_record.material = self.material;
+        true
+    }
+
+    /// Covers the whole path, so the BVH finds the sphere at any time.
+    fn bounding_box(&self) -> Aabb {
+        let radius = Vec3 {x: self.radius, y: self.radius, z: self.radius};
+        let around = |center: Point3| Aabb::from_points(&(center - radius), &(center + radius));
+        Aabb::enclosing(&around(self.start_center), &around(self.end_center))
+    }
+}
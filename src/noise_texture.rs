@@ -0,0 +1,20 @@
+use crate::{color::Color, perlin::Perlin, texture::Texture, vec::Point3};
+
+/// Grayscale Perlin noise, remapped from [-1,1] to [0,1].
+pub struct NoiseTexture {
+    pub noise: Perlin,
+    pub scale: f32,
+}
+
+impl NoiseTexture {
+    pub fn new(scale: f32) -> Self {
+        Self {noise: Perlin::default(), scale}
+    }
+}
+
+impl Texture for NoiseTexture {
+    fn value(&self, _: f32, _: f32, point: &Point3) -> Color {
+        let noise = self.noise.noise(&(self.scale * *point));
+        Color {x: 1.0, y: 1.0, z: 1.0} * 0.5 * (1.0 + noise)
+    }
+}
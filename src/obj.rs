@@ -0,0 +1,311 @@
+use std::{
+    collections::HashMap, 
+    path::{Path, PathBuf}, 
+    sync::Arc,
+};
+
+use crate::{
+    color::Color, 
+    dielectric::Dielectric, 
+    image_texture::ImageTexture, 
+    lambertian::Lambertian, 
+    material::Material, 
+    material_arena::MaterialArena, 
+    metal::Metal, 
+    triangle_mesh::{MeshData, MeshFace, TriangleMesh, default_material}, 
+    vec::Vec3,
+};
+
+#[derive(Debug)]
+pub enum ObjError {
+    /// Reading the OBJ file or one of its MTL libraries failed.
+    Io {path: PathBuf, error: std::io::Error},
+    Parse {path: PathBuf, line: usize, message: String},
+}
+
+impl std::fmt::Display for ObjError {
+    fn fmt(&self, f: &mut std::fmt::Formatter<'_>) -> std::fmt::Result {
+        match self {
+            Self::Io {path, error} => write!(f, "{}: {}", path.display(), error),
+            Self::Parse {path, line, message} => {
+                write!(f, "{}:{}: {}", path.display(), line, message)
+            },
+        }
+    }
+}
+
+impl std::error::Error for ObjError {
+    fn source(&self) -> Option<&(dyn std::error::Error + 'static)> {
+        match self {
+            Self::Io {error, ..} => Some(error),
+            Self::Parse {..} => None,
+        }
+    }
+}
+
+pub fn load_obj(
+    path: impl AsRef<Path>, 
+    materials: &mut MaterialArena
+) -> Result<TriangleMesh, ObjError> {
+    Ok(TriangleMesh::new(read_obj(path)?, materials))
+}
+
+/// Reads a Wavefront OBJ file and the MTL libraries it references. Polygons are split into
+/// triangle fans, and faces without `usemtl` get the default mesh material. A library that
+/// can't be read only adds to the data's warnings, and its materials fall back to the default
+/// as well.
+pub fn read_obj(path: impl AsRef<Path>) -> Result<MeshData, ObjError> {
+    let path = path.as_ref();
+    let source = read_to_string(path)?;
+    let directory = path.parent().unwrap_or(Path::new(""));
+
+    let mut data = MeshData::default();
+    data.materials.push(default_material());
+    let mut library = HashMap::new();
+    let mut material_indices = HashMap::new();
+    let mut current_material = 0;
+    let mut missing_library = false;
+
+    for (line_index, line) in source.lines().enumerate() {
+        let mut fields = Fields::new(path, line_index + 1, line);
+        let Some(keyword) = fields.next() else {continue;};
+
+        match keyword {
+            "v" => data.positions.push(fields.vec3()?),
+            "vn" => data.normals.push(fields.vec3()?),
+            "vt" => {
+                let u = fields.number()?;
+                let v = fields.optional_number()?.unwrap_or(0.0);
+                data.uvs.push([u, v]);
+            },
+            "f" => {
+                let vertices = fields
+                    .by_ref()
+                    .map(|vertex| parse_face_vertex(vertex, &data))
+                    .collect::<Result<Vec<_>, _>>()
+                    .map_err(|message| fields.error(message))?;
+                if vertices.len() < 3 {return Err(fields.error("Face has fewer than 3 vertices"));}
+
+                for i in 1..vertices.len() - 1 {
+                    let corners = [vertices[0], vertices[i], vertices[i + 1]];
+                    let all_present = |select: fn(&FaceVertex) -> Option<usize>| {
+                        let indices = corners.map(|corner| select(&corner));
+                        indices.iter().all(Option::is_some).then(|| indices.map(Option::unwrap))
+                    };
+                    data.faces.push(MeshFace {
+                        positions: corners.map(|corner| corner.position),
+                        normals: all_present(|corner| corner.normal),
+                        uvs: all_present(|corner| corner.uv),
+                        colors: None,
+                        material: current_material,
+                    });
+                }
+            },
+            "mtllib" => {
+                for library_path in fields.by_ref() {
+                    match load_mtl(&directory.join(library_path)) {
+                        Ok(materials) => library.extend(materials),
+                        Err(error @ ObjError::Io {..}) => {
+                            data.warnings.push(format!("{}, using the default material", error));
+                            missing_library = true;
+                        },
+                        Err(error) => return Err(error),
+                    }
+                }
+            },
+            "usemtl" => {
+                let name = fields.rest();
+                current_material = match material_indices.get(name) {
+                    Some(index) => *index,
+                    None if missing_library && !library.contains_key(name) => 0,
+                    None => {
+                        let definition: &MtlMaterial = library
+                            .get(name)
+                            .ok_or_else(|| fields.error(format!("Unknown material '{}'", name)))?;
+                        data.materials.push(definition.to_material(directory));
+                        material_indices.insert(name.to_string(), data.materials.len() - 1);
+                        data.materials.len() - 1
+                    },
+                };
+            },
+            // Groups, objects, smoothing groups, lines and points do not affect rendering
+            _ => {},
+        }
+    }
+
+    Ok(data)
+}
+
+#[derive(Clone, Copy)]
+struct FaceVertex {
+    position: usize,
+    uv: Option<usize>,
+    normal: Option<usize>,
+}
+
+/// Parses `v`, `v/vt`, `v//vn` or `v/vt/vn`, resolving 1-based and negative relative indices.
+fn parse_face_vertex(vertex: &str, data: &MeshData) -> Result<FaceVertex, String> {
+    let resolve = |index: Option<&str>, count: usize, kind: &str| -> Result<Option<usize>, String> {
+        let Some(index) = index.filter(|index| !index.is_empty()) else {return Ok(None);};
+        let parsed: i64 = index
+            .parse()
+            .map_err(|_| format!("Invalid {} index '{}'", kind, index))?;
+        let resolved = if parsed < 0 {count as i64 + parsed} else {parsed - 1};
+        if resolved < 0 || resolved >= count as i64 {
+            return Err(format!("{} index {} is out of range", kind, parsed));
+        }
+        Ok(Some(resolved as usize))
+    };
+
+    let mut parts = vertex.split('/');
+    let position = resolve(parts.next(), data.positions.len(), "Vertex")?
+        .ok_or_else(|| format!("Face vertex '{}' has no position", vertex))?;
+    let uv = resolve(parts.next(), data.uvs.len(), "Texture coordinate")?;
+    let normal = resolve(parts.next(), data.normals.len(), "Normal")?;
+    Ok(FaceVertex {position, uv, normal})
+}
+
+/// The subset of MTL statements that map onto this crate's materials.
+struct MtlMaterial {
+    diffuse: Color,
+    specular: Color,
+    specular_exponent: f32,
+    refraction_index: Option<f32>,
+    dissolve: f32,
+    illumination: u32,
+    diffuse_map: Option<String>,
+}
+
+impl Default for MtlMaterial {
+    fn default() -> Self {
+        Self {
+            diffuse: Color {x: 0.8, y: 0.8, z: 0.8},
+            specular: Color::default(),
+            specular_exponent: 0.0,
+            refraction_index: None,
+            dissolve: 1.0,
+            illumination: 2,
+            diffuse_map: None,
+        }
+    }
+}
+
+impl MtlMaterial {
+    /// Transparent or refractive illumination models become `Dielectric`, reflective ones
+    /// become `Metal`, and everything else is `Lambertian`.
+    fn to_material(&self, directory: &Path) -> Box<dyn Material> {
+        if self.dissolve < 1.0 || matches!(self.illumination, 4 | 6 | 7 | 9) {
+            return Box::<_>::new(Dielectric {ir: self.refraction_index.unwrap_or(1.5)});
+        }
+
+        if matches!(self.illumination, 3 | 5 | 8) {
+            let albedo = if self.specular.near_zero() {self.diffuse} else {self.specular};
+            // Approximates the roughness of a Phong lobe with this exponent
+            let fuzz = (2.0 / (self.specular_exponent + 2.0)).sqrt();
+            return Box::<_>::new(Metal::new(&albedo, fuzz));
+        }
+
+        // Only PPM images can be decoded, so other maps fall back to the diffuse color
+        let texture = self.diffuse_map
+            .as_ref()
+            .and_then(|map| ImageTexture::load(directory.join(map)).ok());
+        match texture {
+            Some(texture) => Box::<_>::new(Lambertian::from_texture(Arc::<_>::new(texture))),
+            None => Box::<_>::new(Lambertian::new(self.diffuse)),
+        }
+    }
+}
+
+fn load_mtl(path: &Path) -> Result<HashMap<String, MtlMaterial>, ObjError> {
+    let source = read_to_string(path)?;
+    let mut materials = HashMap::new();
+    let mut current: Option<(String, MtlMaterial)> = None;
+
+    for (line_index, line) in source.lines().enumerate() {
+        let mut fields = Fields::new(path, line_index + 1, line);
+        let Some(keyword) = fields.next() else {continue;};
+
+        if keyword == "newmtl" {
+            materials.extend(current.take());
+            current = Some((fields.rest().to_string(), MtlMaterial::default()));
+            continue;
+        }
+
+        let Some((_, material)) = current.as_mut() else {
+            return Err(fields.error(format!("'{}' appears before any newmtl", keyword)));
+        };
+        match keyword {
+            "Kd" => material.diffuse = fields.vec3()?,
+            "Ks" => material.specular = fields.vec3()?,
+            "Ns" => material.specular_exponent = fields.number()?,
+            "Ni" => material.refraction_index = Some(fields.number()?),
+            "d" => material.dissolve = fields.number()?,
+            "Tr" => material.dissolve = 1.0 - fields.number()?,
+            "illum" => material.illumination = fields.number()? as u32,
+            "map_Kd" => material.diffuse_map = fields.rest().split_whitespace().last().map(String::from),
+            _ => {},
+        }
+    }
+    materials.extend(current);
+
+    Ok(materials)
+}
+
+fn read_to_string(path: &Path) -> Result<String, ObjError> {
+    std::fs::read_to_string(path).map_err(|error| ObjError::Io {path: path.to_path_buf(), error})
+}
+
+/// Whitespace separated fields of one line, with comments stripped. A `#` only starts a
+/// comment at the beginning of a field, so names and file names may contain one.
+struct Fields<'a> {
+    path: &'a Path,
+    line: usize,
+    remaining: &'a str,
+}
+
+impl<'a> Fields<'a> {
+    fn new(path: &'a Path, line: usize, text: &'a str) -> Self {
+        let comment = text
+            .match_indices('#')
+            .map(|(index, _)| index)
+            .find(|&index| text[..index].chars().next_back().is_none_or(char::is_whitespace));
+        let text = comment.map_or(text, |index| &text[..index]);
+        Self {path, line, remaining: text.trim()}
+    }
+
+    fn error(&self, message: impl Into<String>) -> ObjError {
+        ObjError::Parse {path: self.path.to_path_buf(), line: self.line, message: message.into()}
+    }
+
+    /// Returns everything left on the line, for names that may contain spaces.
+    fn rest(&mut self) -> &'a str {
+        std::mem::take(&mut self.remaining).trim()
+    }
+
+    fn optional_number(&mut self) -> Result<Option<f32>, ObjError> {
+        self.next()
+            .map(|field| field.parse().map_err(|_| self.error(format!("Invalid number '{}'", field))))
+            .transpose()
+    }
+
+    fn number(&mut self) -> Result<f32, ObjError> {
+        self.optional_number()?.ok_or_else(|| self.error("Expected a number"))
+    }
+
+    fn vec3(&mut self) -> Result<Vec3, ObjError> {
+        Ok(Vec3 {x: self.number()?, y: self.number()?, z: self.number()?})
+    }
+}
+
+impl<'a> Iterator for Fields<'a> {
+    type Item = &'a str;
+
+    fn next(&mut self) -> Option<Self::Item> {
+        let trimmed = self.remaining.trim_start();
+        if trimmed.is_empty() {return None;}
+        let end = trimmed.find(char::is_whitespace).unwrap_or(trimmed.len());
+        let (field, remaining) = trimmed.split_at(end);
+        self.remaining = remaining;
+        Some(field)
+    }
+}
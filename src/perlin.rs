@@ -0,0 +1,101 @@
+use crate::{sampler::Sampler, vec::{Point3, Vec3}};
+
+const POINT_COUNT: usize = 256;
+
+/// Gradient noise over a lattice of random unit vectors, repeating every 256 units.
+pub struct Perlin {
+    random_vectors: Vec<Vec3>,
+    permutation_x: Vec<usize>,
+    permutation_y: Vec<usize>,
+    permutation_z: Vec<usize>,
+}
+
+impl Default for Perlin {
+    /// Always builds the same lattice, so noise textures look alike from render to render.
+    fn default() -> Self {
+        let mut sampler = Sampler::new(0, 0);
+        Self {
+            random_vectors: (0..POINT_COUNT)
+                .map(|_| Vec3::random(&mut sampler, -1.0, 1.0).unit_vector())
+                .collect(),
+            permutation_x: generate_permutation(&mut sampler),
+            permutation_y: generate_permutation(&mut sampler),
+            permutation_z: generate_permutation(&mut sampler),
+        }
+    }
+}
+
+impl Perlin {
+    /// Returns noise in roughly [-1,1] that is zero at every lattice point.
+    pub fn noise(&self, point: &Point3) -> f32 {
+        let cell = |coordinate: f32| coordinate.floor();
+        let (i, j, k) = (cell(point.x), cell(point.y), cell(point.z));
+        let offset = Vec3 {x: point.x - i, y: point.y - j, z: point.z - k};
+        let (i, j, k) = (i as i32, j as i32, k as i32);
+
+        let mut corners = [[[Vec3::default(); 2]; 2]; 2];
+        for (di, plane) in corners.iter_mut().enumerate() {
+            for (dj, row) in plane.iter_mut().enumerate() {
+                for (dk, corner) in row.iter_mut().enumerate() {
+                    let hash = self.permutation_x[lattice_index(i + di as i32)]
+                        ^ self.permutation_y[lattice_index(j + dj as i32)]
+                        ^ self.permutation_z[lattice_index(k + dk as i32)];
+                    *corner = self.random_vectors[hash];
+                }
+            }
+        }
+
+        trilinear_interpolate(&corners, &offset)
+    }
+
+    /// Sums `depth` octaves of noise, each at double the frequency and half the weight of the
+    /// previous one, and returns the magnitude.
+    pub fn turbulence(&self, point: &Point3, depth: usize) -> f32 {
+        let mut accumulated = 0.0;
+        let mut sample_point = *point;
+        let mut weight = 1.0;
+
+        for _ in 0..depth {
+            accumulated += weight * self.noise(&sample_point);
+            weight *= 0.5;
+            sample_point *= 2.0;
+        }
+
+        accumulated.abs()
+    }
+}
+
+fn lattice_index(coordinate: i32) -> usize {
+    (coordinate & (POINT_COUNT as i32 - 1)) as usize
+}
+
+fn generate_permutation(sampler: &mut Sampler) -> Vec<usize> {
+    let mut permutation: Vec<usize> = (0..POINT_COUNT).collect();
+    for i in (1..POINT_COUNT).rev() {
+        let target = (sampler.range(0.0, i as f32 + 1.0) as usize).min(i);
+        permutation.swap(i, target);
+    }
+    permutation
+}
+
+/// Blends the gradients at the cell corners, easing the offset with a Hermite cubic so the
+/// noise has no visible grid artifacts.
+fn trilinear_interpolate(corners: &[[[Vec3; 2]; 2]; 2], offset: &Vec3) -> f32 {
+    let hermite = |t: f32| t * t * (3.0 - 2.0 * t);
+    let (uu, vv, ww) = (hermite(offset.x), hermite(offset.y), hermite(offset.z));
+
+    let mut accumulated = 0.0;
+    for (i, plane) in corners.iter().enumerate() {
+        for (j, row) in plane.iter().enumerate() {
+            for (k, corner) in row.iter().enumerate() {
+                let (i, j, k) = (i as f32, j as f32, k as f32);
+                let weight = Vec3 {x: offset.x - i, y: offset.y - j, z: offset.z - k};
+                accumulated += (i * uu + (1.0 - i) * (1.0 - uu))
+                    * (j * vv + (1.0 - j) * (1.0 - vv))
+                    * (k * ww + (1.0 - k) * (1.0 - ww))
+                    * corner.dot(&weight);
+            }
+        }
+    }
+    accumulated
+}
@@ -0,0 +1,21 @@
+use crate::film::Film;
+
+/// Writes the film as a little-endian color PFM (Portable FloatMap). The pixels are the film's
+/// linear radiance, unclamped. PFM stores rows from the bottom of the image up.
+pub fn write_pfm(stream: &mut impl std::io::Write, film: &Film) -> std::io::Result<()> {
+    // A negative scale marks the data as little-endian
+    write!(stream, "PF\n{} {}\n-1.0\n", film.width, film.height)?;
+
+    let mut row = Vec::with_capacity(film.width * 12);
+    for y in (0..film.height).rev() {
+        row.clear();
+        for x in 0..film.width {
+            let pixel = film.pixel(x, y);
+            for channel in [pixel.x, pixel.y, pixel.z] {
+                row.extend_from_slice(&channel.to_le_bytes());
+            }
+        }
+        stream.write_all(&row)?;
+    }
+    Ok(())
+}
@@ -0,0 +1,41 @@
+use crate::{
+    aabb::{self, Aabb}, 
+    hit_record::HitRecord, 
+    hittable::Hittable, 
+    interval::Interval, 
+    material_arena::MaterialId, 
+    ray::Ray, 
+    vec::{Point3, Vec3, orthonormal_basis},
+};
+
+/// An infinite plane through `point`. Texture coordinates are distances from `point` along two
+/// tangent directions, so they are unbounded.
+pub struct Plane {
+    pub point: Point3,
+    pub normal: Vec3,
+    pub material: MaterialId,
+}
+
+impl Hittable for Plane {
+    fn hit(&self, ray: &Ray, ray_t: Interval, hit_record: &mut HitRecord) -> bool {
+        let normal = self.normal.unit_vector();
+        let denominator = normal.dot(&ray.direction);
+        if denominator.abs() < 1e-8 {return false;}
+
+        let t = (self.point - ray.origin).dot(&normal) / denominator;
+        if !ray_t.surrounds(t) {return false;}
+
+        let (tangent, bitangent) = orthonormal_basis(&normal);
+        hit_record.t = t;
+        hit_record.point = ray.at(t);
+        hit_record.set_face_normal(ray, &normal);
+        let offset = hit_record.point - self.point;
+        (hit_record.u, hit_record.v) = (offset.dot(&tangent), offset.dot(&bitangent));
+        hit_record.material = self.material;
+        true
+    }
+
+    fn bounding_box(&self) -> Aabb {
+        aabb::UNIVERSE
+    }
+}
@@ -0,0 +1,414 @@
+use std::path::Path;
+
+use crate::{
+    color::{Color, gamma_to_linear}, 
+    material_arena::MaterialArena, 
+    triangle_mesh::{MeshData, MeshFace, TriangleMesh, default_material}, 
+    vec::Vec3, 
+    vertex_color::VertexColor,
+};
+
+#[derive(Debug)]
+pub enum PlyError {
+    Io(std::io::Error),
+    InvalidHeader {line: usize, message: String},
+    MissingProperty {element: &'static str, property: &'static str},
+    InvalidValue {element: String, index: usize, message: String},
+    UnexpectedEnd,
+    IndexOutOfRange {face: usize, index: i64},
+}
+
+impl std::fmt::Display for PlyError {
+    fn fmt(&self, f: &mut std::fmt::Formatter<'_>) -> std::fmt::Result {
+        match self {
+            Self::Io(error) => write!(f, "{}", error),
+            Self::InvalidHeader {line, message} => write!(f, "header line {}: {}", line, message),
+            Self::MissingProperty {element, property} => {
+                write!(f, "element '{}' has no '{}' property", element, property)
+            },
+            Self::InvalidValue {element, index, message} => {
+                write!(f, "{} {}: {}", element, index, message)
+            },
+            Self::UnexpectedEnd => write!(f, "file ended before every element was read"),
+            Self::IndexOutOfRange {face, index} => {
+                write!(f, "face {} references missing vertex {}", face, index)
+            },
+        }
+    }
+}
+
+impl std::error::Error for PlyError {
+    fn source(&self) -> Option<&(dyn std::error::Error + 'static)> {
+        match self {
+            Self::Io(error) => Some(error),
+            _ => None,
+        }
+    }
+}
+
+impl From<std::io::Error> for PlyError {
+    fn from(error: std::io::Error) -> Self {
+        Self::Io(error)
+    }
+}
+
+pub fn load_ply(
+    path: impl AsRef<Path>, 
+    materials: &mut MaterialArena
+) -> Result<TriangleMesh, PlyError> {
+    Ok(TriangleMesh::new(read_ply(path)?, materials))
+}
+
+/// Reads an ASCII or binary PLY file. Polygons are split into triangle fans. A mesh with vertex
+/// colors keeps them in `MeshData::colors` and shades every face with one `VertexColor`
+/// material; otherwise every face uses the default mesh material.
+pub fn read_ply(path: impl AsRef<Path>) -> Result<MeshData, PlyError> {
+    let bytes = std::fs::read(path)?;
+    let (header, body_start) = parse_header(&bytes)?;
+    let mut body = match header.format {
+        Format::Ascii => Body::Ascii(
+            std::str::from_utf8(&bytes[body_start..])
+                .map_err(|_| PlyError::InvalidValue {
+                    element: "body".to_string(), 
+                    index: 0, 
+                    message: "ASCII body is not valid UTF-8".to_string(),
+                })?
+                .split_ascii_whitespace()
+        ),
+        Format::BinaryLittleEndian => Body::Binary {bytes: &bytes[body_start..], big_endian: false},
+        Format::BinaryBigEndian => Body::Binary {bytes: &bytes[body_start..], big_endian: true},
+    };
+
+    let mut data = MeshData::default();
+    let mut polygons: Vec<Vec<i64>> = vec![];
+
+    for element in &header.elements {
+        let find = |name: &str| {
+            element.properties.iter().position(|property| property.name == name)
+        };
+        let find_any = |names: &[&str]| names.iter().find_map(|name| find(name));
+
+        // Nothing is read for an element without properties, so a hostile count could spin for
+        // billions of empty iterations. Vertices and faces still fail on their first one.
+        let is_mesh_element = matches!(element.name.as_str(), "vertex" | "face");
+        if element.properties.is_empty() && !is_mesh_element {continue;}
+
+        let mut values = vec![vec![]; element.properties.len()];
+        for index in 0..element.count {
+            for (property, value) in element.properties.iter().zip(values.iter_mut()) {
+                value.clear();
+                body.read_property(property, value).map_err(|error| match error {
+                    BodyError::End => PlyError::UnexpectedEnd,
+                    BodyError::Invalid(message) => {
+                        PlyError::InvalidValue {element: element.name.clone(), index, message}
+                    },
+                })?;
+            }
+
+            // A list property can stand in for a scalar only if it holds exactly one value
+            let scalar = |property: usize| match values[property].as_slice() {
+                [value] => Ok(*value),
+                _ => {
+                    let name = &element.properties[property].name;
+                    let message = format!("'{}' needs exactly one value", name);
+                    Err(PlyError::InvalidValue {element: element.name.clone(), index, message})
+                },
+            };
+
+            match element.name.as_str() {
+                "vertex" => {
+                    let required = |name: &'static str| {
+                        let property = find(name)
+                            .ok_or(PlyError::MissingProperty {element: "vertex", property: name})?;
+                        Ok::<_, PlyError>(scalar(property)? as f32)
+                    };
+                    let position = Vec3 {x: required("x")?, y: required("y")?, z: required("z")?};
+                    data.positions.push(position);
+
+                    if let (Some(x), Some(y), Some(z)) = (find("nx"), find("ny"), find("nz")) {
+                        let normal = |property: usize| scalar(property).map(|value| value as f32);
+                        data.normals.push(Vec3 {x: normal(x)?, y: normal(y)?, z: normal(z)?});
+                    }
+                    if let (Some(u), Some(v)) = (
+                        find_any(&["u", "s", "texture_u"]), 
+                        find_any(&["v", "t", "texture_v"]),
+                    ) {
+                        data.uvs.push([scalar(u)? as f32, scalar(v)? as f32]);
+                    }
+                    if let (Some(red), Some(green), Some(blue)) = (
+                        find_any(&["red", "r", "diffuse_red"]), 
+                        find_any(&["green", "g", "diffuse_green"]), 
+                        find_any(&["blue", "b", "diffuse_blue"]),
+                    ) {
+                        let channel = |property: usize| {
+                            let scale = element.properties[property].data_type.color_scale();
+                            scalar(property).map(|value| gamma_to_linear((value * scale) as f32))
+                        };
+                        data.colors.push(Color {
+                            x: channel(red)?, 
+                            y: channel(green)?, 
+                            z: channel(blue)?,
+                        });
+                    }
+                },
+                "face" => {
+                    let indices = find_any(&["vertex_indices", "vertex_index"]).ok_or(
+                        PlyError::MissingProperty {element: "face", property: "vertex_indices"}
+                    )?;
+                    // Negative whole numbers are kept, to be reported as out of range below
+                    let polygon = values[indices]
+                        .iter()
+                        .map(|value| {
+                            let whole = value.fract() == 0.0 
+                                && (i64::MIN as f64..i64::MAX as f64).contains(value);
+                            whole.then_some(*value as i64).ok_or_else(|| PlyError::InvalidValue {
+                                element: element.name.clone(),
+                                index,
+                                message: format!("Invalid vertex index '{}'", value),
+                            })
+                        })
+                        .collect::<Result<_, _>>()?;
+                    polygons.push(polygon);
+                },
+                _ => {},
+            }
+        }
+    }
+
+    let vertex_count = data.positions.len();
+    let has_normals = data.normals.len() == vertex_count;
+    let has_uvs = data.uvs.len() == vertex_count;
+    let has_colors = data.colors.len() == vertex_count && vertex_count > 0;
+    data.materials.push(if has_colors {Box::<_>::new(VertexColor)} else {default_material()});
+
+    for (face, polygon) in polygons.iter().enumerate() {
+        let resolve = |index: i64| {
+            usize::try_from(index)
+                .ok()
+                .filter(|index| *index < vertex_count)
+                .ok_or(PlyError::IndexOutOfRange {face, index})
+        };
+        let polygon = polygon
+            .iter()
+            .map(|index| resolve(*index))
+            .collect::<Result<Vec<_>, _>>()?;
+
+        for i in 1..polygon.len().saturating_sub(1) {
+            let corners = [polygon[0], polygon[i], polygon[i + 1]];
+            data.faces.push(MeshFace {
+                positions: corners,
+                normals: has_normals.then_some(corners),
+                uvs: has_uvs.then_some(corners),
+                colors: has_colors.then_some(corners),
+                material: 0,
+            });
+        }
+    }
+
+    Ok(data)
+}
+
+enum Format {
+    Ascii,
+    BinaryLittleEndian,
+    BinaryBigEndian,
+}
+
+#[derive(Clone, Copy)]
+enum DataType {
+    Int8,
+    UInt8,
+    Int16,
+    UInt16,
+    Int32,
+    UInt32,
+    Float32,
+    Float64,
+}
+
+impl DataType {
+    fn parse(name: &str) -> Option<Self> {
+        Some(match name {
+            "char" | "int8" => Self::Int8,
+            "uchar" | "uint8" => Self::UInt8,
+            "short" | "int16" => Self::Int16,
+            "ushort" | "uint16" => Self::UInt16,
+            "int" | "int32" => Self::Int32,
+            "uint" | "uint32" => Self::UInt32,
+            "float" | "float32" => Self::Float32,
+            "double" | "float64" => Self::Float64,
+            _ => return None,
+        })
+    }
+
+    fn size(&self) -> usize {
+        match self {
+            Self::Int8 | Self::UInt8 => 1,
+            Self::Int16 | Self::UInt16 => 2,
+            Self::Int32 | Self::UInt32 | Self::Float32 => 4,
+            Self::Float64 => 8,
+        }
+    }
+
+    /// Integer color channels span their full range, while float channels are already [0,1].
+    fn color_scale(&self) -> f64 {
+        match self {
+            Self::UInt8 | Self::Int8 => 1.0 / u8::MAX as f64,
+            Self::UInt16 | Self::Int16 => 1.0 / u16::MAX as f64,
+            Self::UInt32 | Self::Int32 => 1.0 / u32::MAX as f64,
+            Self::Float32 | Self::Float64 => 1.0,
+        }
+    }
+}
+
+struct Property {
+    name: String,
+    data_type: DataType,
+    /// Set for list properties, which are prefixed by an element count of this type.
+    count_type: Option<DataType>,
+}
+
+struct Element {
+    name: String,
+    count: usize,
+    properties: Vec<Property>,
+}
+
+struct Header {
+    format: Format,
+    elements: Vec<Element>,
+}
+
+/// Returns the header and the byte offset where the element data starts.
+fn parse_header(bytes: &[u8]) -> Result<(Header, usize), PlyError> {
+    let mut format = None;
+    let mut elements: Vec<Element> = vec![];
+    let mut position = 0;
+
+    for line_number in 1.. {
+        let invalid = |message: &str| {
+            PlyError::InvalidHeader {line: line_number, message: message.to_string()}
+        };
+        let data_type = |name: &str| {
+            DataType::parse(name).ok_or_else(|| invalid("Unknown property type"))
+        };
+        let end = bytes[position..]
+            .iter()
+            .position(|byte| *byte == b'\n')
+            .ok_or_else(|| invalid("Missing end_header"))?;
+        let line = std::str::from_utf8(&bytes[position..position + end])
+            .map_err(|_| invalid("Header is not valid ASCII"))?
+            .trim();
+        position += end + 1;
+
+        let fields: Vec<&str> = line.split_whitespace().collect();
+        match fields.as_slice() {
+            ["ply"] if line_number == 1 => {},
+            _ if line_number == 1 => return Err(invalid("File does not start with 'ply'")),
+            ["format", name, _version] => format = Some(match *name {
+                "ascii" => Format::Ascii,
+                "binary_little_endian" => Format::BinaryLittleEndian,
+                "binary_big_endian" => Format::BinaryBigEndian,
+                _ => return Err(invalid("Unknown format")),
+            }),
+            ["comment", ..] | ["obj_info", ..] | [] => {},
+            ["element", name, count] => elements.push(Element {
+                name: name.to_string(),
+                count: count.parse().map_err(|_| invalid("Invalid element count"))?,
+                properties: vec![],
+            }),
+            ["property", "list", count_type, item_type, name] => {
+                let element = elements
+                    .last_mut()
+                    .ok_or_else(|| invalid("Property outside an element"))?;
+                element.properties.push(Property {
+                    name: name.to_string(),
+                    data_type: data_type(item_type)?,
+                    count_type: Some(data_type(count_type)?),
+                });
+            },
+            ["property", scalar_type, name] => {
+                let element = elements
+                    .last_mut()
+                    .ok_or_else(|| invalid("Property outside an element"))?;
+                element.properties.push(Property {
+                    name: name.to_string(),
+                    data_type: data_type(scalar_type)?,
+                    count_type: None,
+                });
+            },
+            ["end_header"] => {
+                let format = format.ok_or_else(|| invalid("Missing format line"))?;
+                return Ok((Header {format, elements}, position));
+            },
+            _ => return Err(invalid("Unrecognized header line")),
+        }
+    }
+    unreachable!()
+}
+
+enum BodyError {
+    End,
+    Invalid(String),
+}
+
+enum Body<'a> {
+    Ascii(std::str::SplitAsciiWhitespace<'a>),
+    Binary {bytes: &'a [u8], big_endian: bool},
+}
+
+impl Body<'_> {
+    fn read_scalar(&mut self, data_type: DataType) -> Result<f64, BodyError> {
+        match self {
+            Self::Ascii(tokens) => {
+                let token = tokens.next().ok_or(BodyError::End)?;
+                token.parse().map_err(|_| BodyError::Invalid(format!("Invalid number '{}'", token)))
+            },
+            Self::Binary {bytes, big_endian} => {
+                let size = data_type.size();
+                if bytes.len() < size {return Err(BodyError::End);}
+                let (value, rest) = bytes.split_at(size);
+                *bytes = rest;
+
+                // Normalize to little endian so one decoder handles both byte orders
+                let mut buffer = [0; 8];
+                buffer[..size].copy_from_slice(value);
+                if *big_endian {buffer[..size].reverse();}
+                let [b0, b1, b2, b3, ..] = buffer;
+                Ok(match data_type {
+                    DataType::Int8 => b0 as i8 as f64,
+                    DataType::UInt8 => b0 as f64,
+                    DataType::Int16 => i16::from_le_bytes([b0, b1]) as f64,
+                    DataType::UInt16 => u16::from_le_bytes([b0, b1]) as f64,
+                    DataType::Int32 => i32::from_le_bytes([b0, b1, b2, b3]) as f64,
+                    DataType::UInt32 => u32::from_le_bytes([b0, b1, b2, b3]) as f64,
+                    DataType::Float32 => f32::from_le_bytes([b0, b1, b2, b3]) as f64,
+                    DataType::Float64 => f64::from_le_bytes(buffer),
+                })
+            },
+        }
+    }
+
+    fn read_property(
+        &mut self, 
+        property: &Property, 
+        values: &mut Vec<f64>
+    ) -> Result<(), BodyError> {
+        let count = match property.count_type {
+            Some(count_type) => {
+                let count = self.read_scalar(count_type)?;
+                if count < 0.0 {return Err(BodyError::Invalid("Negative list length".to_string()));}
+                // Also rejects NaN, which a float count type can hold
+                if count.fract() != 0.0 {
+                    return Err(BodyError::Invalid(format!("Invalid list length '{}'", count)));
+                }
+                count as usize
+            },
+            None => 1,
+        };
+        for _ in 0..count {
+            values.push(self.read_scalar(property.data_type)?);
+        }
+        Ok(())
+    }
+}
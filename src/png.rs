@@ -0,0 +1,154 @@
+use crate::{color::linear_to_srgb, deflate::zlib_compress, film::Film, interval::Interval};
+
+const SIGNATURE: [u8; 8] = [0x89, b'P', b'N', b'G', b'\r', b'\n', 0x1A, b'\n'];
+
+#[derive(Clone, Copy, PartialEq, Eq, Debug)]
+pub enum PngBitDepth {
+    Eight,
+    Sixteen,
+}
+
+#[derive(Clone, Copy, PartialEq, Eq, Debug)]
+pub struct PngOptions {
+    pub bit_depth: PngBitDepth,
+    /// Adds an alpha channel that is opaque wherever the film has samples.
+    pub alpha: bool,
+}
+
+impl Default for PngOptions {
+    fn default() -> Self {
+        Self {bit_depth: PngBitDepth::Eight, alpha: false}
+    }
+}
+
+/// Writes the film as a PNG, encoding linear radiance with the sRGB transfer curve and
+/// tagging the image as sRGB.
+pub fn write_png(
+    stream: &mut impl std::io::Write, 
+    film: &Film, 
+    options: &PngOptions
+) -> std::io::Result<()> {
+    let channels = if options.alpha {4} else {3};
+    let bytes_per_sample = match options.bit_depth {
+        PngBitDepth::Eight => 1,
+        PngBitDepth::Sixteen => 2,
+    };
+    let bytes_per_pixel = channels * bytes_per_sample;
+
+    let mut header = vec![];
+    header.extend_from_slice(&(film.width as u32).to_be_bytes());
+    header.extend_from_slice(&(film.height as u32).to_be_bytes());
+    header.push(8 * bytes_per_sample as u8);
+    // Color type 2 is truecolor and 6 is truecolor with alpha
+    header.push(if options.alpha {6} else {2});
+    // Deflate compression, adaptive filtering, no interlacing
+    header.extend_from_slice(&[0, 0, 0]);
+
+    let unit = Interval {min: 0.0, max: 1.0};
+    let max_value = match options.bit_depth {
+        PngBitDepth::Eight => u8::MAX as f32,
+        PngBitDepth::Sixteen => u16::MAX as f32,
+    };
+    let mut scanlines = Vec::with_capacity(film.height * (1 + film.width * bytes_per_pixel));
+    let mut previous_row = vec![0; film.width * bytes_per_pixel];
+    let mut row = Vec::with_capacity(film.width * bytes_per_pixel);
+
+    for y in 0..film.height {
+        row.clear();
+        for x in 0..film.width {
+            let pixel = film.pixel(x, y);
+            let color = [pixel.x, pixel.y, pixel.z]
+                .map(|channel| linear_to_srgb(unit.clamp(channel)));
+            let coverage = if film.sample_counts[film.index(x, y)] > 0 {1.0} else {0.0};
+
+            for sample in color.iter().chain(options.alpha.then_some(&coverage)) {
+                let quantized = (sample * max_value).round() as u16;
+                match options.bit_depth {
+                    PngBitDepth::Eight => row.push(quantized as u8),
+                    PngBitDepth::Sixteen => row.extend_from_slice(&quantized.to_be_bytes()),
+                }
+            }
+        }
+
+        let (filter, filtered) = best_filter(&row, &previous_row, bytes_per_pixel);
+        scanlines.push(filter);
+        scanlines.extend_from_slice(&filtered);
+        std::mem::swap(&mut row, &mut previous_row);
+    }
+
+    stream.write_all(&SIGNATURE)?;
+    write_chunk(stream, b"IHDR", &header)?;
+    // Perceptual rendering intent, with the gAMA and cHRM values the PNG spec pairs with sRGB
+    write_chunk(stream, b"sRGB", &[0])?;
+    write_chunk(stream, b"gAMA", &45455u32.to_be_bytes())?;
+    let chromaticities: [u32; 8] = [31270, 32900, 64000, 33000, 30000, 60000, 15000, 6000];
+    let chromaticities: Vec<u8> = chromaticities
+        .iter()
+        .flat_map(|value| value.to_be_bytes())
+        .collect();
+    write_chunk(stream, b"cHRM", &chromaticities)?;
+    write_chunk(stream, b"IDAT", &zlib_compress(&scanlines))?;
+    write_chunk(stream, b"IEND", &[])
+}
+
+fn write_chunk(
+    stream: &mut impl std::io::Write, 
+    kind: &[u8; 4], 
+    data: &[u8]
+) -> std::io::Result<()> {
+    stream.write_all(&(data.len() as u32).to_be_bytes())?;
+    stream.write_all(kind)?;
+    stream.write_all(data)?;
+    let crc = crc32(&[kind.as_slice(), data].concat());
+    stream.write_all(&crc.to_be_bytes())
+}
+
+/// Tries each PNG filter on the row and keeps the one with the smallest sum of absolute
+/// differences, which tends to compress best.
+fn best_filter(row: &[u8], previous_row: &[u8], bytes_per_pixel: usize) -> (u8, Vec<u8>) {
+    (0..5u8)
+        .map(|filter| {
+            let filtered: Vec<u8> = (0..row.len()).map(|i| {
+                let left = if i >= bytes_per_pixel {row[i - bytes_per_pixel]} else {0};
+                let up = previous_row[i];
+                let up_left = if i >= bytes_per_pixel {previous_row[i - bytes_per_pixel]} else {0};
+                let prediction = match filter {
+                    0 => 0,
+                    1 => left,
+                    2 => up,
+                    3 => ((left as u16 + up as u16) / 2) as u8,
+                    _ => paeth(left, up, up_left),
+                };
+                row[i].wrapping_sub(prediction)
+            }).collect();
+            (filter, filtered)
+        })
+        .min_by_key(|(_, filtered)| {
+            filtered.iter().map(|byte| (*byte as i8).unsigned_abs() as u32).sum::<u32>()
+        })
+        .expect("There is always at least one filter")
+}
+
+fn paeth(left: u8, up: u8, up_left: u8) -> u8 {
+    let estimate = left as i16 + up as i16 - up_left as i16;
+    let distance = |value: u8| (estimate - value as i16).abs();
+    if distance(left) <= distance(up) && distance(left) <= distance(up_left) {
+        left
+    } else if distance(up) <= distance(up_left) {
+        up
+    } else {
+        up_left
+    }
+}
+
+pub fn crc32(data: &[u8]) -> u32 {
+    let mut crc = u32::MAX;
+    for byte in data {
+        crc ^= *byte as u32;
+        for _ in 0..8 {
+            let mask = (crc & 1).wrapping_neg();
+            crc = (crc >> 1) ^ (0xEDB8_8320 & mask);
+        }
+    }
+    !crc
+}
@@ -0,0 +1,127 @@
+//! Closed-form real roots of monic polynomials up to degree four, after Schwarze's solvers in
+//! Graphics Gems I. Work is in `f64`, since the quartic loses too much precision in `f32`.
+
+use std::f64::consts::PI;
+
+/// Up to four real roots, in ascending order.
+#[derive(Clone, Copy, Default)]
+pub struct Roots {
+    values: [f64; 4],
+    count: usize,
+}
+
+impl Roots {
+    pub fn as_slice(&self) -> &[f64] {
+        &self.values[..self.count]
+    }
+
+    fn push(&mut self, root: f64) {
+        self.values[self.count] = root;
+        self.count += 1;
+    }
+
+    fn shifted(mut self, offset: f64) -> Self {
+        self.values[..self.count].iter_mut().for_each(|root| *root += offset);
+        self
+    }
+
+    fn sorted(mut self) -> Self {
+        self.values[..self.count].sort_by(f64::total_cmp);
+        self
+    }
+}
+
+/// Roots of `x² + b x + c`.
+pub fn solve_quadratic(b: f64, c: f64) -> Roots {
+    let mut roots = Roots::default();
+    let half_b = b / 2.0;
+    let discriminant = half_b * half_b - c;
+    if is_zero(discriminant) {
+        roots.push(-half_b);
+    } else if discriminant > 0.0 {
+        // Avoids cancelling when `half_b` dwarfs the square root
+        let q = -half_b - discriminant.sqrt().copysign(half_b);
+        roots.push(q);
+        if q != 0.0 {roots.push(c / q);}
+    }
+    roots.sorted()
+}
+
+/// Roots of `x³ + a x² + b x + c`.
+pub fn solve_cubic(a: f64, b: f64, c: f64) -> Roots {
+    // Substitute x = y - a/3 for the depressed cubic y³ + 3p y + 2q
+    let square_a = a * a;
+    let p = (b - square_a / 3.0) / 3.0;
+    let q = (2.0 / 27.0 * a * square_a - a * b / 3.0 + c) / 2.0;
+    let cube_p = p * p * p;
+    let discriminant = q * q + cube_p;
+
+    let mut roots = Roots::default();
+    if is_zero(discriminant) {
+        if is_zero(q) {
+            roots.push(0.0);
+        } else {
+            let u = (-q).cbrt();
+            roots.push(2.0 * u);
+            roots.push(-u);
+        }
+    } else if discriminant < 0.0 {
+        // Three distinct real roots, found trigonometrically
+        let phi = (-q / (-cube_p).sqrt()).clamp(-1.0, 1.0).acos() / 3.0;
+        let t = 2.0 * (-p).sqrt();
+        roots.push(t * phi.cos());
+        roots.push(-t * (phi + PI / 3.0).cos());
+        roots.push(-t * (phi - PI / 3.0).cos());
+    } else {
+        let sqrt_discriminant = discriminant.sqrt();
+        roots.push((sqrt_discriminant - q).cbrt() - (sqrt_discriminant + q).cbrt());
+    }
+    roots.shifted(-a / 3.0).sorted()
+}
+
+/// Roots of `x⁴ + a x³ + b x² + c x + d`, each polished with a Newton step against the
+/// original polynomial.
+pub fn solve_quartic(a: f64, b: f64, c: f64, d: f64) -> Roots {
+    // Substitute x = y - a/4 for the depressed quartic y⁴ + p y² + q y + r
+    let square_a = a * a;
+    let p = -3.0 / 8.0 * square_a + b;
+    let q = square_a * a / 8.0 - a * b / 2.0 + c;
+    let r = -3.0 / 256.0 * square_a * square_a + square_a * b / 16.0 - a * c / 4.0 + d;
+
+    let mut roots = Roots::default();
+    if is_zero(r) {
+        // y (y³ + p y + q) = 0
+        roots.push(0.0);
+        for &root in solve_cubic(0.0, p, q).as_slice() {roots.push(root);}
+    } else {
+        // Ferrari's method: a root of the resolvent cubic splits the quartic in two quadratics.
+        // The largest root keeps both square roots real when the quartic has real roots.
+        let resolvent = solve_cubic(-p / 2.0, -r, r * p / 2.0 - q * q / 8.0);
+        let z = resolvent.values[resolvent.count - 1];
+        let Some(u) = non_negative_sqrt(z * z - r) else {return roots;};
+        let Some(v) = non_negative_sqrt(2.0 * z - p) else {return roots;};
+        let v = if q < 0.0 {-v} else {v};
+        for &root in solve_quadratic(v, z - u).as_slice() {roots.push(root);}
+        for &root in solve_quadratic(-v, z + u).as_slice() {roots.push(root);}
+    }
+
+    let mut roots = roots.shifted(-a / 4.0);
+    for root in roots.values[..roots.count].iter_mut() {
+        let x = *root;
+        let value = (((x + a) * x + b) * x + c) * x + d;
+        let slope = ((4.0 * x + 3.0 * a) * x + 2.0 * b) * x + c;
+        if slope != 0.0 {*root -= value / slope;}
+    }
+    roots.sorted()
+}
+
+fn is_zero(x: f64) -> bool {
+    x.abs() < EPSILON
+}
+
+/// Treats slightly negative values as zero, since they are usually rounding.
+fn non_negative_sqrt(x: f64) -> Option<f64> {
+    if is_zero(x) {Some(0.0)} else if x > 0.0 {Some(x.sqrt())} else {None}
+}
+
+const EPSILON: f64 = 1e-9;
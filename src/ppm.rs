@@ -0,0 +1,11 @@
+use crate::{color::to_rgb8, film::Film};
+
+/// Writes the film as an ASCII (P3) PPM image.
+pub fn write_ppm(stream: &mut impl std::io::Write, film: &Film) -> std::io::Result<()> {
+    write!(stream, "P3\n{} {}\n255\n", film.width, film.height)?;
+    for pixel in film.pixels() {
+        let [red, green, blue] = to_rgb8(pixel);
+        writeln!(stream, "{} {} {}", red, green, blue)?;
+    }
+    Ok(())
+}
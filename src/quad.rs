@@ -0,0 +1,94 @@
+use crate::{
+    aabb::Aabb, 
+    hit_record::HitRecord, 
+    hittable::Hittable, 
+    interval::Interval, 
+    light::{Light, LightSample, area_to_solid_angle, same_distance}, 
+    material_arena::MaterialId, 
+    ray::Ray, 
+    vec::{Point3, Vec3},
+};
+
+/// A parallelogram with corner `q` and edges `u` and `v`.
+#[derive(Clone)]
+pub struct Quad {
+    pub q: Point3,
+    pub u: Vec3,
+    pub v: Vec3,
+    pub material: MaterialId,
+    normal: Vec3,
+    d: f32,
+    w: Vec3,
+}
+
+impl Quad {
+    pub fn new(q: Point3, u: Vec3, v: Vec3, material: MaterialId) -> Self {
+        let n = u.cross(&v);
+        let normal = n.unit_vector();
+        Self {
+            q,
+            u,
+            v,
+            material,
+            normal,
+            d: normal.dot(&q),
+            w: n / n.dot(&n),
+        }
+    }
+
+    fn area(&self) -> f32 {
+        self.u.cross(&self.v).length()
+    }
+}
+
+impl Hittable for Quad {
+    fn hit(&self, ray: &Ray, ray_t: Interval, hit_record: &mut HitRecord) -> bool {
+        // No hit if the ray is parallel to the plane
+        let denominator = self.normal.dot(&ray.direction);
+        if denominator.abs() < 1e-8 {return false;}
+
+        let t = (self.d - self.normal.dot(&ray.origin)) / denominator;
+        if !ray_t.surrounds(t) {return false;}
+
+        // Express the hit point in the quad's own (alpha, beta) coordinates
+        let intersection = ray.at(t);
+        let planar_hit_vector = intersection - self.q;
+        let alpha = self.w.dot(&planar_hit_vector.cross(&self.v));
+        let beta = self.w.dot(&self.u.cross(&planar_hit_vector));
+
+        let unit = Interval {min: 0.0, max: 1.0};
+        if !unit.contains(alpha) || !unit.contains(beta) {return false;}
+
+        hit_record.t = t;
+        hit_record.point = intersection;
+        hit_record.set_face_normal(ray, &self.normal);
+        (hit_record.u, hit_record.v) = (alpha, beta);
+        hit_record.material = self.material;
+        true
+    }
+
+    fn bounding_box(&self) -> Aabb {
+        let diagonal_one = Aabb::from_points(&self.q, &(self.q + self.u + self.v));
+        let diagonal_two = Aabb::from_points(&(self.q + self.u), &(self.q + self.v));
+        Aabb::enclosing(&diagonal_one, &diagonal_two).pad(1e-4)
+    }
+}
+
+impl Light for Quad {
+    fn sample(&self, origin: &Point3, u: f32, v: f32) -> Option<LightSample> {
+        let to_light = self.q + u * self.u + v * self.v - *origin;
+        let pdf = area_to_solid_angle(1.0 / self.area(), &to_light, &self.normal);
+        if pdf <= 0.0 {return None;}
+        let distance = to_light.length();
+        Some(LightSample {direction: to_light / distance, distance, pdf})
+    }
+
+    fn pdf(&self, ray: &Ray, t: f32) -> f32 {
+        let mut hit_record = HitRecord::default();
+        let ray_t = Interval {min: 0.001, max: f32::INFINITY};
+        if !self.hit(ray, ray_t, &mut hit_record) || !same_distance(hit_record.t, t) {
+            return 0.0;
+        }
+        area_to_solid_angle(1.0 / self.area(), &(hit_record.point - ray.origin), &self.normal)
+    }
+}
@@ -0,0 +1,79 @@
+use crate::{mat4::Mat4, vec::Vec3};
+
+/// A rotation stored as a unit quaternion, which unlike a matrix can be interpolated without
+/// distorting the object.
+#[derive(Clone, Copy, Debug)]
+pub struct Quaternion {
+    pub w: f32,
+    pub x: f32,
+    pub y: f32,
+    pub z: f32,
+}
+
+impl Default for Quaternion {
+    fn default() -> Self {
+        Self {w: 1.0, x: 0.0, y: 0.0, z: 0.0}
+    }
+}
+
+impl Quaternion {
+    /// The same rotation as `Mat4::rotation(axis, degrees)`.
+    pub fn from_axis_angle(axis: &Vec3, degrees: f32) -> Self {
+        let (sin, cos) = (degrees.to_radians() / 2.0).sin_cos();
+        let axis = axis.unit_vector() * sin;
+        Self {w: cos, x: axis.x, y: axis.y, z: axis.z}
+    }
+
+    pub fn dot(&self, rhs: &Self) -> f32 {
+        self.w * rhs.w + self.x * rhs.x + self.y * rhs.y + self.z * rhs.z
+    }
+
+    /// The angle in radians of the smallest rotation that turns `self` into `other`.
+    pub fn angle_to(&self, other: &Self) -> f32 {
+        2.0 * self.dot(other).abs().min(1.0).acos()
+    }
+
+    /// Spherical linear interpolation, which turns at a constant rate along the shorter way
+    /// around from `self` at 0 to `other` at 1.
+    pub fn slerp(&self, other: &Self, fraction: f32) -> Self {
+        let mut cos = self.dot(other);
+        let mut other = *other;
+        // q and -q are the same rotation, and the one closer to `self` takes the short way
+        if cos < 0.0 {
+            other = other.scaled(-1.0);
+            cos = -cos;
+        }
+
+        let (from_weight, to_weight) = if cos > 0.9995 {
+            // Nearly equal rotations divide by a vanishing sine, so blend linearly instead
+            (1.0 - fraction, fraction)
+        } else {
+            let angle = cos.acos();
+            let sin = angle.sin();
+            (((1.0 - fraction) * angle).sin() / sin, (fraction * angle).sin() / sin)
+        };
+        let blended = Self {
+            w: from_weight * self.w + to_weight * other.w,
+            x: from_weight * self.x + to_weight * other.x,
+            y: from_weight * self.y + to_weight * other.y,
+            z: from_weight * self.z + to_weight * other.z,
+        };
+        blended.scaled(1.0 / blended.dot(&blended).sqrt())
+    }
+
+    pub fn to_mat4(&self) -> Mat4 {
+        let Self {w, x, y, z} = *self;
+        Mat4 {
+            rows: [
+                [1.0 - 2.0 * (y * y + z * z), 2.0 * (x * y - w * z), 2.0 * (x * z + w * y), 0.0],
+                [2.0 * (x * y + w * z), 1.0 - 2.0 * (x * x + z * z), 2.0 * (y * z - w * x), 0.0],
+                [2.0 * (x * z - w * y), 2.0 * (y * z + w * x), 1.0 - 2.0 * (x * x + y * y), 0.0],
+                [0.0, 0.0, 0.0, 1.0],
+            ],
+        }
+    }
+
+    fn scaled(&self, factor: f32) -> Self {
+        Self {w: self.w * factor, x: self.x * factor, y: self.y * factor, z: self.z * factor}
+    }
+}
@@ -4,10 +4,13 @@ use super::vec::{Point3, Vec3};
 pub struct Ray {
     pub origin: Point3,
     pub direction: Vec3,
+    /// The moment within the camera's shutter interval that the ray samples, which places
+    /// moving objects.
+    pub time: f32,
 }
 
 impl Ray {
-    pub fn at(&self, time: f32) -> Point3 {
-        self.origin + time * self.direction
+    pub fn at(&self, t: f32) -> Point3 {
+        self.origin + t * self.direction
     }
-}
\ No newline at end of file
+}
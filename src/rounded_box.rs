@@ -0,0 +1,157 @@
+use crate::{
+    aabb::Aabb, 
+    hit_record::HitRecord, 
+    hittable::Hittable, 
+    interval::Interval, 
+    material_arena::MaterialId, 
+    quad::Quad, 
+    ray::Ray, 
+    util::nearest_root, 
+    vec::{Point3, Vec3},
+};
+
+/// An axis-aligned box whose edges and corners are rounded off by `radius`, made of six flat
+/// faces, twelve quarter cylinders and eight sphere octants. Each part only keeps hits on its
+/// own stretch of the surface, so there are no stray surfaces inside for refracted rays to hit.
+///
+/// Texture coordinates project the point onto the box side the normal faces most, spanning
+/// [0,1] across that side.
+#[derive(Clone)]
+pub struct RoundedBox {
+    pub material: MaterialId,
+    radius: f32,
+    /// The box traced by the centers of the rounded edges and corners.
+    inner: Aabb,
+    outer: Aabb,
+    faces: [Quad; 6],
+}
+
+impl RoundedBox {
+    /// Takes any two opposite corners. `radius` is limited to half the box's smallest size.
+    pub fn new(a: &Point3, b: &Point3, radius: f32, material: MaterialId) -> Self {
+        let outer = Aabb::from_points(a, b);
+        let smallest = outer.x.size().min(outer.y.size()).min(outer.z.size());
+        let radius = radius.clamp(0.0, smallest / 2.0);
+        let inner = Aabb {
+            x: outer.x.expand(-2.0 * radius),
+            y: outer.y.expand(-2.0 * radius),
+            z: outer.z.expand(-2.0 * radius),
+        };
+
+        let dx = Vec3 {x: inner.x.size(), y: 0.0, z: 0.0};
+        let dy = Vec3 {x: 0.0, y: inner.y.size(), z: 0.0};
+        let dz = Vec3 {x: 0.0, y: 0.0, z: inner.z.size()};
+        let corner = |x: f32, y: f32, z: f32| Point3 {x, y, z};
+        let faces = [
+            Quad::new(corner(outer.x.max, inner.y.min, inner.z.min), dy, dz, material),
+            Quad::new(corner(outer.x.min, inner.y.min, inner.z.min), dz, dy, material),
+            Quad::new(corner(inner.x.min, outer.y.max, inner.z.min), dz, dx, material),
+            Quad::new(corner(inner.x.min, outer.y.min, inner.z.min), dx, dz, material),
+            Quad::new(corner(inner.x.min, inner.y.min, outer.z.max), dx, dy, material),
+            Quad::new(corner(inner.x.min, inner.y.min, outer.z.min), dy, dx, material),
+        ];
+        Self {material, radius, inner, outer, faces}
+    }
+
+    pub fn radius(&self) -> f32 {
+        self.radius
+    }
+
+    /// Hits the sphere octant or quarter cylinder that `signs` points to from the middle of the
+    /// box, narrowing `ray_t` and returning the outward normal. An edge has a sign of 0 along its
+    /// own axis, which drops that component and turns the corner's sphere into a cylinder.
+    fn hit_rounded(&self, ray: &Ray, ray_t: &mut Interval, signs: &Vec3) -> Option<Vec3> {
+        if self.radius <= 0.0 {return None;}
+        let mut center = Point3::default();
+        for axis in 0..3 {
+            let bounds = self.inner.axis(axis);
+            center[axis] = if signs[axis] < 0.0 {bounds.min} else {bounds.max};
+        }
+        let mask = Vec3 {x: signs.x.abs(), y: signs.y.abs(), z: signs.z.abs()};
+        let offset = (ray.origin - center) * mask;
+        let direction = ray.direction * mask;
+
+        // Seams get a little overlap, so no ray slips between two parts
+        let tolerance = 1e-3 * self.radius;
+        let on_part = |t: f32| {
+            let point = ray.at(t);
+            (0..3).all(|axis| if signs[axis] == 0.0 {
+                self.inner.axis(axis).contains(point[axis])
+            } else {
+                (point[axis] - center[axis]) * signs[axis] >= -tolerance
+            })
+        };
+        let a = direction.length_squared();
+        let half_b = offset.dot(&direction);
+        let c = offset.length_squared() - self.radius.powi(2);
+        let t = nearest_root(a, half_b, c, *ray_t, on_part)?;
+        ray_t.max = t;
+        Some((ray.at(t) - center) * mask / self.radius)
+    }
+
+    fn box_uv(&self, point: &Point3, normal: &Vec3) -> (f32, f32) {
+        let magnitudes = [normal.x.abs(), normal.y.abs(), normal.z.abs()];
+        let facing = if magnitudes[0] > magnitudes[1] {
+            if magnitudes[0] > magnitudes[2] {0} else {2}
+        } else if magnitudes[1] > magnitudes[2] {1} else {2};
+        let across = |axis: usize| {
+            let bounds = self.outer.axis(axis);
+            (point[axis] - bounds.min) / bounds.size().max(f32::MIN_POSITIVE)
+        };
+        (across((facing + 1) % 3), across((facing + 2) % 3))
+    }
+}
+
+impl Hittable for RoundedBox {
+    fn hit(&self, ray: &Ray, mut ray_t: Interval, hit_record: &mut HitRecord) -> bool {
+        if !self.bounding_box().hit(ray, ray_t) {return false;}
+        let mut hit_anything = false;
+        for face in &self.faces {
+            if face.hit(ray, ray_t, hit_record) {
+                hit_anything = true;
+                ray_t.max = hit_record.t;
+            }
+        }
+
+        // The corners, then the edges along each axis
+        let mut outward_normal = None;
+        let sides = [-1.0, 1.0];
+        for x in sides {
+            for y in sides {
+                for z in sides {
+                    let signs = Vec3 {x, y, z};
+                    if let Some(normal) = self.hit_rounded(ray, &mut ray_t, &signs) {
+                        outward_normal = Some(normal);
+                    }
+                }
+            }
+        }
+        for axis in 0..3 {
+            for first in sides {
+                for second in sides {
+                    let mut signs = Vec3::default();
+                    signs[(axis + 1) % 3] = first;
+                    signs[(axis + 2) % 3] = second;
+                    if let Some(normal) = self.hit_rounded(ray, &mut ray_t, &signs) {
+                        outward_normal = Some(normal);
+                    }
+                }
+            }
+        }
+
+        if let Some(outward_normal) = outward_normal {
+            hit_record.t = ray_t.max;
+            hit_record.point = ray.at(ray_t.max);
+            hit_record.set_face_normal(ray, &outward_normal);
+        } else if !hit_anything {
+            return false;
+        }
+        (hit_record.u, hit_record.v) = self.box_uv(&hit_record.point, &hit_record.normal);
+        hit_record.material = self.material;
+        true
+    }
+
+    fn bounding_box(&self) -> Aabb {
+        self.outer.pad(1e-4)
+    }
+}
@@ -0,0 +1,61 @@
+//! A small, seedable PCG32 random number generator (O'Neill 2014).
+
+use std::hash::{BuildHasher, Hasher};
+
+const MULTIPLIER: u64 = 6_364_136_223_846_793_005;
+
+/// Uniform random numbers for sampling. Every pixel sample gets its own sampler from
+/// `for_sample`, so a seeded render comes out the same however its work is split across threads.
+#[derive(Clone, Debug)]
+pub struct Sampler {
+    state: u64,
+    increment: u64,
+}
+
+impl Sampler {
+    /// Starts the sequence `stream` of generator `seed`. Different streams don't overlap.
+    pub fn new(seed: u64, stream: u64) -> Self {
+        let mut sampler = Self {state: 0, increment: stream << 1 | 1};
+        sampler.next_u32();
+        sampler.state = sampler.state.wrapping_add(seed);
+        sampler.next_u32();
+        sampler
+    }
+
+    /// The sampler for sample `sample` of the pixel numbered `pixel`.
+    pub fn for_sample(seed: u64, pixel: u64, sample: u64) -> Self {
+        Self::new(mix(seed ^ mix(sample)), pixel)
+    }
+
+    pub fn next_u32(&mut self) -> u32 {
+        let state = self.state;
+        self.state = state.wrapping_mul(MULTIPLIER).wrapping_add(self.increment);
+        let xorshifted = (((state >> 18) ^ state) >> 27) as u32;
+        xorshifted.rotate_right((state >> 59) as u32)
+    }
+
+    /// A uniform number in [0, 1).
+    pub fn next_f32(&mut self) -> f32 {
+        // The top 24 bits fill an f32 mantissa exactly
+        (self.next_u32() >> 8) as f32 / (1 << 24) as f32
+    }
+
+    /// A uniform number in [min, max).
+    pub fn range(&mut self, min: f32, max: f32) -> f32 {
+        min + (max - min) * self.next_f32()
+    }
+}
+
+/// A seed that differs on every call, for renders that don't ask to be reproducible.
+pub fn entropy_seed() -> u64 {
+    std::collections::hash_map::RandomState::new().build_hasher().finish()
+}
+
+/// Scrambles the bits of a seed (SplitMix64's finalizer), so nearby seeds give unrelated
+/// sequences.
+fn mix(value: u64) -> u64 {
+    let mut value = value.wrapping_add(0x9E37_79B9_7F4A_7C15);
+    value = (value ^ (value >> 30)).wrapping_mul(0xBF58_476D_1CE4_E5B9);
+    value = (value ^ (value >> 27)).wrapping_mul(0x94D0_49BB_1331_11EB);
+    value ^ (value >> 31)
+}
@@ -0,0 +1,785 @@
+use std::{
+    collections::HashMap, 
+    path::{Path, PathBuf}, 
+    sync::Arc,
+};
+
+use crate::{
+    animated::Animated, 
+    animated_transform::{AnimatedTransform, Keyframe}, 
+    camera::Camera, 
+    checker_texture::CheckerTexture, 
+    color::Color, 
+    cone::Cone, 
+    constant_environment::ConstantEnvironment, 
+    cuboid::Cuboid, 
+    cylinder::Cylinder, 
+    dielectric::Dielectric, 
+    diffuse_light::DiffuseLight, 
+    disk::Disk, 
+    environment::Environment, 
+    environment_light::EnvironmentLight, 
+    gradient_environment::GradientEnvironment, 
+    hittable::Hittable, 
+    hittable_list::HittableList, 
+    image_environment::ImageEnvironment, 
+    image_texture::ImageTexture, 
+    integrator::Integrator, 
+    interval::Interval, 
+    json::{self, Json, JsonError, JsonValue}, 
+    lambertian::Lambertian, 
+    light::Light, 
+    light_list::LightList, 
+    marble_texture::MarbleTexture, 
+    material::Material, 
+    material_arena::{MaterialArena, MaterialId}, 
+    metal::Metal, 
+    moving_sphere::MovingSphere, 
+    noise_texture::NoiseTexture, 
+    obj::read_obj, 
+    plane::Plane, 
+    ply::read_ply, 
+    quad::Quad, 
+    quaternion::Quaternion, 
+    rounded_box::RoundedBox, 
+    solid_color::SolidColor, 
+    sphere::Sphere, 
+    stl::read_stl, 
+    texture::Texture, 
+    torus::Torus, 
+    transform::Transform, 
+    transformed::Transformed, 
+    triangle::Triangle, 
+    triangle_mesh::{MeshData, TriangleMesh}, 
+    vec::Vec3, 
+    wood_texture::WoodTexture,
+};
+
+/// Everything needed to render an image: the objects, the materials they are made of, the
+/// lights among them, and the camera looking at them.
+pub struct Scene {
+    pub camera: Camera,
+    pub world: HittableList,
+    pub materials: MaterialArena,
+    pub lights: LightList,
+    /// Problems worked around while loading, such as a mesh's missing MTL library, each tagged
+    /// with its line. Loading never prints them.
+    pub warnings: Vec<String>,
+}
+
+#[derive(Debug)]
+pub enum SceneError {
+    Io(std::io::Error),
+    Invalid {line: usize, message: String},
+}
+
+impl std::fmt::Display for SceneError {
+    fn fmt(&self, f: &mut std::fmt::Formatter<'_>) -> std::fmt::Result {
+        match self {
+            Self::Io(error) => write!(f, "{}", error),
+            Self::Invalid {line, message} => write!(f, "line {}: {}", line, message),
+        }
+    }
+}
+
+impl std::error::Error for SceneError {
+    fn source(&self) -> Option<&(dyn std::error::Error + 'static)> {
+        match self {
+            Self::Io(error) => Some(error),
+            Self::Invalid {..} => None,
+        }
+    }
+}
+
+impl From<std::io::Error> for SceneError {
+    fn from(error: std::io::Error) -> Self {
+        Self::Io(error)
+    }
+}
+
+impl From<JsonError> for SceneError {
+    fn from(error: JsonError) -> Self {
+        Self::Invalid {line: error.line, message: error.message}
+    }
+}
+
+impl Scene {
+    /// Loads a JSON scene description. Relative file names inside it, such as meshes and image
+    /// textures, are resolved from the scene file's directory.
+    pub fn load(path: impl AsRef<Path>) -> Result<Self, SceneError> {
+        let path = path.as_ref();
+        let source = std::fs::read_to_string(path)?;
+        Self::parse(&source, path.parent().unwrap_or(Path::new("")))
+    }
+
+    /// Builds a scene from JSON with a `camera` object, optional `textures`, `materials` and
+    /// `objects` tables of named definitions, and a `world` array of objects to render.
+    pub fn parse(source: &str, directory: &Path) -> Result<Self, SceneError> {
+        let root = json::parse(source)?;
+        let mut root = Table::new(&root)?;
+        let mut loader = Loader {
+            directory: directory.to_path_buf(),
+            textures: HashMap::new(),
+            materials: HashMap::new(),
+            objects: HashMap::new(),
+            material_arena: MaterialArena::default(),
+            warnings: vec![],
+        };
+
+        if let Some(textures) = root.get("textures") {
+            for (name, _, definition) in entries(textures)? {
+                let texture = loader.texture_definition(definition)?;
+                loader.textures.insert(name.clone(), texture);
+            }
+        }
+        if let Some(materials) = root.get("materials") {
+            for (name, _, definition) in entries(materials)? {
+                let material = loader.material_definition(definition)?;
+                loader.materials.insert(name.clone(), material);
+            }
+        }
+        if let Some(objects) = root.get("objects") {
+            for (name, _, definition) in entries(objects)? {
+                let object = loader.object(definition, None)?;
+                loader.objects.insert(name.clone(), object);
+            }
+        }
+
+        // Emissive spheres, quads and disks placed in the world are registered as lights. Other
+        // emissive shapes, and any shape placed through an instance, are only found by chance
+        let mut world = HittableList::default();
+        let mut lights = LightList::default();
+        for object in array(root.required("world")?)? {
+            world.add(loader.object(object, Some(&mut lights))?);
+        }
+
+        let camera = match root.get("camera") {
+            Some(camera) => parse_camera(camera, directory, &mut lights)?,
+            None => Camera::default(),
+        };
+        root.finish()?;
+
+        Ok(Self {
+            camera,
+            world,
+            materials: loader.material_arena,
+            lights,
+            warnings: loader.warnings,
+        })
+    }
+}
+
+fn parse_camera(
+    json: &Json, 
+    directory: &Path, 
+    lights: &mut LightList
+) -> Result<Camera, SceneError> {
+    let mut table = Table::new(json)?;
+    let mut camera = Camera::default();
+
+    if let Some(value) = table.get("aspect_ratio") {camera.aspect_ratio = number(value)?;}
+    if let Some(image) = table.get("image") {
+        let mut image = Table::new(image)?;
+        if let Some(width) = image.get("width") {
+            camera.image.width = nonzero(width, "'width' must be at least 1")?;
+        }
+        if let Some(height) = image.get("height") {
+            let message = "'height' must be at least 1, leave it out to derive it from the width";
+            camera.image.height = nonzero(height, message)?;
+        }
+        image.finish()?;
+    }
+    if let Some(value) = table.get("samples_per_pixel") {
+        camera.samples_per_pixel = nonzero(value, "'samples_per_pixel' must be at least 1")?;
+    }
+    if let Some(value) = table.get("max_depth") {camera.max_depth = int32(value)?;}
+    if let Some(value) = table.get("russian_roulette_depth") {
+        camera.russian_roulette_depth = int32(value)?;
+    }
+    if let Some(value) = table.get("vertical_field_of_view") {
+        camera.vertical_field_of_view = number(value)?;
+    }
+    if let Some(value) = table.get("look_from") {camera.look_from = vec3(value)?;}
+    if let Some(value) = table.get("look_at") {camera.look_at = vec3(value)?;}
+    if let Some(value) = table.get("vertical_up") {camera.vertical_up = vec3(value)?;}
+    if let Some(value) = table.get("defocus_angle") {camera.defocus_angle = number(value)?;}
+    if let Some(value) = table.get("focus_distance") {camera.focus_distance = number(value)?;}
+    if let Some(value) = table.get("shutter") {
+        camera.shutter = match array(value)? {
+            [open, close] => {
+                let shutter = Interval {min: number(open)?, max: number(close)?};
+                // Moving spheres travel over exactly this span, and keyframes share its scale
+                if !(0.0 <= shutter.min && shutter.min <= shutter.max && shutter.max <= 1.0) {
+                    let message = "Shutter must open and then close between times 0 and 1";
+                    return Err(invalid(value, message));
+                }
+                shutter
+            },
+            _ => return Err(invalid(value, "Expected an array of 2 numbers")),
+        };
+    }
+    if let Some(value) = table.get("environment") {
+        camera.environment = parse_environment(value, directory, lights)?;
+    }
+    if let Some(value) = table.get("thread_count") {camera.thread_count = integer(value)?;}
+    if let Some(value) = table.get("tile_size") {camera.tile_size = int32(value)?;}
+    if let Some(value) = table.get("seed") {camera.seed = Some(integer(value)? as u64);}
+    if let Some(value) = table.get("integrator") {
+        camera.integrator = Integrator::from_name(string(value)?)
+            .ok_or_else(|| invalid(value, "Unknown integrator"))?;
+    }
+    table.finish()?;
+
+    Ok(camera)
+}
+
+/// Accepts "sky", a constant color array, or an environment definition. Image environments are
+/// also registered as lights.
+fn parse_environment(
+    json: &Json, 
+    directory: &Path, 
+    lights: &mut LightList
+) -> Result<Arc<dyn Environment>, SceneError> {
+    match &json.value {
+        JsonValue::String(name) if name == "sky" => {
+            return Ok(Arc::<GradientEnvironment>::default());
+        },
+        JsonValue::Array(_) => return Ok(Arc::<_>::new(ConstantEnvironment {color: vec3(json)?})),
+        _ => {},
+    }
+
+    let mut table = Table::new(json)?;
+    let environment: Arc<dyn Environment> = match table.kind()? {
+        "constant" => Arc::<_>::new(ConstantEnvironment {color: vec3(table.required("color")?)?}),
+        "gradient" => {
+            let mut gradient = GradientEnvironment::default();
+            if let Some(bottom) = table.get("bottom") {gradient.bottom = vec3(bottom)?;}
+            if let Some(top) = table.get("top") {gradient.top = vec3(top)?;}
+            Arc::<_>::new(gradient)
+        },
+        "image" => {
+            let file = table.required("file")?;
+            let path = directory.join(string(file)?);
+            let environment = ImageEnvironment::load(
+                &path, 
+                table.optional_number("rotation", 0.0)?, 
+                table.optional_number("intensity", 1.0)?,
+            ).map_err(|error| {
+                invalid(file, format!("Failed to load '{}': {}", path.display(), error))
+            })?;
+            let environment = Arc::<_>::new(environment);
+            lights.add(Arc::<_>::new(EnvironmentLight {environment: environment.clone()}));
+            environment
+        },
+        kind => return Err(invalid(json, format!("Unknown environment type '{}'", kind))),
+    };
+    table.finish()?;
+    Ok(environment)
+}
+
+struct Loader {
+    directory: PathBuf,
+    textures: HashMap<String, Arc<dyn Texture>>,
+    materials: HashMap<String, MaterialId>,
+    objects: HashMap<String, Arc<dyn Hittable>>,
+    /// Every material built so far, named or inline.
+    material_arena: MaterialArena,
+    warnings: Vec<String>,
+}
+
+impl Loader {
+    /// Accepts a color array, the name of a texture, or an inline texture definition.
+    fn texture(&self, json: &Json) -> Result<Arc<dyn Texture>, SceneError> {
+        match &json.value {
+            JsonValue::Array(_) => Ok(Arc::<_>::new(SolidColor {albedo: vec3(json)?})),
+            JsonValue::String(name) => self.textures
+                .get(name)
+                .cloned()
+                .ok_or_else(|| invalid(json, format!("Unknown texture '{}'", name))),
+            _ => self.texture_definition(json),
+        }
+    }
+
+    fn texture_definition(&self, json: &Json) -> Result<Arc<dyn Texture>, SceneError> {
+        let mut table = Table::new(json)?;
+        let texture: Arc<dyn Texture> = match table.kind()? {
+            "solid" => Arc::<_>::new(SolidColor {albedo: vec3(table.required("color")?)?}),
+            "checker" => Arc::<_>::new(CheckerTexture {
+                inverse_scale: 1.0 / number(table.required("scale")?)?,
+                even: self.texture(table.required("even")?)?,
+                odd: self.texture(table.required("odd")?)?,
+            }),
+            "image" => {
+                let file = table.required("file")?;
+                let path = self.directory.join(string(file)?);
+                let image = ImageTexture::load(&path).map_err(|error| {
+                    invalid(file, format!("Failed to load '{}': {}", path.display(), error))
+                })?;
+                Arc::<_>::new(image)
+            },
+            "noise" => Arc::<_>::new(NoiseTexture::new(table.optional_number("scale", 1.0)?)),
+            "marble" => {
+                let color = table.get("color").map(vec3).transpose()?;
+                let mut marble = MarbleTexture::new(
+                    color.unwrap_or(Color {x: 1.0, y: 1.0, z: 1.0}), 
+                    table.optional_number("scale", 1.0)?,
+                );
+                if let Some(depth) = table.get("turbulence_depth") {
+                    marble.turbulence_depth = integer(depth)?;
+                }
+                Arc::<_>::new(marble)
+            },
+            "wood" => {
+                let mut wood = WoodTexture::new(
+                    vec3(table.required("light")?)?, 
+                    vec3(table.required("dark")?)?, 
+                    table.optional_number("rings_per_unit", 8.0)?,
+                );
+                if let Some(depth) = table.get("turbulence_depth") {
+                    wood.turbulence_depth = integer(depth)?;
+                }
+                Arc::<_>::new(wood)
+            },
+            kind => return Err(invalid(json, format!("Unknown texture type '{}'", kind))),
+        };
+        table.finish()?;
+        Ok(texture)
+    }
+
+    /// Accepts the name of a material or an inline material definition.
+    fn material(&mut self, json: &Json) -> Result<MaterialId, SceneError> {
+        match &json.value {
+            JsonValue::String(name) => self.materials
+                .get(name)
+                .copied()
+                .ok_or_else(|| invalid(json, format!("Unknown material '{}'", name))),
+            _ => self.material_definition(json),
+        }
+    }
+
+    /// Whether a material name or definition is emissive, without adding it to the arena.
+    fn is_emissive(&self, json: &Json) -> bool {
+        match &json.value {
+            JsonValue::String(name) => self.materials
+                .get(name)
+                .is_some_and(|&material| self.material_arena[material].is_emissive()),
+            JsonValue::Object(entries) => entries.iter().any(|(key, _, value)| {
+                key == "type"
+                    && matches!(&value.value, JsonValue::String(kind) if kind == "diffuse_light")
+            }),
+            _ => false,
+        }
+    }
+
+    /// Builds a material and adds it to the arena.
+    fn material_definition(&mut self, json: &Json) -> Result<MaterialId, SceneError> {
+        let mut table = Table::new(json)?;
+        let material: Box<dyn Material> = match table.kind()? {
+            "lambertian" => {
+                Box::<_>::new(Lambertian::from_texture(self.texture(table.required("albedo")?)?))
+            },
+            "metal" => Box::<_>::new(Metal::from_texture(
+                self.texture(table.required("albedo")?)?, 
+                table.optional_number("fuzz", 0.0)?,
+            )),
+            "dielectric" => Box::<_>::new(Dielectric {ir: number(table.required("ir")?)?}),
+            "diffuse_light" => {
+                Box::<_>::new(DiffuseLight::from_texture(self.texture(table.required("emit")?)?))
+            },
+            kind => return Err(invalid(json, format!("Unknown material type '{}'", kind))),
+        };
+        table.finish()?;
+        Ok(self.material_arena.add(material))
+    }
+
+    /// Builds an object, registering emissive shapes in `lights` if given.
+    fn object(
+        &mut self, 
+        json: &Json, 
+        mut lights: Option<&mut LightList>
+    ) -> Result<Arc<dyn Hittable>, SceneError> {
+        let mut table = Table::new(json)?;
+        let kind = table.kind()?;
+        // Spheres, quads and disks register themselves below, and groups and instances have no
+        // material of their own
+        let is_sampled = matches!(kind, "sphere" | "quad" | "disk" | "group" | "instance");
+        if lights.is_some() && !is_sampled {
+            if let Some(material) = table.get("material").filter(|&json| self.is_emissive(json)) {
+                self.warnings.push(format!(
+                    "line {}: Emissive '{}' can't be sampled as a light, only spheres, quads and \
+                    disks can, so it will be noisy", 
+                    material.line, 
+                    kind
+                ));
+            }
+        }
+        let object: Arc<dyn Hittable> = match kind {
+            "sphere" => {
+                let sphere = Sphere {
+                    center: vec3(table.required("center")?)?,
+                    radius: number(table.required("radius")?)?,
+                    material: self.material(table.required("material")?)?,
+                };
+                let is_emissive = self.material_arena[sphere.material].is_emissive();
+                with_light(sphere, is_emissive, lights)
+            },
+            "moving_sphere" => Arc::<_>::new(MovingSphere {
+                start_center: vec3(table.required("start_center")?)?,
+                end_center: vec3(table.required("end_center")?)?,
+                radius: number(table.required("radius")?)?,
+                material: self.material(table.required("material")?)?,
+            }),
+            "quad" => {
+                let quad = Quad::new(
+                    vec3(table.required("q")?)?,
+                    vec3(table.required("u")?)?,
+                    vec3(table.required("v")?)?,
+                    self.material(table.required("material")?)?,
+                );
+                let is_emissive = self.material_arena[quad.material].is_emissive();
+                with_light(quad, is_emissive, lights)
+            },
+            "triangle" => Arc::<_>::new(Triangle {
+                a: vec3(table.required("a")?)?,
+                b: vec3(table.required("b")?)?,
+                c: vec3(table.required("c")?)?,
+                material: self.material(table.required("material")?)?,
+            }),
+            "plane" => Arc::<_>::new(Plane {
+                point: vec3(table.required("point")?)?,
+                normal: vec3(table.required("normal")?)?,
+                material: self.material(table.required("material")?)?,
+            }),
+            "disk" => {
+                let disk = Disk {
+                    center: vec3(table.required("center")?)?,
+                    normal: vec3(table.required("normal")?)?,
+                    radius: number(table.required("radius")?)?,
+                    material: self.material(table.required("material")?)?,
+                };
+                let is_emissive = self.material_arena[disk.material].is_emissive();
+                with_light(disk, is_emissive, lights)
+            },
+            "box" => Arc::<_>::new(Cuboid::new(
+                &vec3(table.required("a")?)?,
+                &vec3(table.required("b")?)?,
+                self.material(table.required("material")?)?,
+            )),
+            "rounded_box" => Arc::<_>::new(RoundedBox::new(
+                &vec3(table.required("a")?)?,
+                &vec3(table.required("b")?)?,
+                number(table.required("radius")?)?,
+                self.material(table.required("material")?)?,
+            )),
+            "cylinder" => {
+                let base = vec3(table.required("base")?)?;
+                Arc::<_>::new(Cylinder::new(
+                    base,
+                    axis_end(&mut table, &base, "top")?,
+                    positive(table.required("radius")?)?,
+                    table.optional_boolean("capped", true)?,
+                    self.material(table.required("material")?)?,
+                ))
+            },
+            "cone" => {
+                let base = vec3(table.required("base")?)?;
+                Arc::<_>::new(Cone::new(
+                    base,
+                    axis_end(&mut table, &base, "apex")?,
+                    positive(table.required("radius")?)?,
+                    table.optional_boolean("capped", true)?,
+                    self.material(table.required("material")?)?,
+                ))
+            },
+            "torus" => {
+                let center = vec3(table.required("center")?)?;
+                let axis_json = table.required("axis")?;
+                let axis = vec3(axis_json)?;
+                if axis.length_squared() == 0.0 {
+                    return Err(invalid(axis_json, "Torus axis is zero"));
+                }
+                let major_radius = positive(table.required("major_radius")?)?;
+                let minor_json = table.required("minor_radius")?;
+                let minor_radius = positive(minor_json)?;
+                // Without a hole the tube would cross the axis, where its normal is undefined
+                if minor_radius >= major_radius {
+                    let message = "Minor radius must be smaller than the major radius";
+                    return Err(invalid(minor_json, message));
+                }
+                let material = self.material(table.required("material")?)?;
+                Arc::<_>::new(Torus::new(center, axis, major_radius, minor_radius, material))
+            },
+            "mesh" => {
+                let data = self.mesh_data(table.required("file")?)?;
+                // A material on the object overrides whatever the mesh file assigned
+                let mesh = match table.get("material") {
+                    Some(material) => TriangleMesh::with_material(data, self.material(material)?),
+                    None => TriangleMesh::new(data, &mut self.material_arena),
+                };
+                Arc::<_>::new(mesh)
+            },
+            "group" => {
+                let mut group = HittableList::default();
+                for object in array(table.required("objects")?)? {
+                    group.add(self.object(object, lights.as_deref_mut())?);
+                }
+                group.build()
+            },
+            "instance" => {
+                let name = table.required("object")?;
+                let object = self.objects
+                    .get(string(name)?)
+                    .cloned()
+                    .ok_or_else(|| invalid(name, "Unknown object"))?;
+                // Keyframes replace the fixed transform fields
+                match table.get("keyframes") {
+                    Some(keyframes) => {
+                        Arc::<_>::new(Animated::new(object, parse_keyframes(keyframes)?))
+                    },
+                    None => Arc::<_>::new(Transformed::new(object, parse_transform(&mut table)?)),
+                }
+            },
+            kind => return Err(invalid(json, format!("Unknown object type '{}'", kind))),
+        };
+        table.finish()?;
+        Ok(object)
+    }
+
+    /// Picks the mesh reader from the file extension, and keeps the reader's warnings.
+    fn mesh_data(&mut self, file: &Json) -> Result<MeshData, SceneError> {
+        let path = self.directory.join(string(file)?);
+        let extension = path
+            .extension()
+            .and_then(|extension| extension.to_str())
+            .map(str::to_ascii_lowercase);
+        let failed = |error: &dyn std::error::Error| {
+            invalid(file, format!("Failed to load '{}': {}", path.display(), error))
+        };
+
+        let mut data = match extension.as_deref() {
+            Some("obj") => read_obj(&path).map_err(|error| failed(&error))?,
+            Some("ply") => read_ply(&path).map_err(|error| failed(&error))?,
+            Some("stl") => read_stl(&path).map_err(|error| failed(&error))?,
+            _ => return Err(invalid(file, "Meshes must be .obj, .ply or .stl files")),
+        };
+        let tagged = data.warnings
+            .drain(..)
+            .map(|warning| format!("line {}: {}", file.line, warning));
+        self.warnings.extend(tagged);
+        Ok(data)
+    }
+}
+
+/// Reads the optional `scale`, `rotate` and `translate` fields of an instance, applied in that
+/// order.
+fn parse_transform(table: &mut Table) -> Result<Transform, SceneError> {
+    let mut transform = Transform::default();
+    if let Some(value) = table.get("scale") {
+        transform = transform.then(&Transform::scale(&parse_scale(value)?));
+    }
+    if let Some(value) = table.get("rotate") {
+        let (axis, angle) = parse_rotation(value)?;
+        transform = transform.then(&Transform::rotate(&axis, angle));
+    }
+    if let Some(value) = table.get("translate") {
+        transform = transform.then(&Transform::translate(&vec3(value)?));
+    }
+    Ok(transform)
+}
+
+/// Reads an array of keyframes, each a `time` with the same optional fields as
+/// `parse_transform`.
+fn parse_keyframes(json: &Json) -> Result<AnimatedTransform, SceneError> {
+    let mut keyframes = vec![];
+    for keyframe in array(json)? {
+        let mut table = Table::new(keyframe)?;
+        let rotation = match table.get("rotate") {
+            Some(value) => {
+                let (axis, angle) = parse_rotation(value)?;
+                Quaternion::from_axis_angle(&axis, angle)
+            },
+            None => Quaternion::default(),
+        };
+        keyframes.push(Keyframe {
+            time: number(table.required("time")?)?,
+            scale: table.get("scale").map(parse_scale).transpose()?.unwrap_or(Vec3 {
+                x: 1.0, 
+                y: 1.0, 
+                z: 1.0,
+            }),
+            rotation,
+            translation: table.get("translate").map(vec3).transpose()?.unwrap_or_default(),
+        });
+        table.finish()?;
+    }
+    AnimatedTransform::new(keyframes)
+        .ok_or_else(|| invalid(json, "Expected at least one keyframe"))
+}
+
+/// Accepts one factor for every axis or an array of three, none of them zero.
+fn parse_scale(json: &Json) -> Result<Vec3, SceneError> {
+    let factors = match json.value {
+        JsonValue::Number(_) => {
+            let factor = number(json)?;
+            Vec3 {x: factor, y: factor, z: factor}
+        },
+        _ => vec3(json)?,
+    };
+    if factors.x == 0.0 || factors.y == 0.0 || factors.z == 0.0 {
+        return Err(invalid(json, "Scale factors must be nonzero"));
+    }
+    Ok(factors)
+}
+
+/// Reads an `axis` and an `angle` in degrees.
+fn parse_rotation(json: &Json) -> Result<(Vec3, f32), SceneError> {
+    let mut table = Table::new(json)?;
+    let axis = vec3(table.required("axis")?)?;
+    if axis.length_squared() == 0.0 {return Err(invalid(json, "Rotation axis is zero"));}
+    let angle = number(table.required("angle")?)?;
+    table.finish()?;
+    Ok((axis, angle))
+}
+
+/// Shares a shape with the world, also adding it to `lights` if it's emissive.
+fn with_light<T>(shape: T, is_emissive: bool, lights: Option<&mut LightList>) -> Arc<dyn Hittable>
+where T: Hittable + Light + 'static,
+{
+    let shape = Arc::<_>::new(shape);
+    if let Some(lights) = lights.filter(|_| is_emissive) {
+        lights.add(shape.clone());
+    }
+    shape
+}
+
+/// A JSON object being read field by field. `finish` rejects any field that was never read,
+/// which catches misspelled keys.
+struct Table<'a> {
+    json: &'a Json,
+    entries: &'a [(String, usize, Json)],
+    used: Vec<bool>,
+}
+
+impl<'a> Table<'a> {
+    fn new(json: &'a Json) -> Result<Self, SceneError> {
+        let JsonValue::Object(entries) = &json.value else {
+            return Err(invalid(json, format!("Expected an object, found {}", json.value.kind())));
+        };
+        Ok(Self {json, entries, used: vec![false; entries.len()]})
+    }
+
+    fn get(&mut self, key: &str) -> Option<&'a Json> {
+        let index = self.entries.iter().position(|(name, _, _)| name == key)?;
+        self.used[index] = true;
+        Some(&self.entries[index].2)
+    }
+
+    fn required(&mut self, key: &str) -> Result<&'a Json, SceneError> {
+        self.get(key).ok_or_else(|| invalid(self.json, format!("Missing field '{}'", key)))
+    }
+
+    fn optional_number(&mut self, key: &str, default: f32) -> Result<f32, SceneError> {
+        self.get(key).map(number).transpose().map(|value| value.unwrap_or(default))
+    }
+
+    fn optional_boolean(&mut self, key: &str, default: bool) -> Result<bool, SceneError> {
+        self.get(key).map(boolean).transpose().map(|value| value.unwrap_or(default))
+    }
+
+    fn kind(&mut self) -> Result<&'a str, SceneError> {
+        string(self.required("type")?)
+    }
+
+    fn finish(self) -> Result<(), SceneError> {
+        match self.entries.iter().zip(&self.used).find(|(_, used)| !**used) {
+            Some(((name, line, _), _)) => Err(SceneError::Invalid {
+                line: *line, 
+                message: format!("Unknown field '{}'", name),
+            }),
+            None => Ok(()),
+        }
+    }
+}
+
+fn invalid(json: &Json, message: impl Into<String>) -> SceneError {
+    SceneError::Invalid {line: json.line, message: message.into()}
+}
+
+fn entries(json: &Json) -> Result<&[(String, usize, Json)], SceneError> {
+    match &json.value {
+        JsonValue::Object(entries) => Ok(entries),
+        value => Err(invalid(json, format!("Expected an object, found {}", value.kind()))),
+    }
+}
+
+fn array(json: &Json) -> Result<&[Json], SceneError> {
+    match &json.value {
+        JsonValue::Array(items) => Ok(items),
+        value => Err(invalid(json, format!("Expected an array, found {}", value.kind()))),
+    }
+}
+
+fn string(json: &Json) -> Result<&str, SceneError> {
+    match &json.value {
+        JsonValue::String(text) => Ok(text),
+        value => Err(invalid(json, format!("Expected a string, found {}", value.kind()))),
+    }
+}
+
+fn number(json: &Json) -> Result<f32, SceneError> {
+    match &json.value {
+        JsonValue::Number(number) => Ok(*number as f32),
+        value => Err(invalid(json, format!("Expected a number, found {}", value.kind()))),
+    }
+}
+
+fn boolean(json: &Json) -> Result<bool, SceneError> {
+    match &json.value {
+        JsonValue::Bool(value) => Ok(*value),
+        value => Err(invalid(json, format!("Expected a boolean, found {}", value.kind()))),
+    }
+}
+
+fn integer(json: &Json) -> Result<usize, SceneError> {
+    match &json.value {
+        JsonValue::Number(number) if *number >= 0.0 && number.fract() == 0.0 => Ok(*number as usize),
+        _ => Err(invalid(json, "Expected a non-negative integer")),
+    }
+}
+
+/// A non-negative integer small enough for the camera's `i32` settings.
+fn int32(json: &Json) -> Result<i32, SceneError> {
+    i32::try_from(integer(json)?)
+        .map_err(|_| invalid(json, format!("Out of range, the largest allowed is {}", i32::MAX)))
+}
+
+/// Like `int32`, but 0 is rejected with `message`.
+fn nonzero(json: &Json, message: &str) -> Result<i32, SceneError> {
+    match int32(json)? {
+        0 => Err(invalid(json, message)),
+        value => Ok(value),
+    }
+}
+
+fn vec3(json: &Json) -> Result<Vec3, SceneError> {
+    match array(json)? {
+        [x, y, z] => Ok(Vec3 {x: number(x)?, y: number(y)?, z: number(z)?}),
+        _ => Err(invalid(json, "Expected an array of 3 numbers")),
+    }
+}
+
+fn positive(json: &Json) -> Result<f32, SceneError> {
+    let value = number(json)?;
+    if value <= 0.0 {return Err(invalid(json, "Expected a positive number"));}
+    Ok(value)
+}
+
+/// Reads the point at `key` that ends an axis starting from `base`, which it must not touch.
+fn axis_end(table: &mut Table, base: &Vec3, key: &str) -> Result<Vec3, SceneError> {
+    let json = table.required(key)?;
+    let end = vec3(json)?;
+    if (end - *base).length_squared() == 0.0 {
+        return Err(invalid(json, format!("'{}' is the same point as 'base'", key)));
+    }
+    Ok(end)
+}
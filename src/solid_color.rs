@@ -0,0 +1,11 @@
+use crate::{color::Color, texture::Texture, vec::Point3};
+
+pub struct SolidColor {
+    pub albedo: Color,
+}
+
+impl Texture for SolidColor {
+    fn value(&self, _: f32, _: f32, _: &Point3) -> Color {
+        self.albedo
+    }
+}
@@ -1,44 +1,114 @@
+use crate::aabb::Aabb;
 use crate::hittable::Hittable;
 use crate::hit_record::HitRecord;
 use crate::interval::Interval;
-use crate::material::Material;
+use crate::light::{Light, LightSample, same_distance};
+use crate::material_arena::MaterialId;
 use crate::ray::Ray;
-use super::vec::Point3;
+use super::vec::{Point3, Vec3, orthonormal_basis};
+use std::f32::consts::PI;
 
+/// Maps a point on the unit sphere to texture coordinates, where `u` is the angle around the Y
+/// axis from X=-1 and `v` is the angle from Y=-1 to Y=+1, both scaled to [0,1].
+fn sphere_uv(point: &Point3) -> (f32, f32) {
+    let theta = (-point.y).acos();
+    let phi = (-point.z).atan2(point.x) + PI;
+    (phi / (2.0 * PI), theta / PI)
+}
+
+#[derive(Clone)]
 pub struct Sphere {
     pub center: Point3,
     pub radius: f32,
-    pub material: Box<dyn Material>,
+    pub material: MaterialId,
+}
+
+/// Fills in everything but the material for the nearest hit inside `ray_t` with the sphere at
+/// `center`, so shapes that place their sphere per ray can share it.
+pub fn hit_sphere(
+    center: &Point3, 
+    radius: f32, 
+    ray: &Ray, 
+    ray_t: Interval, 
+    hit_record: &mut HitRecord
+) -> bool {
+    let oc = ray.origin - *center;
+    let a = ray.direction.length_squared();
+    let half_b = oc.dot(&ray.direction);
+    let c = oc.length_squared() - radius.powi(2);
+    let discriminant = half_b.powi(2) - a * c;
+    if discriminant < 0.0 {return false;}
+    let sqrt_discriminat = discriminant.sqrt();
+
+    // Find the nearest root that lies in the acceptable range
+    let mut root = (-half_b - sqrt_discriminat) / a;
+    if !ray_t.surrounds(root) {
+        root = (-half_b + sqrt_discriminat) / a;
+        if !ray_t.surrounds(root) {return false;}
+    }
+
+    hit_record.t = root;
+    hit_record.point = ray.at(hit_record.t);
+    let outward_normal = (hit_record.point - *center) / radius;
+    hit_record.set_face_normal(ray, &outward_normal);
+    (hit_record.u, hit_record.v) = sphere_uv(&outward_normal);
+    true
 }
 
 impl Hittable for Sphere {
-    fn hit(
-        &mut self, 
-        ray: &Ray, 
-        ray_time: Interval,
-        hit_record: 
-        &mut HitRecord
-    ) -> bool {
-        let oc = ray.origin - self.center;
-        let a = ray.direction.length_squared();
-        let half_b = oc.dot(&ray.direction);
-        let c = oc.length_squared() - self.radius.powi(2);
-        let discriminant = half_b.powi(2) - a * c;
-        if discriminant < 0.0 {return false;}
-        let sqrt_discriminat = discriminant.sqrt();
-
-        // Find the nearest root that lies in the acceptable range
-        let mut root = (-half_b - sqrt_discriminat) / a;
-        if !ray_time.surrounds(root) {
-            root = (-half_b + sqrt_discriminat) / a;
-            if !ray_time.surrounds(root) {return false;}
+    fn hit(&self, ray: &Ray, ray_t: Interval, hit_record: &mut HitRecord) -> bool {
+        if !hit_sphere(&self.center, self.radius, ray, ray_t, hit_record) {return false;}
+        hit_record.material = self.material;
+        true
+    }
+
+    fn bounding_box(&self) -> Aabb {
+        let radius = Vec3 {x: self.radius, y: self.radius, z: self.radius};
+        Aabb::from_points(&(self.center - radius), &(self.center + radius))
+    }
+}
+
+impl Sphere {
+    /// Returns one minus the cosine of the half-angle of the cone the sphere fills as seen from
+    /// `origin`, or `None` from inside the sphere.
+    fn cone_size(&self, origin: &Point3) -> Option<f32> {
+        let sin_squared = self.radius.powi(2) / (self.center - *origin).length_squared();
+        if sin_squared >= 1.0 {return None;}
+        // Rearranged to stay precise for small, distant spheres
+        Some(sin_squared / (1.0 + (1.0 - sin_squared).sqrt()))
+    }
+}
+
+/// Samples the cone of directions that the sphere covers, which is uniform in solid angle.
+impl Light for Sphere {
+    fn sample(&self, origin: &Point3, u: f32, v: f32) -> Option<LightSample> {
+        let cone_size = self.cone_size(origin)?;
+        let cos_theta = 1.0 - u * cone_size;
+        let sin_theta = (1.0 - cos_theta.powi(2)).max(0.0).sqrt();
+        let phi = 2.0 * PI * v;
+
+        let axis = (self.center - *origin).unit_vector();
+        let (tangent, bitangent) = orthonormal_basis(&axis);
+        let direction = sin_theta * phi.cos() * tangent 
+            + sin_theta * phi.sin() * bitangent 
+            + cos_theta * axis;
+
+        // Rays grazing the silhouette can miss through rounding
+        let mut hit_record = HitRecord::default();
+        let ray = Ray {origin: *origin, direction, time: 0.0};
+        if !self.hit(&ray, Interval {min: 0.001, max: f32::INFINITY}, &mut hit_record) {
+            return None;
         }
+        Some(LightSample {direction, distance: hit_record.t, pdf: 1.0 / (2.0 * PI * cone_size)})
+    }
 
-        hit_record.time = root;
-        hit_record.point = ray.at(hit_record.time);
-        let outward_normal = (hit_record.point - self.center) / self.radius;
-        hit_record.set_face_normal(ray, &outward_normal);
-        hit_record.material = self.material.clone();
-        return true;
+    fn pdf(&self, ray: &Ray, t: f32) -> f32 {
+        let Some(cone_size) = self.cone_size(&ray.origin) else {return 0.0;};
+        let mut hit_record = HitRecord::default();
+        let ray_t = Interval {min: 0.001, max: f32::INFINITY};
+        if !self.hit(ray, ray_t, &mut hit_record) || !same_distance(hit_record.t, t) {
+            return 0.0;
+        }
+        1.0 / (2.0 * PI * cone_size)
     }
 }
\ No newline at end of file
@@ -0,0 +1,151 @@
+use std::path::Path;
+
+use crate::{
+    material_arena::MaterialArena, 
+    triangle_mesh::{MeshData, MeshFace, TriangleMesh, default_material}, 
+    vec::Vec3,
+};
+
+#[derive(Debug)]
+pub enum StlError {
+    Io(std::io::Error),
+    Parse {line: usize, message: String},
+    Truncated {expected_bytes: usize, actual_bytes: usize},
+}
+
+impl std::fmt::Display for StlError {
+    fn fmt(&self, f: &mut std::fmt::Formatter<'_>) -> std::fmt::Result {
+        match self {
+            Self::Io(error) => write!(f, "{}", error),
+            Self::Parse {line, message} => write!(f, "line {}: {}", line, message),
+            Self::Truncated {expected_bytes, actual_bytes} => write!(
+                f, 
+                "binary STL should be {} bytes but is {}", 
+                expected_bytes, 
+                actual_bytes
+            ),
+        }
+    }
+}
+
+impl std::error::Error for StlError {
+    fn source(&self) -> Option<&(dyn std::error::Error + 'static)> {
+        match self {
+            Self::Io(error) => Some(error),
+            _ => None,
+        }
+    }
+}
+
+impl From<std::io::Error> for StlError {
+    fn from(error: std::io::Error) -> Self {
+        Self::Io(error)
+    }
+}
+
+const BINARY_HEADER_SIZE: usize = 84;
+const BINARY_TRIANGLE_SIZE: usize = 50;
+
+pub fn load_stl(
+    path: impl AsRef<Path>, 
+    materials: &mut MaterialArena
+) -> Result<TriangleMesh, StlError> {
+    Ok(TriangleMesh::new(read_stl(path)?, materials))
+}
+
+/// Reads an ASCII or binary STL file. Facet normals are ignored in favor of the winding order,
+/// and every face uses the default mesh material.
+pub fn read_stl(path: impl AsRef<Path>) -> Result<MeshData, StlError> {
+    let bytes = std::fs::read(path)?;
+    let mut data = MeshData::default();
+    data.materials.push(default_material());
+
+    // Binary files may also start with "solid", so trust the size they declare first
+    let declared_size = bytes.get(80..BINARY_HEADER_SIZE).map(|count| {
+        let triangle_count = u32::from_le_bytes([count[0], count[1], count[2], count[3]]);
+        BINARY_HEADER_SIZE + BINARY_TRIANGLE_SIZE * triangle_count as usize
+    });
+    let is_ascii = bytes.trim_ascii_start().starts_with(b"solid") 
+        && declared_size != Some(bytes.len());
+
+    let positions = if is_ascii {parse_ascii(&bytes)?} else {parse_binary(&bytes, declared_size)?};
+    for corners in positions.chunks_exact(3) {
+        let first = data.positions.len();
+        data.positions.extend_from_slice(corners);
+        data.faces.push(MeshFace {
+            positions: [first, first + 1, first + 2],
+            normals: None,
+            uvs: None,
+            colors: None,
+            material: 0,
+        });
+    }
+
+    Ok(data)
+}
+
+fn parse_binary(bytes: &[u8], declared_size: Option<usize>) -> Result<Vec<Vec3>, StlError> {
+    let expected_bytes = declared_size.unwrap_or(BINARY_HEADER_SIZE);
+    if bytes.len() < expected_bytes {
+        return Err(StlError::Truncated {expected_bytes, actual_bytes: bytes.len()});
+    }
+
+    let read_f32 = |offset: usize| {
+        f32::from_le_bytes([bytes[offset], bytes[offset + 1], bytes[offset + 2], bytes[offset + 3]])
+    };
+    let triangles = bytes[BINARY_HEADER_SIZE..expected_bytes].chunks_exact(BINARY_TRIANGLE_SIZE);
+    let mut positions = Vec::with_capacity(triangles.len() * 3);
+    for triangle in 0..triangles.len() {
+        // Skip the 12 byte facet normal
+        let start = BINARY_HEADER_SIZE + triangle * BINARY_TRIANGLE_SIZE + 12;
+        for corner in 0..3 {
+            let offset = start + corner * 12;
+            positions.push(Vec3 {
+                x: read_f32(offset), 
+                y: read_f32(offset + 4), 
+                z: read_f32(offset + 8),
+            });
+        }
+    }
+    Ok(positions)
+}
+
+fn parse_ascii(bytes: &[u8]) -> Result<Vec<Vec3>, StlError> {
+    let source = std::str::from_utf8(bytes)
+        .map_err(|_| StlError::Parse {line: 1, message: "STL is not valid UTF-8".to_string()})?;
+    let mut positions = vec![];
+    let mut facet_vertices = 0;
+
+    for (line_index, line) in source.lines().enumerate() {
+        let error = |message: &str| {
+            StlError::Parse {line: line_index + 1, message: message.to_string()}
+        };
+        let mut fields = line.split_whitespace();
+        match fields.next() {
+            Some("vertex") => {
+                let mut coordinate = || -> Result<f32, StlError> {
+                    fields
+                        .next()
+                        .and_then(|field| field.parse().ok())
+                        .ok_or_else(|| error("Vertex needs three numbers"))
+                };
+                positions.push(Vec3 {x: coordinate()?, y: coordinate()?, z: coordinate()?});
+                facet_vertices += 1;
+            },
+            Some("endloop") => {
+                if facet_vertices != 3 {
+                    return Err(error("Facet does not have exactly 3 vertices"));
+                }
+                facet_vertices = 0;
+            },
+            Some("solid" | "facet" | "outer" | "endfacet" | "endsolid") | None => {},
+            Some(keyword) => return Err(error(&format!("Unexpected '{}'", keyword))),
+        }
+    }
+    if facet_vertices != 0 {
+        let line = source.lines().count();
+        return Err(StlError::Parse {line, message: "Unterminated facet".to_string()});
+    }
+
+    Ok(positions)
+}
@@ -0,0 +1,6 @@
+use crate::{color::Color, vec::Point3};
+
+pub trait Texture: Send + Sync {
+    /// Looks up the color at surface coordinates `u`, `v`, which lie on the surface at `point`.
+    fn value(&self, u: f32, v: f32, point: &Point3) -> Color;
+}
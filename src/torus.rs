@@ -0,0 +1,111 @@
+use std::f32::consts::PI;
+
+use crate::{
+    aabb::Aabb, 
+    frame::Frame, 
+    hit_record::HitRecord, 
+    hittable::Hittable, 
+    interval::Interval, 
+    material_arena::MaterialId, 
+    polynomial::solve_quartic, 
+    ray::Ray, 
+    vec::{Point3, Vec3},
+};
+
+/// A ring around `axis`, sweeping a tube of `minor_radius` along a circle of `major_radius`.
+/// `u` is the angle around the axis and `v` the angle around the tube, both scaled to [0,1].
+/// The minor radius has to be the smaller one, leaving a hole where the normal is defined.
+#[derive(Clone)]
+pub struct Torus {
+    pub center: Point3,
+    pub axis: Vec3,
+    pub major_radius: f32,
+    pub minor_radius: f32,
+    pub material: MaterialId,
+    frame: Frame,
+}
+
+impl Torus {
+    pub fn new(
+        center: Point3, 
+        axis: Vec3, 
+        major_radius: f32, 
+        minor_radius: f32, 
+        material: MaterialId
+    ) -> Self {
+        Self {center, axis, major_radius, minor_radius, material, frame: Frame::new(&axis)}
+    }
+}
+
+impl Hittable for Torus {
+    fn hit(&self, ray: &Ray, ray_t: Interval, hit_record: &mut HitRecord) -> bool {
+        let origin = self.frame.to_local(&(ray.origin - self.center));
+        let direction = self.frame.to_local(&ray.direction);
+        let (major, minor) = (self.major_radius as f64, self.minor_radius as f64);
+
+        // With a unit direction the quartic's coefficients stay comparable in size
+        let length = direction.length() as f64;
+        let d = to_f64(&direction).map(|component| component / length);
+        let mut o = to_f64(&origin);
+
+        // Solve from where the ray enters the bounding sphere, so distant rays keep precision
+        let bound = major + minor;
+        let discriminant = dot(&o, &d).powi(2) - (dot(&o, &o) - bound * bound);
+        if discriminant < 0.0 {return false;}
+        let start = (-dot(&o, &d) - discriminant.sqrt()).max(0.0);
+        for (o, d) in o.iter_mut().zip(d) {*o += start * d;}
+
+        // (|p|² + R² - r²)² = 4 R² (x² + y²) along the ray p = o + s d
+        let e = dot(&o, &o) + major * major - minor * minor;
+        let f = dot(&o, &d);
+        let four_major_squared = 4.0 * major * major;
+        let roots = solve_quartic(
+            4.0 * f,
+            4.0 * f * f + 2.0 * e - four_major_squared * (d[0] * d[0] + d[1] * d[1]),
+            4.0 * e * f - 2.0 * four_major_squared * (o[0] * d[0] + o[1] * d[1]),
+            e * e - four_major_squared * (o[0] * o[0] + o[1] * o[1]),
+        );
+        let Some(&s) = roots.as_slice().iter().find(|&&s| {
+            ray_t.surrounds(((start + s) / length) as f32)
+        }) else {
+            return false;
+        };
+
+        let local = Vec3 {
+            x: (o[0] + s * d[0]) as f32,
+            y: (o[1] + s * d[1]) as f32,
+            z: (o[2] + s * d[2]) as f32,
+        };
+        // The normal points away from the nearest point on the circle through the tube
+        let distance = (local.x.powi(2) + local.y.powi(2)).sqrt();
+        let ring = Vec3 {
+            x: local.x / distance * self.major_radius,
+            y: local.y / distance * self.major_radius,
+            z: 0.0,
+        };
+        let outward_normal = self.frame.to_world(&(local - ring).unit_vector());
+
+        let t = ((start + s) / length) as f32;
+        hit_record.t = t;
+        hit_record.point = ray.at(t);
+        hit_record.set_face_normal(ray, &outward_normal);
+        hit_record.u = (local.y.atan2(local.x) + PI) / (2.0 * PI);
+        hit_record.v = (local.z.atan2(distance - self.major_radius) + PI) / (2.0 * PI);
+        hit_record.material = self.material;
+        true
+    }
+
+    fn bounding_box(&self) -> Aabb {
+        let tube = Vec3 {x: self.minor_radius, y: self.minor_radius, z: self.minor_radius};
+        let extent = self.frame.disk_extent(self.major_radius) + tube;
+        Aabb::from_points(&(self.center - extent), &(self.center + extent))
+    }
+}
+
+fn to_f64(vec: &Vec3) -> [f64; 3] {
+    [vec.x as f64, vec.y as f64, vec.z as f64]
+}
+
+fn dot(a: &[f64; 3], b: &[f64; 3]) -> f64 {
+    a[0] * b[0] + a[1] * b[1] + a[2] * b[2]
+}
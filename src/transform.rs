@@ -0,0 +1,75 @@
+use crate::{
+    aabb::{self, Aabb}, 
+    mat4::{self, Mat4}, 
+    vec::{Point3, Vec3},
+};
+
+/// An affine transform from object space to world space, kept together with its inverse.
+#[derive(Clone, Copy, Debug)]
+pub struct Transform {
+    pub matrix: Mat4,
+    pub inverse: Mat4,
+}
+
+impl Default for Transform {
+    fn default() -> Self {
+        Self {matrix: mat4::IDENTITY, inverse: mat4::IDENTITY}
+    }
+}
+
+impl Transform {
+    /// Returns `None` for matrices that can't be inverted.
+    pub fn new(matrix: Mat4) -> Option<Self> {
+        Some(Self {matrix, inverse: matrix.affine_inverse()?})
+    }
+
+    pub fn translate(offset: &Vec3) -> Self {
+        Self {matrix: Mat4::translation(offset), inverse: Mat4::translation(&-*offset)}
+    }
+
+    /// Every factor must be nonzero.
+    pub fn scale(factors: &Vec3) -> Self {
+        let inverse = Vec3 {x: 1.0 / factors.x, y: 1.0 / factors.y, z: 1.0 / factors.z};
+        Self {matrix: Mat4::scaling(factors), inverse: Mat4::scaling(&inverse)}
+    }
+
+    pub fn rotate(axis: &Vec3, degrees: f32) -> Self {
+        let matrix = Mat4::rotation(axis, degrees);
+        // Rotations are orthogonal, so the transpose undoes them
+        Self {matrix, inverse: matrix.transpose()}
+    }
+
+    /// Applies `self` first and `next` after it.
+    pub fn then(&self, next: &Transform) -> Self {
+        Self {matrix: next.matrix * self.matrix, inverse: self.inverse * next.inverse}
+    }
+
+    pub fn point(&self, point: &Point3) -> Point3 {
+        self.matrix.transform_point(point)
+    }
+
+    pub fn vector(&self, vector: &Vec3) -> Vec3 {
+        self.matrix.transform_vector(vector)
+    }
+
+    /// Normals stay perpendicular to surfaces by going through the inverse transpose. The
+    /// result isn't normalized.
+    pub fn normal(&self, normal: &Vec3) -> Vec3 {
+        self.inverse.transpose().transform_vector(normal)
+    }
+
+    /// The box around all eight transformed corners of `bbox`. Unbounded boxes stay unbounded.
+    pub fn bounding_box(&self, bbox: &Aabb) -> Aabb {
+        if bbox.is_empty() {return *bbox;}
+        if !bbox.is_bounded() {return aabb::UNIVERSE;}
+
+        (0..8).fold(Aabb::default(), |transformed, corner| {
+            let point = self.point(&Point3 {
+                x: if corner & 1 == 0 {bbox.x.min} else {bbox.x.max},
+                y: if corner & 2 == 0 {bbox.y.min} else {bbox.y.max},
+                z: if corner & 4 == 0 {bbox.z.min} else {bbox.z.max},
+            });
+            Aabb::enclosing(&transformed, &Aabb::from_points(&point, &point))
+        })
+    }
+}
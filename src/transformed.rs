@@ -0,0 +1,65 @@
+use std::sync::Arc;
+
+use crate::{
+    aabb::Aabb, 
+    hit_record::HitRecord, 
+    hittable::Hittable, 
+    interval::Interval, 
+    ray::Ray, 
+    transform::Transform,
+};
+
+/// An instance of a shared object placed by an affine transform, so one heavy mesh can appear
+/// many times without being copied.
+pub struct Transformed<H: Hittable + ?Sized> {
+    object: Arc<H>,
+    transform: Transform,
+    bbox: Aabb,
+}
+
+/// Hits `object` with the ray moved into its space by `transform`. The direction isn't
+/// renormalized, so distances along the ray mean the same in both spaces.
+pub fn hit_transformed<H: Hittable + ?Sized>(
+    object: &H, 
+    transform: &Transform, 
+    ray: &Ray, 
+    ray_t: Interval, 
+    hit_record: &mut HitRecord
+) -> bool {
+    let object_ray = Ray {
+        origin: transform.inverse.transform_point(&ray.origin),
+        direction: transform.inverse.transform_vector(&ray.direction),
+        time: ray.time,
+    };
+    if !object.hit(&object_ray, ray_t, hit_record) {return false;}
+
+    // The normal already faces against the ray, which the inverse transpose preserves
+    hit_record.point = transform.point(&hit_record.point);
+    hit_record.normal = transform.normal(&hit_record.normal).unit_vector();
+    true
+}
+
+impl<H: Hittable + ?Sized> Transformed<H> {
+    pub fn new(object: Arc<H>, transform: Transform) -> Self {
+        let bbox = transform.bounding_box(&object.bounding_box());
+        Self {object, transform, bbox}
+    }
+
+    pub fn object(&self) -> &Arc<H> {
+        &self.object
+    }
+
+    pub fn transform(&self) -> &Transform {
+        &self.transform
+    }
+}
+
+impl<H: Hittable + ?Sized> Hittable for Transformed<H> {
+    fn hit(&self, ray: &Ray, ray_t: Interval, hit_record: &mut HitRecord) -> bool {
+        hit_transformed(self.object.as_ref(), &self.transform, ray, ray_t, hit_record)
+    }
+
+    fn bounding_box(&self) -> Aabb {
+        self.bbox
+    }
+}
@@ -0,0 +1,100 @@
+use crate::{
+    aabb::Aabb, 
+    hit_record::HitRecord, 
+    hittable::Hittable, 
+    interval::Interval, 
+    material_arena::MaterialId, 
+    ray::Ray, 
+    vec::{Point3, Vec3},
+};
+
+/// A triangle whose texture coordinates are the barycentric weights of `b` and `c`.
+pub struct Triangle {
+    pub a: Point3,
+    pub b: Point3,
+    pub c: Point3,
+    pub material: MaterialId,
+}
+
+impl Hittable for Triangle {
+    fn hit(&self, ray: &Ray, ray_t: Interval, hit_record: &mut HitRecord) -> bool {
+        let Some((t, barycentric)) = intersect_triangle(ray, ray_t, [&self.a, &self.b, &self.c])
+        else {return false;};
+
+        let outward_normal = (self.b - self.a).cross(&(self.c - self.a)).unit_vector();
+        hit_record.t = t;
+        hit_record.point = ray.at(t);
+        hit_record.set_face_normal(ray, &outward_normal);
+        (hit_record.u, hit_record.v) = (barycentric[1], barycentric[2]);
+        hit_record.material = self.material;
+        true
+    }
+
+    fn bounding_box(&self) -> Aabb {
+        triangle_bounding_box([&self.a, &self.b, &self.c])
+    }
+}
+
+pub fn triangle_bounding_box(vertices: [&Point3; 3]) -> Aabb {
+    let [a, b, c] = vertices;
+    Aabb::enclosing(&Aabb::from_points(a, b), &Aabb::from_points(c, c)).pad(1e-4)
+}
+
+/// Watertight ray-triangle intersection (Woop, Benthin and Wald 2013). Rays through a shared
+/// edge or vertex hit at least one of the triangles meeting there, so meshes have no cracks.
+///
+/// Returns the ray time and the barycentric weights of the three vertices.
+pub fn intersect_triangle(
+    ray: &Ray, 
+    ray_t: Interval, 
+    vertices: [&Point3; 3]
+) -> Option<(f32, [f32; 3])> {
+    // Pick the dominant direction axis as z, keeping the winding of the other two axes
+    let abs_direction = Vec3 {x: ray.direction.x.abs(), y: ray.direction.y.abs(), z: ray.direction.z.abs()};
+    let kz = if abs_direction.x > abs_direction.y {
+        if abs_direction.x > abs_direction.z {0} else {2}
+    } else if abs_direction.y > abs_direction.z {1} else {2};
+    let mut kx = (kz + 1) % 3;
+    let mut ky = (kx + 1) % 3;
+    if ray.direction[kz] < 0.0 {std::mem::swap(&mut kx, &mut ky);}
+    if ray.direction[kz] == 0.0 {return None;}
+
+    // Shear so the ray points down +z from the origin
+    let shear_x = ray.direction[kx] / ray.direction[kz];
+    let shear_y = ray.direction[ky] / ray.direction[kz];
+    let shear_z = 1.0 / ray.direction[kz];
+
+    let [a, b, c] = vertices.map(|vertex| *vertex - ray.origin);
+    let sheared = |vertex: &Vec3| (
+        vertex[kx] - shear_x * vertex[kz], 
+        vertex[ky] - shear_y * vertex[kz],
+    );
+    let (ax, ay) = sheared(&a);
+    let (bx, by) = sheared(&b);
+    let (cx, cy) = sheared(&c);
+
+    let mut u = cx * by - cy * bx;
+    let mut v = ax * cy - ay * cx;
+    let mut w = bx * ay - by * ax;
+
+    // Recompute edge functions that land exactly on an edge in double precision
+    if u == 0.0 || v == 0.0 || w == 0.0 {
+        let edge = |px: f32, py: f32, qx: f32, qy: f32| {
+            (px as f64 * qy as f64 - py as f64 * qx as f64) as f32
+        };
+        u = edge(cx, cy, bx, by);
+        v = edge(ax, ay, cx, cy);
+        w = edge(bx, by, ax, ay);
+    }
+
+    if (u < 0.0 || v < 0.0 || w < 0.0) && (u > 0.0 || v > 0.0 || w > 0.0) {return None;}
+
+    let determinant = u + v + w;
+    if determinant == 0.0 {return None;}
+
+    let scaled_time = u * shear_z * a[kz] + v * shear_z * b[kz] + w * shear_z * c[kz];
+    let t = scaled_time / determinant;
+    if !ray_t.surrounds(t) {return None;}
+
+    Some((t, [u / determinant, v / determinant, w / determinant]))
+}
@@ -0,0 +1,159 @@
+use std::sync::Arc;
+
+use crate::{
+    aabb::Aabb, 
+    color::Color, 
+    bvh_node::BvhNode, 
+    hit_record::HitRecord, 
+    hittable::Hittable, 
+    hittable_list::HittableList, 
+    interval::Interval, 
+    lambertian::Lambertian, 
+    material::Material, 
+    material_arena::{MaterialArena, MaterialId}, 
+    ray::Ray, 
+    triangle::{intersect_triangle, triangle_bounding_box}, 
+    vec::{Point3, Vec3},
+};
+
+/// Indices of one triangle into the buffers of a `MeshData`.
+#[derive(Clone, Copy)]
+pub struct MeshFace {
+    pub positions: [usize; 3],
+    pub normals: Option<[usize; 3]>,
+    pub uvs: Option<[usize; 3]>,
+    pub colors: Option<[usize; 3]>,
+    pub material: usize,
+}
+
+/// Vertex buffers shared by every triangle of a mesh.
+#[derive(Default)]
+pub struct MeshData {
+    pub positions: Vec<Point3>,
+    pub normals: Vec<Vec3>,
+    pub uvs: Vec<[f32; 2]>,
+    pub colors: Vec<Color>,
+    pub faces: Vec<MeshFace>,
+    /// Moved into the scene's `MaterialArena` when the mesh is built.
+    pub materials: Vec<Box<dyn Material>>,
+    /// Problems the reader worked around, such as a missing MTL library. Readers never print
+    /// them, so the caller decides whether to report them.
+    pub warnings: Vec<String>,
+}
+
+/// The material given to faces when a mesh file does not assign one.
+pub fn default_material() -> Box<dyn Material> {
+    Box::<_>::new(Lambertian::new(Color {x: 0.8, y: 0.8, z: 0.8}))
+}
+
+/// A triangle mesh that hits like a single object, with its own BVH over the faces.
+pub struct TriangleMesh {
+    data: Arc<MeshData>,
+    bvh: BvhNode,
+}
+
+impl TriangleMesh {
+    /// Every face must index into the buffers of `data`. The mesh's materials are added to
+    /// `materials`.
+    pub fn new(mut data: MeshData, materials: &mut MaterialArena) -> Self {
+        let ids: Vec<_> = std::mem::take(&mut data.materials)
+            .into_iter()
+            .map(|material| materials.add(material))
+            .collect();
+        Self::build(data, |face| ids[face.material])
+    }
+
+    /// Gives every face `material`, ignoring the materials of `data`.
+    pub fn with_material(mut data: MeshData, material: MaterialId) -> Self {
+        data.materials.clear();
+        Self::build(data, |_| material)
+    }
+
+    fn build(data: MeshData, material: impl Fn(&MeshFace) -> MaterialId) -> Self {
+        let data = Arc::<_>::new(data);
+        let triangles = data.faces
+            .iter()
+            .enumerate()
+            .map(|(index, face)| {
+                let triangle = MeshTriangle {
+                    mesh: data.clone(), 
+                    face: index, 
+                    material: material(face),
+                };
+                Arc::<_>::new(triangle) as Arc<dyn Hittable>
+            })
+            .collect();
+
+        Self {bvh: BvhNode::new(HittableList {objects: triangles}), data}
+    }
+
+    pub fn data(&self) -> &MeshData {
+        &self.data
+    }
+}
+
+impl Hittable for TriangleMesh {
+    fn hit(&self, ray: &Ray, ray_t: Interval, hit_record: &mut HitRecord) -> bool {
+        self.bvh.hit(ray, ray_t, hit_record)
+    }
+
+    fn bounding_box(&self) -> Aabb {
+        self.bvh.bounding_box()
+    }
+}
+
+struct MeshTriangle {
+    mesh: Arc<MeshData>,
+    face: usize,
+    material: MaterialId,
+}
+
+impl MeshTriangle {
+    fn vertices(&self) -> [&Point3; 3] {
+        let face = &self.mesh.faces[self.face];
+        face.positions.map(|index| &self.mesh.positions[index])
+    }
+}
+
+impl Hittable for MeshTriangle {
+    fn hit(&self, ray: &Ray, ray_t: Interval, hit_record: &mut HitRecord) -> bool {
+        let [a, b, c] = self.vertices();
+        let Some((t, weights)) = intersect_triangle(ray, ray_t, [a, b, c]) else {return false;};
+        let face = &self.mesh.faces[self.face];
+
+        hit_record.t = t;
+        hit_record.point = ray.at(t);
+        let geometric_normal = (*b - *a).cross(&(*c - *a)).unit_vector();
+        hit_record.set_face_normal(ray, &geometric_normal);
+
+        // Shade with the interpolated vertex normal, flipped to the side the ray came from
+        if let Some(normals) = face.normals {
+            let shading_normal = normals
+                .iter()
+                .zip(weights)
+                .fold(Vec3::default(), |sum, (index, weight)| sum + weight * self.mesh.normals[*index])
+                .unit_vector();
+            hit_record.normal = if hit_record.front_face {shading_normal} else {-shading_normal};
+        }
+
+        (hit_record.u, hit_record.v) = match face.uvs {
+            Some(uvs) => uvs.iter().zip(weights).fold((0.0, 0.0), |(u, v), (index, weight)| {
+                let [vertex_u, vertex_v] = self.mesh.uvs[*index];
+                (u + weight * vertex_u, v + weight * vertex_v)
+            }),
+            None => (weights[1], weights[2]),
+        };
+        if let Some(colors) = face.colors {
+            hit_record.vertex_color = colors.iter().zip(weights).fold(
+                Color::default(), 
+                |sum, (index, weight)| sum + weight * self.mesh.colors[*index],
+            );
+        }
+        hit_record.material = self.material;
+        true
+    }
+
+    fn bounding_box(&self) -> Aabb {
+        triangle_bounding_box(self.vertices())
+    }
+}
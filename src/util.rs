@@ -1,16 +1,34 @@
 use std::f32::consts::PI;
 
-use rand::prelude::Distribution;
+use crate::interval::Interval;
 
 pub fn degrees_to_radians(degrees: f32) -> f32 {
     degrees * PI / 180.0
 }
 
-pub fn random_double(min: Option<f32>, max: Option<f32>) -> f32 {
-    let range = rand::distributions::Uniform::new(
-        min.unwrap_or(0.0), 
-        max.unwrap_or(1.0)
-    );
-    let mut rng = rand::thread_rng();
-    range.sample(&mut rng)
-}
\ No newline at end of file
+/// Weighs a sample taken with density `pdf` against another strategy that could have produced
+/// it with density `other_pdf` (Veach's power heuristic with exponent 2).
+pub fn power_heuristic(pdf: f32, other_pdf: f32) -> f32 {
+    let (squared, other_squared) = (pdf * pdf, other_pdf * other_pdf);
+    if squared + other_squared <= 0.0 {return 0.0;}
+    squared / (squared + other_squared)
+}
+
+/// Returns the nearer root of `a t² + 2 half_b t + c` that lies inside `ray_t` and passes
+/// `accept`, for shapes that clip a quadric surface.
+pub fn nearest_root(
+    a: f32, 
+    half_b: f32, 
+    c: f32, 
+    ray_t: Interval, 
+    accept: impl Fn(f32) -> bool
+) -> Option<f32> {
+    let discriminant = half_b.powi(2) - a * c;
+    if discriminant < 0.0 {return None;}
+
+    // Stays precise when `a` is tiny, where the textbook formula cancels
+    let q = -half_b - discriminant.sqrt().copysign(half_b);
+    let (near, far) = (q / a, c / q);
+    let roots = if near <= far {[near, far]} else {[far, near]};
+    roots.into_iter().find(|&t| ray_t.surrounds(t) && accept(t))
+}
@@ -7,7 +7,7 @@ pub struct Vec3 {
 
 pub use Vec3 as Point3;
 
-use crate::util::random_double;
+use crate::sampler::Sampler;
 
 impl Vec3 {
     pub fn length_squared(&self) -> f32 {
@@ -34,11 +34,11 @@ impl Vec3 {
         *self / self.length()
     }
 
-    pub fn random(min: Option<f32>, max: Option<f32>) -> Self {
+    pub fn random(sampler: &mut Sampler, min: f32, max: f32) -> Self {
         Self {
-            x: random_double(min, max),
-            y: random_double(min, max),
-            z: random_double(min, max),
+            x: sampler.range(min, max),
+            y: sampler.range(min, max),
+            z: sampler.range(min, max),
         }
     }
 
@@ -47,22 +47,42 @@ impl Vec3 {
     }
 }
 
-pub fn random_in_unit_sphere() -> Vec3 {
+pub fn random_in_unit_sphere(sampler: &mut Sampler) -> Vec3 {
     loop {
-        let p = Vec3::random(Some(-1.0), Some(1.0));
+        let p = Vec3::random(sampler, -1.0, 1.0);
         if p.length_squared() < 1.0 {
             return p;
         }
     }
 }
 
-pub fn random_unit_vector() -> Vec3 {
-    random_in_unit_sphere().unit_vector()
+pub fn random_unit_vector(sampler: &mut Sampler) -> Vec3 {
+    random_in_unit_sphere(sampler).unit_vector()
 }
 
-pub fn random_on_hemisphere(normal: &Vec3) -> Vec3 {
-    let on_unit_sphere = random_unit_vector();
-    return  if on_unit_sphere.dot(normal) > 0.0 {on_unit_sphere} else {-on_unit_sphere}
+pub fn random_on_hemisphere(sampler: &mut Sampler, normal: &Vec3) -> Vec3 {
+    let on_unit_sphere = random_unit_vector(sampler);
+    if on_unit_sphere.dot(normal) > 0.0 {on_unit_sphere} else {-on_unit_sphere}
+}
+
+/// Maps two uniform numbers in [0, 1) to a unit vector uniformly distributed over the sphere.
+pub fn uniform_sphere_direction(u: f32, v: f32) -> Vec3 {
+    let z = 1.0 - 2.0 * u;
+    let radius = (1.0 - z * z).max(0.0).sqrt();
+    let phi = 2.0 * std::f32::consts::PI * v;
+    Vec3 {x: radius * phi.cos(), y: radius * phi.sin(), z}
+}
+
+/// Returns two unit vectors that form a right-handed orthonormal basis with the unit `normal`
+/// (Duff et al. 2017).
+pub fn orthonormal_basis(normal: &Vec3) -> (Vec3, Vec3) {
+    let sign = 1.0_f32.copysign(normal.z);
+    let a = -1.0 / (sign + normal.z);
+    let b = normal.x * normal.y * a;
+    (
+        Vec3 {x: 1.0 + sign * normal.x * normal.x * a, y: sign * b, z: -sign * normal.x},
+        Vec3 {x: b, y: sign + normal.y * normal.y * a, z: -normal.y},
+    )
 }
 
 pub fn reflect(vec: &Vec3, normal: &Vec3) -> Vec3 {
@@ -76,13 +96,9 @@ pub fn refract(uv: &Vec3, normal: &Vec3, etai_over_etat:f32) -> Vec3 {
     r_out_perp + r_out_parallel
 }
 
-pub fn random_in_unit_disk() -> Vec3 {
+pub fn random_in_unit_disk(sampler: &mut Sampler) -> Vec3 {
     loop {
-        let p = Vec3 {
-            x: random_double(Some(-1.0), Some(1.0)), 
-            y: random_double(Some(-1.0), Some(1.0)), 
-            z: 0.0
-        };
+        let p = Vec3 {x: sampler.range(-1.0, 1.0), y: sampler.range(-1.0, 1.0), z: 0.0};
         if p.length_squared() < 1.0 {return p;}
     }
 }
@@ -218,7 +234,7 @@ impl std::ops::DivAssign<f32> for Vec3 {
     }
 }
 
-#[derive(Default)]
+#[derive(Clone, Copy, Default)]
 pub struct Vec2<T> 
 where T: std::ops::Add, 
 {
@@ -0,0 +1,33 @@
+use crate::{
+    color::Color, 
+    hit_record::HitRecord, 
+    lambertian::{diffuse_eval, diffuse_pdf, diffuse_scatter}, 
+    material::{BsdfSample, Material}, 
+    ray::Ray, 
+    sampler::Sampler, 
+    vec::Vec3,
+};
+
+/// A diffuse surface whose albedo is the vertex color a mesh blended at the hit, so one
+/// material serves every face of a colored mesh.
+#[derive(Clone, Copy, Default)]
+pub struct VertexColor;
+
+impl Material for VertexColor {
+    fn scatter(
+        &self,
+        _: &Ray,
+        hit_record: &HitRecord,
+        sampler: &mut Sampler,
+    ) -> Option<BsdfSample> {
+        Some(diffuse_scatter(hit_record.vertex_color, hit_record, sampler))
+    }
+
+    fn eval(&self, hit_record: &HitRecord, wi: &Vec3, _: &Vec3) -> Color {
+        diffuse_eval(hit_record.vertex_color, hit_record, wi)
+    }
+
+    fn pdf(&self, hit_record: &HitRecord, wi: &Vec3, _: &Vec3) -> f32 {
+        diffuse_pdf(hit_record, wi)
+    }
+}
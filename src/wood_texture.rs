@@ -0,0 +1,28 @@
+use crate::{color::Color, perlin::Perlin, texture::Texture, vec::Point3};
+
+/// Concentric growth rings around the Y axis, warped by turbulence.
+pub struct WoodTexture {
+    pub noise: Perlin,
+    pub light: Color,
+    pub dark: Color,
+    pub rings_per_unit: f32,
+    pub turbulence_depth: usize,
+}
+
+impl WoodTexture {
+    pub fn new(light: Color, dark: Color, rings_per_unit: f32) -> Self {
+        Self {noise: Perlin::default(), light, dark, rings_per_unit, turbulence_depth: 4}
+    }
+}
+
+impl Texture for WoodTexture {
+    fn value(&self, _: f32, _: f32, point: &Point3) -> Color {
+        let radius = (point.x.powi(2) + point.z.powi(2)).sqrt();
+        let turbulence = self.noise.turbulence(point, self.turbulence_depth);
+        let ring = (self.rings_per_unit * radius + 2.0 * turbulence).fract();
+
+        // Sharpen the rings so the dark latewood is narrower than the light earlywood
+        let alpha = ring.powi(3);
+        (1.0 - alpha) * self.light + alpha * self.dark
+    }
+}
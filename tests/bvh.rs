@@ -0,0 +1,109 @@
+use std::sync::Arc;
+
+use raytracer::{
+    bvh_node::BvhNode, 
+    color::Color, 
+    hit_record::HitRecord, 
+    hittable::Hittable, 
+    hittable_list::{BVH_THRESHOLD, HittableList}, 
+    lambertian::Lambertian, 
+    material_arena::{MaterialArena, MaterialId}, 
+    plane::Plane, 
+    ray::Ray, 
+    sampler::Sampler, 
+    sphere::Sphere, 
+    triangle::Triangle, 
+    vec::{Point3, Vec3},
+};
+
+mod common;
+
+/// Spheres of very different sizes and thin triangles scattered through a box, each with its
+/// own material so a hit on the wrong object shows.
+fn random_objects(sampler: &mut Sampler, count: usize) -> Vec<Arc<dyn Hittable>> {
+    let mut materials = MaterialArena::default();
+    (0..count)
+        .map(|i| {
+            let material = materials.add(Box::<_>::new(Lambertian::new(Color::default())));
+            let center = Vec3::random(sampler, -10.0, 10.0);
+            let object: Arc<dyn Hittable> = if i % 3 == 0 {
+                Arc::<_>::new(Triangle {
+                    a: center,
+                    b: center + Vec3::random(sampler, -2.0, 2.0),
+                    c: center + Vec3::random(sampler, -2.0, 2.0),
+                    material,
+                })
+            } else {
+                let radius = 0.05 + 1.5 * sampler.next_f32().powi(3);
+                Arc::<_>::new(Sphere {center, radius, material})
+            };
+            object
+        })
+        .collect()
+}
+
+/// A ray aimed near one of the objects, so that most rays hit something.
+fn random_ray(sampler: &mut Sampler, objects: &[Arc<dyn Hittable>]) -> Ray {
+    let origin = Vec3::random(sampler, -14.0, 14.0);
+    let object = &objects[(sampler.next_u32() as usize) % objects.len()];
+    let target = object.bounding_box().centroid() + Vec3::random(sampler, -0.05, 0.05);
+    Ray {origin, direction: target - origin, time: 0.0}
+}
+
+/// The parts of a hit that must not depend on how the objects are organized.
+fn summary(hit_record: HitRecord) -> (f32, [f32; 3], MaterialId) {
+    let normal = hit_record.normal;
+    (hit_record.t, [normal.x, normal.y, normal.z], hit_record.material)
+}
+
+#[test]
+fn bvh_finds_the_same_nearest_hit_as_a_plain_list() {
+    let mut sampler = Sampler::new(3, 0);
+    for count in [3, 17, 300] {
+        let objects = random_objects(&mut sampler, count);
+        let list = HittableList {objects: objects.clone()};
+        let tree = BvhNode::new(HittableList {objects});
+
+        let mut hits = 0;
+        for i in 0..2000 {
+            let ray = random_ray(&mut sampler, &list.objects);
+            let expected = common::hit(&list, &ray).map(summary);
+            let actual = common::hit(&tree, &ray).map(summary);
+            assert_eq!(actual, expected, "ray {} through {} objects", i, count);
+            hits += actual.is_some() as usize;
+        }
+        // Most rays should hit something, or the comparison says little
+        assert!(hits > 600, "only {} of 2000 rays hit {} objects", hits, count);
+    }
+}
+
+#[test]
+fn planes_kept_outside_the_bvh_are_still_hit() {
+    let mut sampler = Sampler::new(5, 0);
+    let mut objects = random_objects(&mut sampler, 4 * BVH_THRESHOLD);
+    let floor = MaterialId::default();
+    objects.push(Arc::<_>::new(Plane {
+        point: Point3 {x: 0.0, y: -20.0, z: 0.0},
+        normal: Vec3 {x: 0.0, y: 1.0, z: 0.0},
+        material: floor,
+    }));
+    let list = HittableList {objects: objects.clone()};
+    let world = HittableList {objects}.build();
+
+    // Far from every bounded object, so only the plane is in the way
+    let ray = Ray {
+        origin: Point3 {x: 100.0, y: 0.0, z: 100.0},
+        direction: Vec3 {x: 0.0, y: -1.0, z: 0.0},
+        time: 0.0,
+    };
+    let hit_record = common::hit(world.as_ref(), &ray).unwrap();
+    assert_eq!(hit_record.t, 20.0);
+    assert_eq!(hit_record.material, floor);
+
+    // Elsewhere the objects in the tree and the plane behind them are both found
+    for i in 0..500 {
+        let ray = random_ray(&mut sampler, &list.objects[..list.objects.len() - 1]);
+        let expected = common::hit(&list, &ray).map(summary);
+        assert_eq!(common::hit(world.as_ref(), &ray).map(summary), expected, "ray {}", i);
+    }
+}
@@ -0,0 +1,89 @@
+use std::process::{Command, Output};
+
+mod common;
+
+const SCENE: &str = r#"{
+    "camera": {"image": {"width": 8, "height": 8}, "samples_per_pixel": 1},
+    "materials": {"grey": {"type": "lambertian", "albedo": [0.5, 0.5, 0.5]}},
+    "world": [{"type": "sphere", "center": [0, 0, -1], "radius": 0.5, "material": "grey"}]
+}"#;
+
+fn raytracer(args: &[&str]) -> Output {
+    Command::new(env!("CARGO_BIN_EXE_raytracer")).args(args).output().unwrap()
+}
+
+fn stderr(output: &Output) -> String {
+    String::from_utf8_lossy(&output.stderr).into_owned()
+}
+
+#[test]
+fn options_override_the_scene_and_pick_the_format_from_the_extension() {
+    let scene = common::temp_file("cli-scene.json", SCENE.as_bytes());
+    let output_path = common::temp_path("cli-output.png");
+    let output = raytracer(&[
+        scene.to_str().unwrap(), 
+        "-o", 
+        output_path.to_str().unwrap(), 
+        "--width=5", 
+        "-H", 
+        "3", 
+        "--threads", 
+        "2", 
+        "--seed", 
+        "1",
+    ]);
+    assert_eq!(output.status.code(), Some(0), "{}", stderr(&output));
+
+    // The IHDR chunk right after the signature holds the size the options asked for
+    let png = std::fs::read(&output_path).unwrap();
+    assert_eq!(png[..8], *b"\x89PNG\r\n\x1a\n");
+    assert_eq!(png[16..24], [0, 0, 0, 5, 0, 0, 0, 3]);
+
+    // Without an extension the image is PPM
+    let output_path = common::temp_path("cli-output");
+    let output = raytracer(&[scene.to_str().unwrap(), "-o", output_path.to_str().unwrap()]);
+    assert_eq!(output.status.code(), Some(0), "{}", stderr(&output));
+    assert!(std::fs::read(&output_path).unwrap().starts_with(b"P3\n8 8\n"));
+}
+
+#[test]
+fn unknown_output_extension_is_a_usage_error() {
+    let scene = common::temp_file("cli-extension.json", SCENE.as_bytes());
+    let output_path = common::temp_path("cli-output.jpg");
+    let output = raytracer(&[scene.to_str().unwrap(), "-o", output_path.to_str().unwrap()]);
+    assert_eq!(output.status.code(), Some(2));
+    assert!(stderr(&output).starts_with("Unknown output extension '.jpg', use --format\n"));
+    assert!(!output_path.exists());
+
+    // An explicit format still writes to any file name
+    let output = raytracer(&[
+        scene.to_str().unwrap(), 
+        "-o", 
+        output_path.to_str().unwrap(), 
+        "--format", 
+        "ppm",
+    ]);
+    assert_eq!(output.status.code(), Some(0), "{}", stderr(&output));
+    assert!(std::fs::read(&output_path).unwrap().starts_with(b"P3\n"));
+}
+
+#[test]
+fn invalid_arguments_exit_with_status_2() {
+    let cases: [(&[&str], &str); 6] = [
+        (&["--width", "wide"], "'--width' expects a number, got 'wide'"),
+        (&["-s", "0"], "'-s' must be greater than zero"),
+        (&["--bit-depth=12"], "'--bit-depth' must be 8 or 16, got '12'"),
+        (&["--format", "gif"], "Unknown format 'gif'"),
+        (&["--seed"], "'--seed' needs a value"),
+        (&["--colour"], "Unknown option '--colour'"),
+    ];
+    for (args, expected) in cases {
+        let output = raytracer(args);
+        assert_eq!(output.status.code(), Some(2), "{:?}", args);
+        assert!(stderr(&output).starts_with(&format!("{}\n", expected)), "{}", stderr(&output));
+    }
+
+    let output = raytracer(&["--help"]);
+    assert_eq!(output.status.code(), Some(0));
+    assert!(String::from_utf8_lossy(&output.stdout).starts_with("Usage: raytracer"));
+}
@@ -0,0 +1,72 @@
+//! Helpers shared by the integration tests. Each test crate uses only some of them.
+#![allow(dead_code)]
+
+use std::path::PathBuf;
+
+use raytracer::{
+    camera::Camera, 
+    color::Color, 
+    film::Film, 
+    hit_record::HitRecord, 
+    hittable::Hittable, 
+    interval::Interval, 
+    light_list::LightList, 
+    logger::Logger, 
+    material_arena::MaterialArena, 
+    ray::Ray,
+};
+
+/// Returns the nearest hit past the usual self-intersection offset, if any.
+pub fn hit(object: &dyn Hittable, ray: &Ray) -> Option<HitRecord> {
+    let mut hit_record = HitRecord::default();
+    let ray_t = Interval {min: 0.001, max: f32::INFINITY};
+    object.hit(ray, ray_t, &mut hit_record).then_some(hit_record)
+}
+
+/// Renders with the camera, logging progress to the test's stdout and stderr.
+pub fn render(
+    camera: &Camera, 
+    world: &dyn Hittable, 
+    materials: &MaterialArena, 
+    lights: &LightList
+) -> Film {
+    let mut logger = Logger {stdout: std::io::stdout().lock(), stderr: std::io::stderr().lock()};
+    camera.render(&mut logger, world, materials, lights)
+}
+
+/// The film's pixels as raw bits, for comparing renders exactly.
+pub fn bits(film: &Film) -> Vec<[u32; 3]> {
+    film.pixels().map(|pixel| [pixel.x.to_bits(), pixel.y.to_bits(), pixel.z.to_bits()]).collect()
+}
+
+/// A path in the temporary directory, named after the test process so parallel test runs
+/// don't collide.
+pub fn temp_path(name: &str) -> PathBuf {
+    std::env::temp_dir().join(format!("raytracer-{}-{}", std::process::id(), name))
+}
+
+/// Writes `contents` to the file at `temp_path(name)`.
+pub fn temp_file(name: &str, contents: &[u8]) -> PathBuf {
+    let path = temp_path(name);
+    std::fs::write(&path, contents).unwrap();
+    path
+}
+
+/// A film whose pixels span several orders of magnitude, including values above one and a
+/// negative channel, with the last pixel left without samples.
+pub fn film(width: usize, height: usize) -> Film {
+    let mut film = Film::new(width, height);
+    for y in 0..height {
+        for x in 0..width {
+            if (x, y) == (width - 1, height - 1) {continue;}
+            let scale = 10.0_f32.powi(x as i32 % 5 - 2);
+            let color = Color {
+                x: scale * (1.0 + x as f32), 
+                y: 0.1 * y as f32, 
+                z: 0.5 - 0.2 * x as f32,
+            };
+            film.add_samples(x, y, 2.0 * color, 2);
+        }
+    }
+    film
+}
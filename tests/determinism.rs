@@ -0,0 +1,60 @@
+use std::sync::Arc;
+
+use raytracer::{
+    camera::Camera, 
+    color::Color, 
+    dielectric::Dielectric, 
+    hittable_list::HittableList, 
+    lambertian::Lambertian, 
+    light_list::LightList, 
+    material_arena::MaterialArena, 
+    metal::Metal, 
+    sphere::Sphere, 
+    vec::{Point3, Vec2},
+};
+
+mod common;
+
+/// One sphere of each scattering material, so every sampling path draws random numbers.
+fn scene() -> (HittableList, MaterialArena) {
+    let mut world = HittableList::default();
+    let mut materials = MaterialArena::default();
+    world.add(Arc::<_>::new(Sphere {
+        center: Point3 {x: 0.0, y: -100.5, z: -1.0},
+        radius: 100.0,
+        material: materials.add(Box::<_>::new(Lambertian::new(Color {x: 0.5, y: 0.7, z: 0.3}))),
+    }));
+    world.add(Arc::<_>::new(Sphere {
+        center: Point3 {x: -1.0, y: 0.0, z: -1.0},
+        radius: 0.5,
+        material: materials.add(Box::<_>::new(Dielectric {ir: 1.5})),
+    }));
+    world.add(Arc::<_>::new(Sphere {
+        center: Point3 {x: 1.0, y: 0.0, z: -1.0},
+        radius: 0.5,
+        material: materials.add(Box::<_>::new(Metal::new(&Color {x: 0.8, y: 0.6, z: 0.2}, 0.4))),
+    }));
+    (world, materials)
+}
+
+fn render(seed: u64, thread_count: usize, tile_size: i32) -> Vec<[u32; 3]> {
+    let mut camera = Camera::default();
+    camera.image = Vec2 {width: 20, height: 12};
+    camera.samples_per_pixel = 4;
+    camera.defocus_angle = 2.0;
+    camera.focus_distance = 1.0;
+    camera.thread_count = thread_count;
+    camera.tile_size = tile_size;
+    camera.seed = Some(seed);
+
+    let (world, materials) = scene();
+    common::bits(&common::render(&camera, &world, &materials, &LightList::default()))
+}
+
+#[test]
+fn seeded_renders_match_across_threads_and_tiles() {
+    let reference = render(11, 1, 16);
+    assert_eq!(reference, render(11, 4, 16));
+    assert_eq!(reference, render(11, 3, 5));
+    assert_ne!(reference, render(12, 1, 16));
+}
@@ -0,0 +1,99 @@
+use std::f32::consts::PI;
+
+use raytracer::{
+    color::Color, 
+    distribution::Distribution2D, 
+    environment::Environment, 
+    image_environment::ImageEnvironment, 
+    image_texture::ImageTexture, 
+    sampler::Sampler, 
+    vec::uniform_sphere_direction,
+};
+
+const SAMPLES: usize = 200_000;
+
+/// A grid of ones with a single cell `brightness` times as bright.
+fn one_bright_cell(size: (usize, usize), cell: (usize, usize), brightness: f32) -> Vec<f32> {
+    let (width, height) = size;
+    let mut function = vec![1.0; width * height];
+    function[cell.1 * width + cell.0] = brightness;
+    function
+}
+
+/// The midpoint rule over an `n` by `n` grid of [0, 1)^2.
+fn integrate(n: usize, function: impl Fn(f32, f32) -> f32) -> f32 {
+    let mut total = 0.0;
+    for j in 0..n {
+        for i in 0..n {
+            total += function((i as f32 + 0.5) / n as f32, (j as f32 + 0.5) / n as f32) as f64;
+        }
+    }
+    (total / (n * n) as f64) as f32
+}
+
+#[test]
+fn distribution_samples_in_proportion_to_the_function() {
+    let (width, height, cell) = (8, 4, (5, 2));
+    let function = one_bright_cell((width, height), cell, 25.0);
+    let distribution = Distribution2D::new(&function, width, height);
+    let expected_share = 25.0 / (25.0 + (width * height - 1) as f32);
+
+    let mut sampler = Sampler::new(1, 0);
+    let mut in_cell = 0;
+    for _ in 0..SAMPLES {
+        let ((u, v), pdf) = distribution.sample(sampler.next_f32(), sampler.next_f32());
+        assert!((0.0..1.0).contains(&u) && (0.0..1.0).contains(&v), "({}, {})", u, v);
+        assert_eq!(distribution.pdf(u, v), pdf, "({}, {})", u, v);
+        if ((u * width as f32) as usize, (v * height as f32) as usize) == cell {in_cell += 1;}
+    }
+    let share = in_cell as f32 / SAMPLES as f32;
+    assert!((share - expected_share).abs() < 0.01, "{} of samples, expected {}", share,
+        expected_share);
+
+    let integral = integrate(64, |u, v| distribution.pdf(u, v));
+    assert!((integral - 1.0).abs() < 1e-4, "pdf integrates to {}", integral);
+}
+
+/// A 16 by 8 environment of luminance one with a single bright texel, turned about +y.
+fn environment() -> (ImageEnvironment, Color, f32) {
+    let (width, height, cell, brightness) = (16, 8, (5, 2), 40.0);
+    let mut pixels = vec![Color {x: 1.0, y: 1.0, z: 1.0}; width * height];
+    let bright = Color {x: brightness, y: brightness, z: brightness};
+    pixels[cell.1 * width + cell.0] = bright;
+    let image = ImageTexture {width, height, pixels};
+
+    // Each texel weighs its luminance times the sine of its row's polar angle
+    let sin_theta = |row: usize| (PI * (row as f32 + 0.5) / height as f32).sin();
+    let total: f32 = (0..height).map(|row| width as f32 * sin_theta(row)).sum::<f32>()
+        + (brightness - 1.0) * sin_theta(cell.1);
+    let share = brightness * sin_theta(cell.1) / total;
+    (ImageEnvironment::new(image, 30.0, 2.0), bright, share)
+}
+
+#[test]
+fn image_environment_samples_match_their_pdf_and_brightness() {
+    let (environment, bright, expected_share) = environment();
+    let mut sampler = Sampler::new(2, 0);
+    let mut in_texel = 0;
+    let mut mismatched = 0;
+    for _ in 0..SAMPLES {
+        let (direction, pdf) = environment.sample(sampler.next_f32(), sampler.next_f32());
+        assert!((direction.length() - 1.0).abs() < 1e-4);
+        // A sample right on a texel edge may round into its neighbor on the way back
+        let reported = environment.pdf(&direction);
+        if (reported - pdf).abs() > 1e-3 * pdf {mismatched += 1;}
+        if environment.radiance(&direction).x == 2.0 * bright.x {in_texel += 1;}
+    }
+    assert!(mismatched < SAMPLES / 1000, "{} samples disagree with pdf()", mismatched);
+    let share = in_texel as f32 / SAMPLES as f32;
+    assert!((share - expected_share).abs() < 0.01, "{} of samples, expected {}", share,
+        expected_share);
+}
+
+#[test]
+fn image_environment_pdf_integrates_to_one_over_the_sphere() {
+    let (environment, _, _) = environment();
+    let integral = integrate(600, |u, v| environment.pdf(&uniform_sphere_direction(u, v)));
+    let integral = 4.0 * PI * integral;
+    assert!((integral - 1.0).abs() < 0.01, "pdf integrates to {}", integral);
+}
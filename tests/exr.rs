@@ -0,0 +1,183 @@
+use raytracer::{
+    color::Color, 
+    exr::{ExrPixelType, write_exr}, 
+    film::Film,
+};
+
+mod common;
+
+fn write(film: &Film, pixel_type: ExrPixelType) -> Vec<u8> {
+    let mut exr = vec![];
+    write_exr(&mut exr, film, pixel_type).unwrap();
+    exr
+}
+
+fn i32_at(bytes: &[u8], offset: usize) -> i32 {
+    i32::from_le_bytes(bytes[offset..offset + 4].try_into().unwrap())
+}
+
+fn c_string(bytes: &[u8], position: &mut usize) -> String {
+    let length = bytes[*position..].iter().position(|byte| *byte == 0).unwrap();
+    let text = String::from_utf8(bytes[*position..*position + length].to_vec()).unwrap();
+    *position += length + 1;
+    text
+}
+
+struct Attribute<'a> {
+    name: String,
+    kind: String,
+    value: &'a [u8],
+}
+
+/// Splits the header into its attributes, returning them with the offset of the scanline
+/// offset table that follows.
+fn header(exr: &[u8]) -> (Vec<Attribute<'_>>, usize) {
+    assert_eq!(exr[..4], [0x76, 0x2F, 0x31, 0x01], "bad magic number");
+    assert_eq!(i32_at(exr, 4), 2, "not a single-part scanline file");
+    let mut position = 8;
+    let mut attributes = vec![];
+    loop {
+        let name = c_string(exr, &mut position);
+        if name.is_empty() {return (attributes, position);}
+        let kind = c_string(exr, &mut position);
+        let size = i32_at(exr, position) as usize;
+        attributes.push(Attribute {name, kind, value: &exr[position + 4..position + 4 + size]});
+        position += 4 + size;
+    }
+}
+
+/// Follows the offset table to each scanline chunk and returns its B, G and R planes as raw
+/// samples of `sample_size` bytes.
+fn planes(exr: &[u8], width: usize, height: usize, sample_size: usize) -> Vec<[Vec<&[u8]>; 3]> {
+    let (_, table) = header(exr);
+    let data_size = width * 3 * sample_size;
+    let mut rows = vec![];
+    for y in 0..height {
+        let offset = table + 8 * y;
+        let chunk = u64::from_le_bytes(exr[offset..offset + 8].try_into().unwrap()) as usize;
+        assert_eq!(chunk, table + 8 * height + y * (8 + data_size), "row {} offset", y);
+        assert_eq!(i32_at(exr, chunk), y as i32);
+        assert_eq!(i32_at(exr, chunk + 4), data_size as i32);
+        let data = &exr[chunk + 8..chunk + 8 + data_size];
+        let plane = |channel: usize| {
+            data[channel * width * sample_size..(channel + 1) * width * sample_size]
+                .chunks(sample_size)
+                .collect()
+        };
+        rows.push([plane(0), plane(1), plane(2)]);
+    }
+    assert_eq!(exr.len(), table + height * (8 + 8 + data_size), "trailing bytes");
+    rows
+}
+
+fn half_to_f32(bits: u16) -> f32 {
+    let sign = if bits & 0x8000 != 0 {-1.0} else {1.0};
+    let exponent = (bits >> 10 & 0x1F) as i32;
+    let mantissa = (bits & 0x3FF) as f32;
+    sign * match exponent {
+        0 => mantissa * 2.0_f32.powi(-24),
+        0x1F if mantissa == 0.0 => f32::INFINITY,
+        0x1F => f32::NAN,
+        _ => (1.0 + mantissa / 1024.0) * 2.0_f32.powi(exponent - 15),
+    }
+}
+
+#[test]
+fn header_describes_bgr_channels_and_the_window() {
+    for (pixel_type, id) in [(ExrPixelType::Half, 1), (ExrPixelType::Float, 2)] {
+        let exr = write(&common::film(5, 3), pixel_type);
+        let (attributes, _) = header(&exr);
+        let names: Vec<_> = attributes
+            .iter()
+            .map(|attribute| (attribute.name.as_str(), attribute.kind.as_str()))
+            .collect();
+        assert_eq!(names, [
+            ("channels", "chlist"), 
+            ("compression", "compression"), 
+            ("dataWindow", "box2i"), 
+            ("displayWindow", "box2i"), 
+            ("lineOrder", "lineOrder"), 
+            ("pixelAspectRatio", "float"), 
+            ("screenWindowCenter", "v2f"), 
+            ("screenWindowWidth", "float"),
+        ]);
+
+        let mut channels = vec![];
+        for name in [b'B', b'G', b'R'] {
+            channels.extend([name, 0]);
+            channels.extend(i32::to_le_bytes(id));
+            channels.extend([0, 0, 0, 0, 1, 0, 0, 0, 1, 0, 0, 0]);
+        }
+        channels.push(0);
+        assert_eq!(attributes[0].value, channels);
+        assert_eq!(attributes[1].value, [0], "compressed");
+        let window: Vec<u8> = [0_i32, 0, 4, 2].into_iter().flat_map(i32::to_le_bytes).collect();
+        assert_eq!(attributes[2].value, window);
+        assert_eq!(attributes[3].value, window);
+        assert_eq!(attributes[4].value, [0], "not increasing y");
+    }
+}
+
+#[test]
+fn float_scanlines_hold_the_exact_radiance() {
+    let film = common::film(7, 4);
+    let exr = write(&film, ExrPixelType::Float);
+    let rows = planes(&exr, 7, 4, 4);
+    for (y, [blue, green, red]) in rows.iter().enumerate() {
+        for x in 0..7 {
+            let color = film.pixel(x, y);
+            let value = |sample: &[u8]| f32::from_le_bytes(sample.try_into().unwrap());
+            let stored = [value(red[x]), value(green[x]), value(blue[x])];
+            assert_eq!(stored, [color.x, color.y, color.z], "pixel {} {}", x, y);
+        }
+    }
+}
+
+#[test]
+fn half_scanlines_round_to_the_nearest_half() {
+    let film = common::film(7, 4);
+    let exr = write(&film, ExrPixelType::Half);
+    let rows = planes(&exr, 7, 4, 2);
+    for (y, [blue, green, red]) in rows.iter().enumerate() {
+        for x in 0..7 {
+            let color = film.pixel(x, y);
+            let value = |sample: &[u8]| {
+                half_to_f32(u16::from_le_bytes(sample.try_into().unwrap()))
+            };
+            let stored = [value(red[x]), value(green[x]), value(blue[x])];
+            for (stored, expected) in stored.into_iter().zip([color.x, color.y, color.z]) {
+                // Half of a unit in the last place, or of the smallest subnormal
+                let tolerance = (expected.abs() * 2.0_f32.powi(-11)).max(2.0_f32.powi(-25));
+                assert!((stored - expected).abs() <= tolerance, "{} for {}", stored, expected);
+            }
+        }
+    }
+}
+
+#[test]
+fn half_conversion_rounds_ties_to_even_and_saturates() {
+    let cases: [(f32, u16); 12] = [
+        (1.0, 0x3C00), 
+        (-2.0, 0xC000), 
+        (1.0 + 2.0_f32.powi(-11), 0x3C00), 
+        (1.0 + 3.0 * 2.0_f32.powi(-11), 0x3C02), 
+        (65504.0, 0x7BFF), 
+        (65520.0, 0x7C00), 
+        (1e6, 0x7C00), 
+        (f32::NEG_INFINITY, 0xFC00), 
+        (2.0_f32.powi(-14), 0x0400), 
+        (2.0_f32.powi(-24), 0x0001), 
+        (2.0_f32.powi(-25), 0x0000), 
+        (f32::NAN, 0x7E00),
+    ];
+    let mut film = Film::new(cases.len(), 1);
+    for (x, (value, _)) in cases.iter().enumerate() {
+        film.add_sample(x, 0, Color {x: *value, y: 0.0, z: 0.0});
+    }
+    let exr = write(&film, ExrPixelType::Half);
+    let rows = planes(&exr, cases.len(), 1, 2);
+    for ((value, expected), sample) in cases.iter().zip(&rows[0][2]) {
+        let bits = u16::from_le_bytes((*sample).try_into().unwrap());
+        assert_eq!(bits, *expected, "{} became {:#06x}", value, bits);
+    }
+}
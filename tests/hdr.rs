@@ -0,0 +1,121 @@
+use raytracer::{
+    color::Color, 
+    film::Film, 
+    hdr::{read_hdr, write_hdr},
+};
+
+mod common;
+
+fn round_trip(film: &Film) -> (Vec<u8>, Film) {
+    let mut hdr = vec![];
+    write_hdr(&mut hdr, film).unwrap();
+    let read = read_hdr(hdr.as_slice()).unwrap();
+    (hdr, read)
+}
+
+/// RGBE keeps 8 bits of mantissa relative to the pixel's largest component.
+fn assert_close(read: Color, written: Color, pixel: (usize, usize)) {
+    let written = [written.x, written.y, written.z].map(|channel| channel.max(0.0));
+    let largest = written.iter().fold(0.0_f32, |largest, channel| largest.max(*channel));
+    for (read, written) in [read.x, read.y, read.z].into_iter().zip(written) {
+        let tolerance = largest * 2.0_f32.powi(-7);
+        assert!((read - written).abs() <= tolerance, "{:?}: {} for {}", pixel, read, written);
+    }
+}
+
+#[test]
+fn flat_and_run_length_encoded_images_round_trip() {
+    // Widths below 8 are written flat, the others run-length encoded
+    for (width, height) in [(5, 3), (23, 7)] {
+        let film = common::film(width, height);
+        let (hdr, read) = round_trip(&film);
+        let header = format!("#?RADIANCE\nFORMAT=32-bit_rle_rgbe\n\n-Y {} +X {}\n", height, width);
+        assert!(hdr.starts_with(header.as_bytes()));
+        assert_eq!((read.width, read.height), (width, height));
+        assert!(read.sample_counts.iter().all(|count| *count == 1));
+        for y in 0..height {
+            for x in 0..width {
+                assert_close(read.pixel(x, y), film.pixel(x, y), (x, y));
+            }
+        }
+    }
+}
+
+#[test]
+fn runs_longer_than_a_run_code_are_split() {
+    let mut film = Film::new(300, 1);
+    for x in 0..300 {
+        let level = if x < 290 {0.25} else {0.75};
+        film.add_sample(x, 0, Color {x: level, y: level, z: level});
+    }
+    let (hdr, read) = round_trip(&film);
+    // Each component is three runs of at most 127 bytes, then the last 10, at 2 bytes a run
+    let header_length = "#?RADIANCE\nFORMAT=32-bit_rle_rgbe\n\n-Y 1 +X 300\n".len();
+    assert_eq!(hdr.len(), header_length + 4 + 4 * 4 * 2);
+    for x in 0..300 {
+        assert_close(read.pixel(x, 0), film.pixel(x, 0), (x, 0));
+    }
+}
+
+#[test]
+fn old_style_runs_exposure_and_bottom_up_rows_are_read() {
+    let mut hdr = b"#?RGBE\nEXPOSURE=2\n\n+Y 2 +X 3\n".to_vec();
+    // The bottom row: one pixel, then an old-style repeat of it twice
+    hdr.extend([128, 64, 0, 129, 1, 1, 1, 2]);
+    // The top row, flat
+    hdr.extend([128, 128, 128, 128, 0, 0, 0, 0, 255, 0, 0, 130]);
+    let film = read_hdr(hdr.as_slice()).unwrap();
+
+    let expected = |rgbe: [u8; 3], exponent: i32| {
+        let scale = 2.0_f32.powi(exponent - 136) / 2.0;
+        Color {x: rgbe[0] as f32 + 0.5, y: rgbe[1] as f32 + 0.5, z: rgbe[2] as f32 + 0.5} * scale
+    };
+    let bottom = expected([128, 64, 0], 129);
+    for x in 0..3 {
+        let pixel = film.pixel(x, 1);
+        assert_eq!([pixel.x, pixel.y, pixel.z], [bottom.x, bottom.y, bottom.z]);
+    }
+    let top = expected([255, 0, 0], 130);
+    let pixel = film.pixel(2, 0);
+    assert_eq!([pixel.x, pixel.y, pixel.z], [top.x, top.y, top.z]);
+    assert_eq!(film.pixel(1, 0).x, 0.0);
+}
+
+#[test]
+fn malformed_images_are_errors() {
+    let cases: [&[u8]; 6] = [
+        b"P6\n", 
+        b"#?RADIANCE\nFORMAT=32-bit_rle_xyze\n\n-Y 1 +X 1\n\x80\x80\x80\x80", 
+        b"#?RADIANCE\n", 
+        b"#?RADIANCE\n\n+X 1 -Y 1\n\x80\x80\x80\x80", 
+        b"#?RADIANCE\n\n-Y one +X 1\n\x80\x80\x80\x80", 
+        b"#?RADIANCE\n\n-Y 2 +X 1\n\x80\x80\x80\x80",
+    ];
+    for hdr in cases {
+        let error = read_hdr(hdr).err();
+        let kind = error.as_ref().map(std::io::Error::kind);
+        assert_eq!(kind, Some(std::io::ErrorKind::InvalidData), "{}", hdr.escape_ascii());
+    }
+
+    // Resolutions that overflow, or that the raster is far too short to hold, fail before
+    // anything is allocated for them
+    let too_short = "HDR raster is too short for its resolution";
+    let cases: [(&[u8], &str); 3] = [
+        (
+            b"#?RADIANCE\n\n-Y 4294967296 +X 4294967297\n\x80\x80\x80\x80", 
+            "HDR image is too large",
+        ), 
+        (b"#?RADIANCE\n\n-Y 200000 +X 200000\n", too_short), 
+        (b"#?RADIANCE\n\n-Y 1 +X 100000\n\x80\x80\x80\x80\x01\x01\x01\xff", too_short),
+    ];
+    for (hdr, message) in cases {
+        let error = read_hdr(hdr).err().unwrap();
+        assert_eq!(error.kind(), std::io::ErrorKind::InvalidData, "{}", hdr.escape_ascii());
+        assert_eq!(error.to_string(), message, "{}", hdr.escape_ascii());
+    }
+
+    // A run-length encoded scanline whose run overshoots the width
+    let mut hdr = b"#?RADIANCE\n\n-Y 1 +X 8\n".to_vec();
+    hdr.extend([2, 2, 0, 8, 128 + 9, 0]);
+    assert!(read_hdr(hdr.as_slice()).is_err());
+}
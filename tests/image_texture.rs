@@ -0,0 +1,36 @@
+use std::io::ErrorKind;
+
+use raytracer::{color::gamma_to_linear, image_texture::ImageTexture};
+
+mod common;
+
+fn load(name: &str, bytes: &[u8]) -> std::io::Result<ImageTexture> {
+    ImageTexture::load(common::temp_file(name, bytes))
+}
+
+#[test]
+fn ascii_and_binary_ppm_load_the_same_pixels() {
+    let ascii = load("pixels.ppm", b"P3\n# two pixels\n2 1\n255\n255 0 0  0 128 255\n").unwrap();
+    let binary = load("pixels-binary.ppm", b"P6 2 1 255\n\xFF\x00\x00\x00\x80\xFF").unwrap();
+    for texture in [ascii, binary] {
+        assert_eq!((texture.width, texture.height), (2, 1));
+        let [red, blue] = [texture.pixels[0], texture.pixels[1]];
+        assert_eq!([red.x, red.y, red.z], [1.0, 0.0, 0.0]);
+        assert_eq!([blue.x, blue.y, blue.z], [0.0, gamma_to_linear(128.0 / 255.0), 1.0]);
+    }
+}
+
+#[test]
+fn oversized_and_truncated_ppm_are_errors() {
+    let cases: [(&str, &[u8]); 5] = [
+        ("overflow.ppm", b"P6 4294967296 4294967296 255\n"), 
+        ("overflow-rgb.ppm", b"P3 9223372036854775807 1 255\n"), 
+        ("truncated.ppm", b"P6 2 2 255\n\x00\x00\x00"), 
+        ("truncated-wide.ppm", b"P6 1 1 65535\n\x00\x00\x00\x00\x00"), 
+        ("maximum.ppm", b"P3 1 1 0\n0 0 0"),
+    ];
+    for (name, bytes) in cases {
+        let kind = load(name, bytes).err().map(|error| error.kind());
+        assert_eq!(kind, Some(ErrorKind::InvalidData), "{}", name);
+    }
+}
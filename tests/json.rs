@@ -0,0 +1,45 @@
+use raytracer::json::{JsonValue, parse};
+
+fn error(source: &str) -> (usize, String) {
+    let error = parse(source).unwrap_err();
+    (error.line, error.message)
+}
+
+#[test]
+fn values_keep_their_lines_and_key_order() {
+    let source = "{\n  \"b\": [1, -2.5e1, true],\n  \"a\": \"\\u0041\\n\",\n  \"c\": null\n}";
+    let json = parse(source).unwrap();
+    let JsonValue::Object(entries) = json.value else {panic!("expected an object")};
+    let keys: Vec<_> = entries.iter().map(|(key, line, _)| (key.as_str(), *line)).collect();
+    assert_eq!(keys, [("b", 2), ("a", 3), ("c", 4)]);
+
+    let JsonValue::Array(items) = &entries[0].2.value else {panic!("expected an array")};
+    assert!(matches!(items[1].value, JsonValue::Number(number) if number == -25.0));
+    assert!(matches!(&entries[1].2.value, JsonValue::String(text) if text == "A\n"));
+    assert!(matches!(entries[2].2.value, JsonValue::Null));
+}
+
+#[test]
+fn errors_point_at_their_line() {
+    assert_eq!(error("[1,\n2,\n]").0, 3);
+    assert_eq!(error("{\"a\": 1\n\"b\": 2}"), (2, "Expected ',' or '}'".to_string()));
+    assert_eq!(error("\n\n\"open"), (3, "Unterminated string".to_string()));
+    assert_eq!(error("[1.2.3]").1, "Invalid number '1.2.3'");
+    assert_eq!(error("[nul]").1, "Expected 'null'");
+    assert_eq!(error("\"\\q\"").1, "Invalid escape sequence");
+    assert_eq!(error("{} {}"), (1, "Unexpected characters after the top-level value".to_string()));
+    assert_eq!(error("\n").1, "Unexpected end of file");
+}
+
+#[test]
+fn deep_nesting_is_an_error_instead_of_a_stack_overflow() {
+    let nested = |depth: usize| format!("{}{}", "[".repeat(depth), "]".repeat(depth));
+    assert!(parse(&nested(128)).is_ok());
+    assert!(parse(&format!("{}1{}", "[{\"a\": ".repeat(64), "}]".repeat(64))).is_ok());
+
+    let (line, message) = error(&format!("\n{}", nested(129)));
+    assert_eq!(line, 2);
+    assert_eq!(message, "Nested more than 128 levels deep");
+    assert!(parse(&"[".repeat(200_000)).is_err());
+    assert!(parse(&"{\"a\":".repeat(200_000)).is_err());
+}
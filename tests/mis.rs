@@ -0,0 +1,111 @@
+use std::sync::Arc;
+
+use raytracer::{
+    camera::Camera, 
+    color::{Color, luminance}, 
+    constant_environment::ConstantEnvironment, 
+    diffuse_light::DiffuseLight, 
+    film::Film, 
+    hittable_list::HittableList, 
+    lambertian::Lambertian, 
+    light_list::LightList, 
+    material_arena::MaterialArena, 
+    metal::Metal, 
+    quad::Quad, 
+    sphere::Sphere, 
+    vec::{Point3, Vec2, Vec3},
+};
+
+mod common;
+
+const RENDERS: u64 = 16;
+
+/// A glossy sphere on a floor under a small, bright light: the worst case for finding the light
+/// by sampling the material alone.
+fn scene() -> (HittableList, MaterialArena, LightList) {
+    let mut world = HittableList::default();
+    let mut materials = MaterialArena::default();
+    world.add(Arc::<_>::new(Quad::new(
+        Point3 {x: -4.0, y: 0.0, z: -4.0},
+        Vec3 {x: 8.0, y: 0.0, z: 0.0},
+        Vec3 {x: 0.0, y: 0.0, z: 8.0},
+        materials.add(Box::<_>::new(Lambertian::new(Color {x: 0.6, y: 0.6, z: 0.6}))),
+    )));
+    world.add(Arc::<_>::new(Sphere {
+        center: Point3 {x: 0.0, y: 1.0, z: 0.0},
+        radius: 1.0,
+        material: materials.add(Box::<_>::new(Metal::new(&Color {x: 0.9, y: 0.8, z: 0.7}, 0.15))),
+    }));
+
+    let light = Arc::<_>::new(Quad::new(
+        Point3 {x: -0.25, y: 3.0, z: 2.0},
+        Vec3 {x: 0.5, y: 0.0, z: 0.0},
+        Vec3 {x: 0.0, y: 0.0, z: 0.5},
+        materials.add(Box::<_>::new(DiffuseLight::new(Color {x: 40.0, y: 40.0, z: 40.0}))),
+    ));
+    let mut lights = LightList::default();
+    lights.add(light.clone());
+    world.add(light);
+    (world, materials, lights)
+}
+
+fn render(world: &HittableList, materials: &MaterialArena, lights: &LightList, seed: u64) -> Film {
+    let mut camera = Camera::default();
+    camera.image = Vec2 {width: 24, height: 24};
+    camera.samples_per_pixel = 8;
+    camera.max_depth = 4;
+    camera.vertical_field_of_view = 40.0;
+    camera.look_from = Point3 {x: 0.0, y: 2.0, z: 5.0};
+    camera.look_at = Point3 {x: 0.0, y: 1.0, z: 0.0};
+    camera.environment = Arc::<_>::new(ConstantEnvironment {color: Color::default()});
+    camera.thread_count = 1;
+    camera.seed = Some(seed);
+
+    common::render(&camera, world, materials, lights)
+}
+
+/// Renders the scene several times, returning the mean luminance over all of them and the
+/// per-pixel variance between them, averaged over the image.
+fn mean_and_variance(
+    world: &HittableList, 
+    materials: &MaterialArena, 
+    lights: &LightList
+) -> (f32, f32) {
+    let films: Vec<_> = (0..RENDERS).map(|seed| render(world, materials, lights, seed)).collect();
+    let pixel_count = films[0].width * films[0].height;
+
+    let (mut mean, mut variance) = (0.0, 0.0);
+    for index in 0..pixel_count {
+        let values: Vec<_> = films
+            .iter()
+            .map(|film| luminance(&film.pixel(index % film.width, index / film.width)))
+            .collect();
+        let pixel_mean = values.iter().sum::<f32>() / RENDERS as f32;
+        mean += pixel_mean;
+        variance += values.iter().map(|value| (value - pixel_mean).powi(2)).sum::<f32>() 
+            / (RENDERS - 1) as f32;
+    }
+    (mean / pixel_count as f32, variance / pixel_count as f32)
+}
+
+#[test]
+fn mis_reduces_variance_against_bsdf_sampling() {
+    let (world, materials, lights) = scene();
+    let (mis_mean, mis_variance) = mean_and_variance(&world, &materials, &lights);
+    let (bsdf_mean, bsdf_variance) = mean_and_variance(&world, &materials, &LightList::default());
+
+    // Both estimate the same image, though BSDF sampling alone is too noisy to pin down closely
+    assert!(
+        (mis_mean - bsdf_mean).abs() < 0.25 * bsdf_mean, 
+        "MIS mean {} is far from the BSDF sampling mean {}", 
+        mis_mean, 
+        bsdf_mean
+    );
+    assert!(
+        mis_variance < 0.5 * bsdf_variance, 
+        "MIS variance {} is not well below the BSDF sampling variance {}", 
+        mis_variance, 
+        bsdf_variance
+    );
+}
+
@@ -0,0 +1,119 @@
+use std::sync::Arc;
+
+use raytracer::{
+    animated::Animated, 
+    animated_transform::{AnimatedTransform, Keyframe}, 
+    hittable::Hittable, 
+    hittable_list::HittableList, 
+    mat4::Mat4, 
+    material_arena::MaterialId, 
+    moving_sphere::MovingSphere, 
+    quad::Quad, 
+    quaternion::Quaternion, 
+    ray::Ray, 
+    sphere::Sphere, 
+    vec::{Point3, Vec3},
+};
+
+mod common;
+
+/// A ray straight down onto `x` at `time`.
+fn ray_down(x: f32, time: f32) -> Ray {
+    Ray {
+        origin: Point3 {x, y: 10.0, z: 0.0}, 
+        direction: Vec3 {x: 0.0, y: -1.0, z: 0.0}, 
+        time,
+    }
+}
+
+#[test]
+fn moving_sphere_is_found_through_the_bvh_at_every_time() {
+    let mut world = HittableList::default();
+    for i in 0..8 {
+        world.add(Arc::<_>::new(Sphere {
+            center: Point3 {x: i as f32 * 3.0, y: -5.0, z: 0.0},
+            radius: 0.5,
+            material: MaterialId::default(),
+        }));
+    }
+    world.add(Arc::<_>::new(MovingSphere {
+        start_center: Point3 {x: 0.0, y: 0.0, z: 0.0},
+        end_center: Point3 {x: 20.0, y: 0.0, z: 0.0},
+        radius: 1.0,
+        material: MaterialId::default(),
+    }));
+    let world = world.build();
+
+    for step in 0..=10 {
+        let time = step as f32 / 10.0;
+        let hit_record = common::hit(world.as_ref(), &ray_down(20.0 * time, time)).unwrap();
+        assert!((hit_record.t - 9.0).abs() < 1e-4, "Missed the sphere at time {}", time);
+        // Where the sphere was at the start, only the static spheres remain
+        if step > 2 {
+            let start = common::hit(world.as_ref(), &ray_down(0.0, time));
+            assert!(start.is_none_or(|hit_record| hit_record.t > 12.0));
+        }
+    }
+}
+
+#[test]
+fn quaternion_rotation_matches_the_matrix() {
+    let axis = Vec3 {x: 0.3, y: -1.0, z: 0.6};
+    let matrix = Mat4::rotation(&axis, 130.0);
+    let from_quaternion = Quaternion::from_axis_angle(&axis, 130.0).to_mat4();
+    for (row, expected) in from_quaternion.rows.iter().zip(matrix.rows) {
+        for (value, expected) in row.iter().zip(expected) {
+            assert!((value - expected).abs() < 1e-5);
+        }
+    }
+}
+
+#[test]
+fn keyframed_instance_stays_inside_its_bounds() {
+    let quad: Arc<dyn Hittable> = Arc::<_>::new(Quad::new(
+        Point3 {x: 1.0, y: -0.5, z: 0.0},
+        Vec3 {x: 2.0, y: 0.0, z: 0.0},
+        Vec3 {x: 0.0, y: 1.0, z: 0.0},
+        MaterialId::default(),
+    ));
+    let keyframe = |time: f32, degrees: f32, scale: f32, x: f32| Keyframe {
+        time,
+        scale: Vec3 {x: scale, y: scale, z: scale},
+        rotation: Quaternion::from_axis_angle(&Vec3 {x: 0.0, y: 0.0, z: 1.0}, degrees),
+        translation: Vec3 {x, y: 0.0, z: 0.0},
+    };
+    let transform = AnimatedTransform::new(vec![
+        keyframe(1.0, 170.0, 2.0, 3.0),
+        keyframe(0.0, 0.0, 1.0, 0.0),
+    ]).unwrap();
+    let instance = Animated::new(quad.clone(), transform.clone());
+    let bbox = instance.bounding_box();
+
+    for step in 0..=200 {
+        let pose = transform.at(step as f32 / 200.0);
+        let quad_box = quad.bounding_box();
+        for corner in 0..8 {
+            let point = pose.point(&Point3 {
+                x: if corner & 1 == 0 {quad_box.x.min} else {quad_box.x.max},
+                y: if corner & 2 == 0 {quad_box.y.min} else {quad_box.y.max},
+                z: if corner & 4 == 0 {quad_box.z.min} else {quad_box.z.max},
+            });
+            let inside = [
+                bbox.x.contains(point.x), 
+                bbox.y.contains(point.y), 
+                bbox.z.contains(point.z),
+            ];
+            assert!(inside.iter().all(|&inside| inside), "Pose {} leaves the bounds", step);
+        }
+    }
+
+    // Halfway the quad has turned 85 degrees and grown by half, so it stands nearly upright
+    let ray = Ray {
+        origin: Point3 {x: 2.5, y: 3.0, z: 5.0},
+        direction: Vec3 {x: 0.0, y: 0.0, z: -1.0},
+        time: 0.5,
+    };
+    let hit_record = common::hit(&instance, &ray).unwrap();
+    assert!((hit_record.t - 5.0).abs() < 1e-3);
+    assert!(common::hit(&instance, &Ray {time: 0.0, ..ray}).is_none());
+}
@@ -0,0 +1,93 @@
+use std::sync::Arc;
+
+use raytracer::{
+    camera::Camera, 
+    color::{Color, luminance}, 
+    constant_environment::ConstantEnvironment, 
+    diffuse_light::DiffuseLight, 
+    hittable_list::HittableList, 
+    lambertian::Lambertian, 
+    light_list::LightList, 
+    material_arena::MaterialArena, 
+    quad::Quad, 
+    sphere::Sphere, 
+    vec::{Point3, Vec2, Vec3},
+};
+
+mod common;
+
+const RENDERS: u64 = 24;
+
+/// A small, bright sphere hanging out of view over a floor in the dark, lit by nothing else.
+fn scene() -> (HittableList, MaterialArena, LightList) {
+    let mut world = HittableList::default();
+    let mut materials = MaterialArena::default();
+    world.add(Arc::<_>::new(Quad::new(
+        Point3 {x: -4.0, y: 0.0, z: -4.0},
+        Vec3 {x: 8.0, y: 0.0, z: 0.0},
+        Vec3 {x: 0.0, y: 0.0, z: 8.0},
+        materials.add(Box::<_>::new(Lambertian::new(Color {x: 0.7, y: 0.7, z: 0.7}))),
+    )));
+
+    let emit = Color {x: 60.0, y: 60.0, z: 60.0};
+    let light = Arc::<_>::new(Sphere {
+        center: Point3 {x: 0.0, y: 1.5, z: 0.0},
+        radius: 0.15,
+        material: materials.add(Box::<_>::new(DiffuseLight::new(emit))),
+    });
+    let mut lights = LightList::default();
+    lights.add(light.clone());
+    world.add(light);
+    (world, materials, lights)
+}
+
+/// The mean luminance of each of several renders with different seeds.
+fn means(world: &HittableList, materials: &MaterialArena, lights: &LightList) -> Vec<f32> {
+    let mut camera = Camera::default();
+    camera.image = Vec2 {width: 16, height: 16};
+    camera.samples_per_pixel = 64;
+    camera.max_depth = 4;
+    camera.vertical_field_of_view = 20.0;
+    camera.look_from = Point3 {x: 0.0, y: 3.0, z: 4.0};
+    camera.look_at = Point3 {x: 0.0, y: 0.0, z: 0.0};
+    camera.environment = Arc::<_>::new(ConstantEnvironment {color: Color::default()});
+    camera.thread_count = 1;
+
+    (0..RENDERS)
+        .map(|seed| {
+            camera.seed = Some(seed);
+            let film = common::render(&camera, world, materials, lights);
+            let total: f32 = film.pixels().map(|pixel| luminance(&pixel)).sum();
+            total / (film.width * film.height) as f32
+        })
+        .collect()
+}
+
+/// The mean of `values` and the standard error of that mean.
+fn mean_and_error(values: &[f32]) -> (f32, f32) {
+    let count = values.len() as f32;
+    let mean = values.iter().sum::<f32>() / count;
+    let variance = values.iter().map(|value| (value - mean).powi(2)).sum::<f32>() / (count - 1.0);
+    (mean, (variance / count).sqrt())
+}
+
+#[test]
+fn sampling_a_small_light_directly_keeps_the_mean() {
+    let (world, materials, lights) = scene();
+    let (nee_mean, nee_error) = mean_and_error(&means(&world, &materials, &lights));
+    let no_lights = LightList::default();
+    let (bsdf_mean, bsdf_error) = mean_and_error(&means(&world, &materials, &no_lights));
+
+    // The floor is lit only by the sphere, so both must pick up plenty of its light
+    assert!(nee_mean > 0.05, "NEE mean {} is too dark to compare", nee_mean);
+    let error = (nee_error.powi(2) + bsdf_error.powi(2)).sqrt();
+    assert!(
+        (nee_mean - bsdf_mean).abs() < 4.0 * error, 
+        "NEE mean {} is more than 4 standard errors {} from the BSDF sampling mean {}", 
+        nee_mean, 
+        error, 
+        bsdf_mean
+    );
+    // Otherwise a noisy BSDF sampling mean could hide a real difference
+    assert!(error < 0.05 * nee_mean, "standard error {} is too large", error);
+}
@@ -0,0 +1,76 @@
+use raytracer::obj::{ObjError, read_obj};
+
+mod common;
+
+#[test]
+fn hash_inside_a_field_is_not_a_comment() {
+    let library = common::temp_file("hash.mtl", b"newmtl red#1\nKd 1 0 0 # pure red\n");
+    let source = format!(
+        "mtllib {}\nv 0 0 0\nv 1 0 0\nv 0 1 0 # last\nusemtl red#1\nf 1 2 3\n", 
+        library.file_name().unwrap().to_str().unwrap()
+    );
+    let path = common::temp_file("hash.obj", source.as_bytes());
+    let data = read_obj(&path).unwrap();
+    assert_eq!(data.positions.len(), 3);
+    assert_eq!(data.materials.len(), 2);
+    assert_eq!(data.faces[0].material, 1);
+}
+
+#[test]
+fn missing_library_falls_back_to_the_default_material() {
+    let source = b"mtllib nowhere.mtl\nv 0 0 0\nv 1 0 0\nv 0 1 0\nusemtl red\nf 1 2 3\n";
+    let path = common::temp_file("missing-library.obj", source);
+    let data = read_obj(&path).unwrap();
+    assert_eq!(data.materials.len(), 1);
+    assert_eq!(data.faces[0].material, 0);
+    assert_eq!(data.warnings.len(), 1);
+    assert!(data.warnings[0].contains("nowhere.mtl"), "{}", data.warnings[0]);
+    assert!(data.warnings[0].ends_with(", using the default material"), "{}", data.warnings[0]);
+}
+
+#[test]
+fn unreadable_file_names_its_path() {
+    let path = std::env::temp_dir().join("raytracer-no-such-file.obj");
+    let error = read_obj(&path).err().unwrap();
+    assert!(matches!(&error, ObjError::Io {path: error_path, ..} if *error_path == path));
+    assert!(error.to_string().starts_with(&path.display().to_string()));
+}
+
+#[test]
+fn malformed_input_is_reported_with_its_line() {
+    let cases: [(&str, &[u8], usize, &str); 7] = [
+        ("short-vertex.obj", b"v 0 0 0\nv 1 0\n", 2, "Expected a number"),
+        ("bad-number.obj", b"v 0 0 zero\n", 1, "Invalid number 'zero'"),
+        ("short-face.obj", b"v 0 0 0\nv 1 0 0\nf 1 2\n", 3, "Face has fewer than 3 vertices"),
+        ("index.obj", b"v 0 0 0\nv 1 0 0\nf 1 2 4\n", 3, "Vertex index 4 is out of range"),
+        ("relative.obj", b"v 0 0 0\nv 1 0 0\nf -3 1 2\n", 3, "Vertex index -3 is out of range"),
+        ("uv.obj", b"v 0 0 0\nf 1/1 1/1 1/1\n", 2, "Texture coordinate index 1 is out of range"),
+        ("material.obj", b"v 0 0 0\nusemtl red\n", 2, "Unknown material 'red'"),
+    ];
+    for (name, source, expected_line, expected_message) in cases {
+        let path = common::temp_file(name, source);
+        match read_obj(&path) {
+            Err(ObjError::Parse {line, message, ..}) => {
+                assert_eq!(line, expected_line, "{}", name);
+                assert_eq!(message, expected_message, "{}", name);
+            },
+            Err(error) => panic!("{}: unexpected error {}", name, error),
+            Ok(_) => panic!("{}: parsed", name),
+        }
+    }
+}
+
+#[test]
+fn malformed_library_is_an_error() {
+    let library = common::temp_file("orphan.mtl", b"Kd 1 0 0\n");
+    let source = format!("mtllib {}\n", library.file_name().unwrap().to_str().unwrap());
+    let path = common::temp_file("orphan.obj", source.as_bytes());
+    match read_obj(&path) {
+        Err(ObjError::Parse {path, line: 1, message}) => {
+            assert_eq!(path, library);
+            assert_eq!(message, "'Kd' appears before any newmtl");
+        },
+        Err(error) => panic!("unexpected error {}", error),
+        Ok(_) => panic!("parsed"),
+    }
+}
@@ -0,0 +1,59 @@
+use std::sync::Arc;
+
+use raytracer::{
+    camera::Camera, 
+    color::Color, 
+    hittable_list::HittableList, 
+    lambertian::Lambertian, 
+    light_list::LightList, 
+    material_arena::MaterialArena, 
+    sphere::Sphere, 
+    vec::{Point3, Vec2},
+};
+
+mod common;
+
+/// A row of small spheres on a large one, enough for tiles to differ in how much work they take.
+fn scene() -> (HittableList, MaterialArena) {
+    let mut world = HittableList::default();
+    let mut materials = MaterialArena::default();
+    let ground = materials.add(Box::<_>::new(Lambertian::new(Color {x: 0.5, y: 0.5, z: 0.5})));
+    world.add(Arc::<_>::new(Sphere {
+        center: Point3 {x: 0.0, y: -1000.0, z: 0.0},
+        radius: 1000.0,
+        material: ground,
+    }));
+    for i in 0..8 {
+        let tint = Color {x: 0.1 * i as f32, y: 0.8 - 0.1 * i as f32, z: 0.4};
+        world.add(Arc::<_>::new(Sphere {
+            center: Point3 {x: i as f32 - 3.5, y: 0.4, z: -(i % 3) as f32},
+            radius: 0.4,
+            material: materials.add(Box::<_>::new(Lambertian::new(tint))),
+        }));
+    }
+    (world, materials)
+}
+
+fn render(seed: u64, thread_count: usize, tile_size: i32) -> Vec<[u32; 3]> {
+    let mut camera = Camera::default();
+    camera.image = Vec2 {width: 24, height: 16};
+    camera.samples_per_pixel = 3;
+    camera.look_from = Point3 {x: 0.0, y: 2.0, z: 6.0};
+    camera.look_at = Point3 {x: 0.0, y: 0.3, z: 0.0};
+    camera.thread_count = thread_count;
+    camera.tile_size = tile_size;
+    camera.seed = Some(seed);
+
+    let (world, materials) = scene();
+    common::bits(&common::render(&camera, &world, &materials, &LightList::default()))
+}
+
+#[test]
+fn parallel_renders_match_the_serial_render() {
+    for seed in [1, 2, 3] {
+        // One thread working through a single tile covering the image renders serially
+        let serial = render(seed, 1, 24);
+        assert_eq!(serial, render(seed, 8, 4), "seed {}", seed);
+        assert_eq!(serial, render(seed, 5, 7), "seed {}", seed);
+    }
+}
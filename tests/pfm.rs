@@ -0,0 +1,26 @@
+use raytracer::pfm::write_pfm;
+
+mod common;
+
+#[test]
+fn pfm_stores_little_endian_rows_from_the_bottom() {
+    let film = common::film(5, 3);
+    let mut pfm = vec![];
+    write_pfm(&mut pfm, &film).unwrap();
+
+    let header = b"PF\n5 3\n-1.0\n";
+    assert_eq!(pfm[..header.len()], header[..]);
+    let data = &pfm[header.len()..];
+    assert_eq!(data.len(), 5 * 3 * 3 * 4);
+
+    let values: Vec<f32> = data
+        .chunks(4)
+        .map(|bytes| f32::from_le_bytes(bytes.try_into().unwrap()))
+        .collect();
+    for (index, pixel) in values.chunks(3).enumerate() {
+        let (x, y) = (index % 5, 2 - index / 5);
+        let color = film.pixel(x, y);
+        // Unclamped, so values above one and negative ones survive
+        assert_eq!(pixel, [color.x, color.y, color.z], "pixel {} {}", x, y);
+    }
+}
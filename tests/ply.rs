@@ -0,0 +1,218 @@
+use raytracer::{
+    hit_record::HitRecord, 
+    hittable::Hittable, 
+    interval::Interval, 
+    material_arena::MaterialArena, 
+    ply::{PlyError, read_ply}, 
+    ray::Ray, 
+    triangle_mesh::{MeshData, TriangleMesh}, 
+    vec::{Point3, Vec3},
+};
+
+mod common;
+
+const TRIANGLE_FACE: &str = "element face 1\nproperty list uchar int vertex_indices\nend_header\n";
+
+fn ascii(vertex_properties: &str, body: &str) -> Vec<u8> {
+    format!(
+        "ply\nformat ascii 1.0\nelement vertex 3\n{}{}{}", 
+        vertex_properties, 
+        TRIANGLE_FACE, 
+        body
+    ).into_bytes()
+}
+
+#[test]
+fn empty_list_in_place_of_a_scalar_is_an_error() {
+    let path = common::temp_file("empty-list.ply", &ascii(
+        "property list uchar float x\nproperty float y\nproperty float z\n",
+        "0 0 0\n0 1 0\n0 0 1\n3 0 1 2\n",
+    ));
+    let error = read_ply(&path).err().unwrap();
+    assert!(matches!(error, PlyError::InvalidValue {index: 0, ..}), "{}", error);
+}
+
+#[test]
+fn element_without_properties_is_skipped_whatever_its_count() {
+    let bytes = format!(
+        "ply\nformat ascii 1.0\nelement padding 18446744073709551615\nelement vertex 3\n{}{}{}", 
+        "property float x\nproperty float y\nproperty float z\n", 
+        TRIANGLE_FACE, 
+        "0 0 0\n1 0 0\n0 1 0\n3 0 1 2\n"
+    ).into_bytes();
+    let path = common::temp_file("no-properties.ply", &bytes);
+    assert_eq!(read_ply(&path).unwrap().faces.len(), 1);
+}
+
+#[test]
+fn negative_index_is_reported_as_given() {
+    let path = common::temp_file("negative-index.ply", &ascii(
+        "property float x\nproperty float y\nproperty float z\n",
+        "0 0 0\n1 0 0\n0 1 0\n3 0 -4 2\n",
+    ));
+    let error = read_ply(&path).err().unwrap();
+    assert!(matches!(error, PlyError::IndexOutOfRange {face: 0, index: -4}), "{}", error);
+}
+
+#[test]
+fn vertex_colors_share_one_material_and_keep_uvs() {
+    let path = common::temp_file("colors.ply", format!(
+        "ply\nformat ascii 1.0\nelement vertex 4\n{}{}{}{}{}{}", 
+        "property float x\nproperty float y\nproperty float z\n", 
+        "property float u\nproperty float v\n", 
+        "property uchar red\nproperty uchar green\nproperty uchar blue\n", 
+        "element face 1\nproperty list uchar int vertex_indices\nend_header\n", 
+        "0 0 0 0 0 255 0 0\n1 0 0 1 0 0 255 0\n1 1 0 1 1 0 0 255\n0 1 0 0 1 255 255 255\n", 
+        "4 0 1 2 3\n"
+    ).as_bytes());
+    let data = read_ply(&path).unwrap();
+    assert_eq!(data.faces.len(), 2);
+    assert_eq!(data.materials.len(), 1);
+    assert_eq!(data.colors.len(), 4);
+    assert!(data.faces.iter().all(|face| face.uvs.is_some() && face.colors.is_some()));
+
+    // The hit blends the corner colors while u and v stay the file's texture coordinates
+    let mut materials = MaterialArena::default();
+    let mesh = TriangleMesh::new(data, &mut materials);
+    let ray = Ray {
+        origin: Point3 {x: 0.25, y: 0.25, z: 1.0}, 
+        direction: Vec3 {x: 0.0, y: 0.0, z: -1.0}, 
+        time: 0.0,
+    };
+    let mut hit_record = HitRecord::default();
+    let ray_t = Interval {min: 0.001, max: f32::INFINITY};
+    assert!(mesh.hit(&ray, ray_t, &mut hit_record));
+    assert!((hit_record.u - 0.25).abs() < 1e-5 && (hit_record.v - 0.25).abs() < 1e-5);
+    assert!(hit_record.vertex_color.x > hit_record.vertex_color.y);
+    assert_eq!(materials.len(), 1);
+}
+
+fn read(name: &str, bytes: &[u8]) -> Result<MeshData, PlyError> {
+    read_ply(common::temp_file(name, bytes))
+}
+
+#[test]
+fn malformed_headers_report_their_line() {
+    let cases = [
+        ("not ply\nend_header\n", 1, "File does not start with 'ply'"),
+        ("ply\nformat zip 1.0\nend_header\n", 2, "Unknown format"),
+        ("ply\nformat ascii 1.0\nelement vertex three\n", 3, "Invalid element count"),
+        ("ply\nformat ascii 1.0\nproperty float x\n", 3, "Property outside an element"),
+        ("ply\nformat ascii 1.0\nelement vertex 1\nproperty half x\n", 4, "Unknown property type"),
+        ("ply\nformat ascii 1.0\nelements 1\n", 3, "Unrecognized header line"),
+        ("ply\nelement vertex 0\nend_header\n", 3, "Missing format line"),
+        ("ply\nformat ascii 1.0\nelement vertex 0", 3, "Missing end_header"),
+    ];
+    for (index, (header, expected_line, expected_message)) in cases.into_iter().enumerate() {
+        match read(&format!("header-{}.ply", index), header.as_bytes()) {
+            Err(PlyError::InvalidHeader {line, message}) => {
+                assert_eq!(line, expected_line, "{}", header);
+                assert_eq!(message, expected_message, "{}", header);
+            },
+            Err(error) => panic!("{:?}: unexpected error {}", header, error),
+            Ok(_) => panic!("{:?}: parsed", header),
+        }
+    }
+}
+
+#[test]
+fn malformed_ascii_bodies_are_errors() {
+    let xyz = "property float x\nproperty float y\nproperty float z\n";
+    let error = |name: &str, properties: &str, body: &str| {
+        read(name, &ascii(properties, body)).err()
+    };
+
+    let invalid = error("ascii-number.ply", xyz, "0 0 0\n1 zero 0\n0 1 0\n3 0 1 2\n");
+    assert!(matches!(
+        invalid, 
+        Some(PlyError::InvalidValue {ref element, index: 1, ref message})
+            if element == "vertex" && message == "Invalid number 'zero'"
+    ));
+    let negative = error("ascii-list.ply", xyz, "0 0 0\n1 0 0\n0 1 0\n-3 0 1 2\n");
+    assert!(matches!(negative, Some(PlyError::InvalidValue {index: 0, ..})));
+    let short = error("ascii-end.ply", xyz, "0 0 0\n1 0 0\n0 1 0\n3 0 1\n");
+    assert!(matches!(short, Some(PlyError::UnexpectedEnd)));
+    let missing = error("ascii-missing.ply", "property float x\nproperty float y\n", "0 0\n");
+    assert!(matches!(missing, Some(PlyError::MissingProperty {element: "vertex", property: "z"})));
+    let index = error("ascii-index.ply", xyz, "0 0 0\n1 0 0\n0 1 0\n3 0 1 3\n");
+    assert!(matches!(index, Some(PlyError::IndexOutOfRange {face: 0, index: 3})));
+}
+
+#[test]
+fn float_counts_and_indices_must_be_whole_numbers() {
+    let xyz = "property float x\nproperty float y\nproperty float z\n";
+    let float_face = "element face 1\nproperty list float float vertex_indices\nend_header\n";
+    let cases = [
+        ("3 0 1.5 2\n", "Invalid vertex index '1.5'"),
+        ("3 0 nan 2\n", "Invalid vertex index 'NaN'"),
+        ("3 0 1e30 2\n", "Invalid vertex index '1000000000000000000000000000000'"),
+        ("2.5 0 1 2\n", "Invalid list length '2.5'"),
+        ("nan 0 1 2\n", "Invalid list length 'NaN'"),
+    ];
+    for (case, (face, expected)) in cases.into_iter().enumerate() {
+        let bytes = format!(
+            "ply\nformat ascii 1.0\nelement vertex 3\n{}{}0 0 0\n1 0 0\n0 1 0\n{}", 
+            xyz, 
+            float_face, 
+            face
+        );
+        match read(&format!("float-index-{}.ply", case), bytes.as_bytes()) {
+            Err(PlyError::InvalidValue {element, index: 0, message}) => {
+                assert_eq!((element.as_str(), message.as_str()), ("face", expected), "{}", face);
+            },
+            Err(error) => panic!("{:?}: unexpected error {}", face, error),
+            Ok(_) => panic!("{:?}: parsed", face),
+        }
+    }
+
+    // A whole negative index is still a whole number, and is reported as out of range
+    let bytes = format!(
+        "ply\nformat ascii 1.0\nelement vertex 3\n{}{}0 0 0\n1 0 0\n0 1 0\n3 0 -1.0 2\n", 
+        xyz, 
+        float_face
+    );
+    let error = read("float-negative-index.ply", bytes.as_bytes()).err();
+    assert!(matches!(error, Some(PlyError::IndexOutOfRange {face: 0, index: -1})));
+}
+
+/// A binary triangle with positions as floats and indices as ints, in the given byte order.
+fn binary(big_endian: bool, indices: [i32; 3]) -> Vec<u8> {
+    let format = if big_endian {"binary_big_endian"} else {"binary_little_endian"};
+    let mut bytes = format!(
+        "ply\nformat {} 1.0\nelement vertex 3\n{}{}", 
+        format, 
+        "property float x\nproperty float y\nproperty float z\n", 
+        TRIANGLE_FACE
+    ).into_bytes();
+    let positions = [[0.0f32, 0.0, 0.0], [1.0, 0.0, 0.0], [0.0, 2.0, -1.0]];
+    for value in positions.into_iter().flatten() {
+        let value = if big_endian {value.to_be_bytes()} else {value.to_le_bytes()};
+        bytes.extend(value);
+    }
+    bytes.push(3);
+    for index in indices {
+        let index = if big_endian {index.to_be_bytes()} else {index.to_le_bytes()};
+        bytes.extend(index);
+    }
+    bytes
+}
+
+#[test]
+fn binary_files_read_in_either_byte_order() {
+    for big_endian in [false, true] {
+        let name = |case: &str| format!("binary-{}-{}.ply", big_endian, case);
+        let data = read(&name("valid"), &binary(big_endian, [0, 1, 2])).unwrap();
+        let corner = data.positions[2];
+        assert_eq!([corner.x, corner.y, corner.z], [0.0, 2.0, -1.0], "{}", big_endian);
+        assert_eq!(data.faces[0].positions, [0, 1, 2]);
+
+        let mut truncated = binary(big_endian, [0, 1, 2]);
+        truncated.truncate(truncated.len() - 2);
+        let error = read(&name("truncated"), &truncated).err();
+        assert!(matches!(error, Some(PlyError::UnexpectedEnd)), "{}", big_endian);
+
+        let error = read(&name("index"), &binary(big_endian, [0, -1, 2])).err();
+        let negative = matches!(error, Some(PlyError::IndexOutOfRange {face: 0, index: -1}));
+        assert!(negative, "{}", big_endian);
+    }
+}
@@ -0,0 +1,234 @@
+use raytracer::{
+    color::linear_to_srgb, 
+    deflate::{adler32, zlib_compress}, 
+    png::{PngBitDepth, PngOptions, crc32, write_png}, 
+    sampler::Sampler,
+};
+
+mod common;
+
+/// Reads DEFLATE bits least significant first.
+struct BitReader<'a> {
+    bytes: &'a [u8],
+    bit: usize,
+}
+
+impl BitReader<'_> {
+    fn bits(&mut self, count: usize) -> usize {
+        (0..count).fold(0, |value, index| {
+            let bit = (self.bytes[self.bit / 8] >> (self.bit % 8)) & 1;
+            self.bit += 1;
+            value | (bit as usize) << index
+        })
+    }
+
+    /// Huffman codes are packed most significant bit first.
+    fn code(&mut self, value: usize, count: usize) -> usize {
+        (0..count).fold(value, |value, _| value << 1 | self.bits(1))
+    }
+
+    fn fixed_literal_or_length(&mut self) -> usize {
+        let code = self.code(0, 7);
+        if code < 0x18 {return 256 + code;}
+        let code = self.code(code, 1);
+        match code {
+            0x30..=0xBF => code - 0x30,
+            0xC0..=0xC7 => 280 + code - 0xC0,
+            _ => 144 + self.code(code, 1) - 0x190,
+        }
+    }
+}
+
+/// A zlib decoder for stored and fixed-Huffman blocks, the only kinds the encoder writes,
+/// checking the header and the Adler-32 trailer on the way.
+fn inflate(stream: &[u8]) -> Vec<u8> {
+    let (cmf, flg) = (stream[0], stream[1]);
+    assert_eq!(cmf & 0x0F, 8, "not deflate");
+    assert_eq!((cmf as u16 * 256 + flg as u16) % 31, 0, "bad header check bits");
+
+    // Base values and extra bits of the length and distance codes, from RFC 1951 section 3.2.5
+    let mut lengths = vec![];
+    let mut base = 3;
+    for code in 0..28 {
+        let extra = if code < 8 {0} else {code / 4 - 1};
+        lengths.push((base, extra));
+        base += 1 << extra;
+    }
+    lengths.push((258, 0));
+    let mut distances = vec![];
+    let mut base = 1;
+    for code in 0..30 {
+        let extra = if code < 4 {0} else {code / 2 - 1};
+        distances.push((base, extra));
+        base += 1 << extra;
+    }
+
+    let mut reader = BitReader {bytes: &stream[2..], bit: 0};
+    let mut output: Vec<u8> = vec![];
+    loop {
+        let is_final = reader.bits(1) == 1;
+        match reader.bits(2) {
+            0 => {
+                reader.bit = reader.bit.div_ceil(8) * 8;
+                let length = reader.bits(16);
+                assert_eq!(length ^ reader.bits(16), 0xFFFF, "bad stored length");
+                let start = reader.bit / 8;
+                output.extend_from_slice(&reader.bytes[start..start + length]);
+                reader.bit += 8 * length;
+            },
+            1 => loop {
+                let symbol = reader.fixed_literal_or_length();
+                if symbol == 256 {break;}
+                if symbol < 256 {
+                    output.push(symbol as u8);
+                    continue;
+                }
+                let (base, extra) = lengths[symbol - 257];
+                let length = base + reader.bits(extra);
+                let (base, extra) = distances[reader.code(0, 5)];
+                let distance = base + reader.bits(extra);
+                assert!(distance <= output.len().min(1 << 15), "distance out of range");
+                for _ in 0..length {
+                    output.push(output[output.len() - distance]);
+                }
+            },
+            block_type => panic!("unexpected block type {}", block_type),
+        }
+        if is_final {break;}
+    }
+
+    let end = reader.bit.div_ceil(8);
+    let trailer = &reader.bytes[end..];
+    assert_eq!(trailer, adler32(&output).to_be_bytes(), "bad Adler-32");
+    output
+}
+
+#[test]
+fn checksums_match_reference_values() {
+    assert_eq!(adler32(b"Wikipedia"), 0x11E6_0398);
+    assert_eq!(adler32(&[0xFF; 100_000]), 0x149A_302C);
+    assert_eq!(crc32(b"123456789"), 0xCBF4_3926);
+    assert_eq!(crc32(b"IEND"), 0xAE42_6082);
+}
+
+#[test]
+fn stored_blocks_inflate() {
+    // Two stored blocks, the first not final, around "hello" and " world"
+    let mut stream = vec![0x78, 0x01, 0x00, 5, 0, !5, !0];
+    stream.extend_from_slice(b"hello");
+    stream.extend_from_slice(&[0x01, 6, 0, !6, !0]);
+    stream.extend_from_slice(b" world");
+    stream.extend_from_slice(&adler32(b"hello world").to_be_bytes());
+    assert_eq!(inflate(&stream), b"hello world");
+}
+
+#[test]
+fn zlib_streams_inflate_to_the_input() {
+    let mut sampler = Sampler::new(11, 0);
+    let noise: Vec<u8> = (0..5000).map(|_| (sampler.next_f32() * 256.0) as u8).collect();
+    // Repeats spaced further apart than the window, and runs longer than the longest match
+    let repeats: Vec<u8> = noise.iter().cycle().take(100_000).copied().collect();
+    let distant: Vec<u8> = [&noise[..], &vec![0; 40_000], &noise[..]].concat();
+    let inputs = [
+        vec![], 
+        b"a".to_vec(), 
+        b"abcabcabcabcabcabd".to_vec(), 
+        vec![7; 1000], 
+        noise, 
+        repeats, 
+        distant,
+    ];
+    for input in inputs {
+        let compressed = zlib_compress(&input);
+        assert_eq!(inflate(&compressed), input, "{} bytes", input.len());
+    }
+}
+
+/// Splits a PNG into its chunks, checking the signature and every CRC.
+fn chunks(png: &[u8]) -> Vec<([u8; 4], &[u8])> {
+    assert_eq!(png[..8], [0x89, b'P', b'N', b'G', b'\r', b'\n', 0x1A, b'\n']);
+    let mut chunks = vec![];
+    let mut rest = &png[8..];
+    while !rest.is_empty() {
+        let length = u32::from_be_bytes(rest[..4].try_into().unwrap()) as usize;
+        let kind: [u8; 4] = rest[4..8].try_into().unwrap();
+        let crc = u32::from_be_bytes(rest[8 + length..12 + length].try_into().unwrap());
+        assert_eq!(crc, crc32(&rest[4..8 + length]), "bad CRC");
+        chunks.push((kind, &rest[8..8 + length]));
+        rest = &rest[12 + length..];
+    }
+    chunks
+}
+
+/// Undoes the per-scanline filters, returning the raw rows.
+fn unfilter(data: &[u8], width: usize, height: usize, bytes_per_pixel: usize) -> Vec<u8> {
+    let stride = width * bytes_per_pixel;
+    assert_eq!(data.len(), height * (1 + stride));
+    let mut image: Vec<u8> = vec![];
+    for (y, line) in data.chunks(1 + stride).enumerate() {
+        let start = image.len();
+        for (i, byte) in line[1..].iter().enumerate() {
+            let left = if i >= bytes_per_pixel {image[start + i - bytes_per_pixel]} else {0};
+            let up = if y > 0 {image[start + i - stride]} else {0};
+            let up_left = if y > 0 && i >= bytes_per_pixel {
+                image[start + i - stride - bytes_per_pixel]
+            } else {
+                0
+            };
+            let prediction = match line[0] {
+                0 => 0,
+                1 => left,
+                2 => up,
+                3 => ((left as u16 + up as u16) / 2) as u8,
+                4 => {
+                    let estimate = left as i16 + up as i16 - up_left as i16;
+                    let distance = |value: u8| (estimate - value as i16).abs();
+                    if distance(left) <= distance(up) && distance(left) <= distance(up_left) {
+                        left
+                    } else if distance(up) <= distance(up_left) {
+                        up
+                    } else {
+                        up_left
+                    }
+                },
+                filter => panic!("unknown filter {}", filter),
+            };
+            image.push(byte.wrapping_add(prediction));
+        }
+    }
+    image
+}
+
+#[test]
+fn png_round_trips_through_zlib_and_filters() {
+    let film = common::film(23, 7);
+    for (bit_depth, alpha) in [(PngBitDepth::Eight, false), (PngBitDepth::Sixteen, true)] {
+        let mut png = vec![];
+        write_png(&mut png, &film, &PngOptions {bit_depth, alpha}).unwrap();
+        let chunks = chunks(&png);
+        let kinds: Vec<_> = chunks.iter().map(|(kind, _)| kind).collect();
+        assert_eq!(kinds, [b"IHDR", b"sRGB", b"gAMA", b"cHRM", b"IDAT", b"IEND"]);
+
+        let header = chunks[0].1;
+        let sample_size = if bit_depth == PngBitDepth::Eight {1} else {2};
+        assert_eq!(header[..8], [0, 0, 0, 23, 0, 0, 0, 7]);
+        assert_eq!(header[8..], [8 * sample_size as u8, if alpha {6} else {2}, 0, 0, 0]);
+
+        let channels = if alpha {4} else {3};
+        let image = unfilter(&inflate(chunks[4].1), 23, 7, channels * sample_size);
+        let max_value = if sample_size == 1 {255.0} else {65535.0};
+        for (index, pixel) in image.chunks(channels * sample_size).enumerate() {
+            let (x, y) = (index % 23, index / 23);
+            let color = film.pixel(x, y);
+            let coverage = if film.sample_counts[index] > 0 {1.0} else {0.0};
+            let expected = [color.x, color.y, color.z]
+                .map(|channel| linear_to_srgb(channel.clamp(0.0, 1.0)))
+                .into_iter()
+                .chain(alpha.then_some(coverage));
+            for (sample, expected) in pixel.chunks(sample_size).zip(expected) {
+                let value = sample.iter().fold(0, |value, byte| value << 8 | *byte as u32);
+                assert_eq!(value, (expected * max_value).round() as u32, "pixel {} {}", x, y);
+            }
+        }
+    }
+}
@@ -0,0 +1,219 @@
+use raytracer::{
+    cone::Cone, 
+    cuboid::Cuboid, 
+    cylinder::Cylinder, 
+    hit_record::HitRecord, 
+    hittable::Hittable, 
+    interval::Interval, 
+    material_arena::MaterialId, 
+    ray::Ray, 
+    rounded_box::RoundedBox, 
+    sampler::Sampler, 
+    torus::Torus, 
+    vec::{Point3, Vec3, uniform_sphere_direction},
+};
+
+mod common;
+
+/// A closed shape with its exact signed distance, negative inside, and a point inside it.
+struct Case {
+    name: &'static str,
+    shape: Box<dyn Hittable>,
+    distance: Box<dyn Fn(&Point3) -> f32>,
+    inside: Point3,
+}
+
+fn point(x: f32, y: f32, z: f32) -> Point3 {
+    Point3 {x, y, z}
+}
+
+/// Splits `point` into its distance from the axis through `base` and its height along it.
+fn axial(point: &Point3, base: &Point3, axis: &Vec3) -> (f32, f32) {
+    let offset = *point - *base;
+    let height = offset.dot(axis);
+    ((offset - height * *axis).length(), height)
+}
+
+fn box_distance(point: &Point3, half_size: &Vec3) -> f32 {
+    let q = Vec3 {x: point.x.abs(), y: point.y.abs(), z: point.z.abs()} - *half_size;
+    let outside = Vec3 {x: q.x.max(0.0), y: q.y.max(0.0), z: q.z.max(0.0)};
+    outside.length() + q.x.max(q.y).max(q.z).min(0.0)
+}
+
+/// The exact distance to a capped cone with its apex at the origin and its base `height` below
+/// it, from the distance `radial` off the axis and the height `up` relative to the apex.
+fn cone_distance(radial: f32, up: f32, radius: f32, height: f32) -> f32 {
+    let (qx, qy) = (radius, -height);
+    let dot_q = qx * qx + qy * qy;
+    let along = ((radial * qx + up * qy) / dot_q).clamp(0.0, 1.0);
+    let (ax, ay) = (radial - qx * along, up - qy * along);
+    let (bx, by) = (radial - qx * (radial / qx).clamp(0.0, 1.0), up - qy);
+    let sign = (up * qx - radial * qy).max(qy - up);
+    (ax * ax + ay * ay).min(bx * bx + by * by).sqrt().copysign(sign)
+}
+
+fn cases() -> Vec<Case> {
+    let material = MaterialId::default();
+    let half_size = Vec3 {x: 1.0, y: 0.5, z: 0.75};
+
+    let (base, top) = (point(-0.3, -1.0, 0.2), point(0.4, 1.0, -0.1));
+    let cylinder_axis = (top - base).unit_vector();
+    let cylinder_height = (top - base).length();
+
+    let apex = point(-0.2, 1.2, 0.3);
+    let cone_axis = (apex - base).unit_vector();
+    let cone_height = (apex - base).length();
+
+    let center = point(0.1, 0.0, -0.2);
+    let torus_axis = Vec3 {x: 0.3, y: 1.0, z: 0.2}.unit_vector();
+    let across = torus_axis.cross(&Vec3 {x: 1.0, y: 0.0, z: 0.0}).unit_vector();
+
+    vec![
+        Case {
+            name: "box",
+            // Any two opposite corners will do
+            shape: Box::<_>::new(Cuboid::new(
+                &point(1.0, -0.5, 0.75), 
+                &point(-1.0, 0.5, -0.75), 
+                material,
+            )),
+            distance: Box::<_>::new(move |point| box_distance(point, &half_size)),
+            inside: point(0.2, 0.1, -0.3),
+        },
+        Case {
+            name: "rounded box",
+            shape: Box::<_>::new(RoundedBox::new(&-half_size, &half_size, 0.3, material)),
+            distance: Box::<_>::new(move |point| {
+                box_distance(point, &(half_size - Vec3 {x: 0.3, y: 0.3, z: 0.3})) - 0.3
+            }),
+            inside: point(0.8, 0.3, 0.55),
+        },
+        Case {
+            name: "cylinder",
+            shape: Box::<_>::new(Cylinder::new(base, top, 0.6, true, material)),
+            distance: Box::<_>::new(move |point| {
+                let (radial, height) = axial(point, &base, &cylinder_axis);
+                let half_height = cylinder_height / 2.0;
+                let (dx, dy) = (radial - 0.6, (height - half_height).abs() - half_height);
+                dx.max(dy).min(0.0) + (dx.max(0.0).powi(2) + dy.max(0.0).powi(2)).sqrt()
+            }),
+            inside: point(0.0, 0.0, 0.0),
+        },
+        Case {
+            name: "cone",
+            shape: Box::<_>::new(Cone::new(base, apex, 0.8, true, material)),
+            distance: Box::<_>::new(move |point| {
+                let (radial, height) = axial(point, &base, &cone_axis);
+                cone_distance(radial, height - cone_height, 0.8, cone_height)
+            }),
+            inside: point(0.0, -0.5, 0.2),
+        },
+        Case {
+            name: "torus",
+            shape: Box::<_>::new(Torus::new(center, torus_axis, 1.0, 0.3, material)),
+            distance: Box::<_>::new(move |point| {
+                let (radial, height) = axial(point, &center, &torus_axis);
+                ((radial - 1.0).powi(2) + height.powi(2)).sqrt() - 0.3
+            }),
+            inside: center + 1.1 * across,
+        },
+    ]
+}
+
+fn check_hit(case: &Case, ray: &Ray, hit_record: &HitRecord) {
+    let name = case.name;
+    let distance = (case.distance)(&hit_record.point);
+    assert!(distance.abs() < 2e-3, "{}: hit {} off the surface", name, distance);
+    assert!((hit_record.normal.length() - 1.0).abs() < 1e-3, "{}: normal is not unit", name);
+    assert!(hit_record.normal.dot(&ray.direction) <= 0.0, "{}: normal faces the ray", name);
+    let unit = Interval {min: -1e-4, max: 1.0 + 1e-4};
+    let in_unit = unit.contains(hit_record.u) && unit.contains(hit_record.v);
+    assert!(in_unit, "{}: UV outside [0,1]", name);
+    let bbox = case.shape.bounding_box();
+    let point = hit_record.point;
+    let inside = |bounds: Interval, x: f32| bounds.expand(2e-3).contains(x);
+    let in_box = inside(bbox.x, point.x) && inside(bbox.y, point.y) && inside(bbox.z, point.z);
+    assert!(in_box, "{}: hit outside the bounding box", name);
+
+    // Away from edges the distance's gradient is the surface normal
+    let step = 1e-3;
+    let mut gradient = Vec3::default();
+    for axis in 0..3 {
+        let mut offset = Vec3::default();
+        offset[axis] = step;
+        let difference = (case.distance)(&(point + offset)) - (case.distance)(&(point - offset));
+        gradient[axis] = difference / (2.0 * step);
+    }
+    if (gradient.length() - 1.0).abs() < 1e-2 {
+        let alignment = gradient.unit_vector().dot(&hit_record.normal).abs();
+        assert!(alignment > 0.99, "{}: normal is off by {}", name, alignment);
+    }
+}
+
+#[test]
+fn hits_lie_on_the_surface_with_matching_normals() {
+    let mut sampler = Sampler::new(3, 0);
+    for case in cases() {
+        let mut hits = 0;
+        for _ in 0..2000 {
+            let direction = uniform_sphere_direction(sampler.next_f32(), sampler.next_f32());
+            let origin = 6.0 * direction;
+            let target = Vec3::random(&mut sampler, -1.3, 1.3);
+            let ray = Ray {origin, direction: 0.5 * (target - origin), time: 0.0};
+
+            // Stepping along the ray, nothing before the hit may be inside the shape
+            let found = common::hit(case.shape.as_ref(), &ray);
+            let end = found.map_or(4.0, |hit_record| hit_record.t);
+            for step in 0..400 {
+                let distance = (case.distance)(&ray.at(end * step as f32 / 400.0));
+                assert!(distance > -2e-3, "{}: missed a surface", case.name);
+            }
+            if let Some(hit_record) = found {
+                check_hit(&case, &ray, &hit_record);
+                hits += 1;
+            }
+        }
+        assert!(hits > 200, "{}: only {} hits", case.name, hits);
+    }
+}
+
+#[test]
+fn rays_from_inside_leave_through_the_surface() {
+    let mut sampler = Sampler::new(5, 0);
+    for case in cases() {
+        for _ in 0..500 {
+            let direction = uniform_sphere_direction(sampler.next_f32(), sampler.next_f32());
+            let ray = Ray {origin: case.inside, direction, time: 0.0};
+            let hit_record = common::hit(case.shape.as_ref(), &ray)
+                .unwrap_or_else(|| panic!("{}: ray escaped", case.name));
+            assert!(!hit_record.front_face, "{}: left through a front face", case.name);
+            check_hit(&case, &ray, &hit_record);
+        }
+    }
+}
+
+#[test]
+fn torus_hole_and_open_cylinder_let_rays_through() {
+    let material = MaterialId::default();
+    let up = Vec3 {x: 0.0, y: 1.0, z: 0.0};
+    let down_the_axis = Ray {origin: point(0.0, 5.0, 0.0), direction: -up, time: 0.0};
+
+    let torus = Torus::new(point(0.0, 0.0, 0.0), up, 1.0, 0.25, material);
+    assert!(common::hit(&torus, &down_the_axis).is_none());
+    let across = Ray {
+        origin: point(-5.0, 0.0, 0.0), 
+        direction: Vec3 {x: 1.0, y: 0.0, z: 0.0}, 
+        time: 0.0,
+    };
+    assert!((common::hit(&torus, &across).unwrap().t - 3.75).abs() < 1e-4);
+
+    let tube = Cylinder::new(point(0.0, -1.0, 0.0), point(0.0, 1.0, 0.0), 0.5, false, material);
+    assert!(common::hit(&tube, &down_the_axis).is_none());
+    let slanted = Ray {
+        origin: point(0.0, 3.0, 0.0), 
+        direction: Vec3 {x: 0.2, y: -1.0, z: 0.0}, 
+        time: 0.0,
+    };
+    let inner_wall = common::hit(&tube, &slanted).unwrap();
+    assert!(!inner_wall.front_face && (inner_wall.point.x - 0.5).abs() < 1e-4);
+}
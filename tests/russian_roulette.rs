@@ -0,0 +1,86 @@
+use std::sync::Arc;
+
+use raytracer::{
+    camera::Camera, 
+    color::{Color, luminance}, 
+    hittable_list::HittableList, 
+    lambertian::Lambertian, 
+    light_list::LightList, 
+    material_arena::MaterialArena, 
+    metal::Metal, 
+    sphere::Sphere, 
+    vec::{Point3, Vec2},
+};
+
+mod common;
+
+const SEEDS: u64 = 16;
+
+/// Bright spheres under the sky, so that a good share of the light takes several bounces.
+fn scene() -> (HittableList, MaterialArena) {
+    let mut world = HittableList::default();
+    let mut materials = MaterialArena::default();
+    world.add(Arc::<_>::new(Sphere {
+        center: Point3 {x: 0.0, y: -100.5, z: -1.0},
+        radius: 100.0,
+        material: materials.add(Box::<_>::new(Lambertian::new(Color {x: 0.8, y: 0.8, z: 0.8}))),
+    }));
+    world.add(Arc::<_>::new(Sphere {
+        center: Point3 {x: -0.5, y: 0.0, z: -1.0},
+        radius: 0.5,
+        material: materials.add(Box::<_>::new(Lambertian::new(Color {x: 0.9, y: 0.6, z: 0.5}))),
+    }));
+    world.add(Arc::<_>::new(Sphere {
+        center: Point3 {x: 0.5, y: 0.0, z: -1.0},
+        radius: 0.5,
+        material: materials.add(Box::<_>::new(Metal::new(&Color {x: 0.8, y: 0.8, z: 0.9}, 0.3))),
+    }));
+    (world, materials)
+}
+
+/// Renders the scene once per seed and returns each render's mean brightness.
+fn mean_brightness(russian_roulette_depth: i32, seeds: std::ops::Range<u64>) -> Vec<f32> {
+    let (world, materials) = scene();
+    seeds
+        .map(|seed| {
+            let mut camera = Camera::default();
+            camera.image = Vec2 {width: 24, height: 16};
+            camera.samples_per_pixel = 64;
+            camera.max_depth = 12;
+            camera.russian_roulette_depth = russian_roulette_depth;
+            camera.look_from = Point3 {x: 0.0, y: 0.5, z: 1.0};
+            camera.look_at = Point3 {x: 0.0, y: 0.0, z: -1.0};
+            camera.thread_count = 1;
+            camera.seed = Some(seed);
+
+            let film = common::render(&camera, &world, &materials, &LightList::default());
+            let total = film.pixels().map(|pixel| luminance(&pixel)).sum::<f32>();
+            total / (film.width * film.height) as f32
+        })
+        .collect()
+}
+
+/// Returns the mean of `values` and its standard error.
+fn mean_and_error(values: &[f32]) -> (f32, f32) {
+    let count = values.len() as f32;
+    let mean = values.iter().sum::<f32>() / count;
+    let variance = values.iter().map(|value| (value - mean).powi(2)).sum::<f32>() / (count - 1.0);
+    (mean, (variance / count).sqrt())
+}
+
+#[test]
+fn russian_roulette_keeps_mean_brightness() {
+    // Separate seeds keep the two estimates independent, so their errors add in quadrature
+    let (fixed_depth, fixed_error) = mean_and_error(&mean_brightness(i32::MAX, 0..SEEDS));
+    let (roulette, roulette_error) = mean_and_error(&mean_brightness(1, SEEDS..2 * SEEDS));
+    let tolerance = 4.0 * fixed_error.hypot(roulette_error);
+    // A loose tolerance would let a biased estimator through
+    assert!(tolerance < 0.01 * fixed_depth, "tolerance {} is too loose to compare", tolerance);
+    assert!(
+        (fixed_depth - roulette).abs() < tolerance, 
+        "fixed depth mean {} and Russian roulette mean {} differ by more than {}", 
+        fixed_depth, 
+        roulette, 
+        tolerance
+    );
+}
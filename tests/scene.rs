@@ -0,0 +1,190 @@
+use std::path::Path;
+
+use raytracer::scene::{Scene, SceneError};
+
+mod common;
+
+/// Wraps `world` in an otherwise valid scene, with a material named "white" to refer to.
+fn scene(world: &str) -> String {
+    format!(
+        "{{\n\"materials\": {{\"white\": {{\"type\": \"lambertian\", \"albedo\": [1, 1, 1]}}}},\n\
+         \"world\": [\n{}\n]\n}}", 
+        world
+    )
+}
+
+fn sphere(fields: &str) -> String {
+    format!("{{\"type\": \"sphere\", {}}}", fields)
+}
+
+fn error(source: &str) -> (usize, String) {
+    match Scene::parse(source, Path::new("")) {
+        Err(SceneError::Invalid {line, message}) => (line, message),
+        Err(error) => panic!("unexpected error {}", error),
+        Ok(_) => panic!("scene parsed"),
+    }
+}
+
+#[test]
+fn valid_scene_parses() {
+    let source = scene(&sphere(r#""center": [0, 0, 0], "radius": 1, "material": "white""#));
+    assert!(Scene::parse(&source, Path::new("")).is_ok());
+}
+
+#[test]
+fn unknown_fields_are_reported_on_their_line() {
+    let fields = "\"center\": [0, 0, 0], \"radius\": 1,\n\"material\": \"white\",\n\"raduis\": 2";
+    let source = scene(&sphere(fields));
+    assert_eq!(error(&source), (6, "Unknown field 'raduis'".to_string()));
+    assert_eq!(error("{\"world\": [], \"camra\": {}}"), (1, "Unknown field 'camra'".to_string()));
+}
+
+#[test]
+fn wrong_types_are_reported_on_their_line() {
+    let fields = "\"center\": [0, 0, 0], \n\"radius\": \"1\", \"material\": \"white\"";
+    let source = scene(&sphere(fields));
+    assert_eq!(error(&source), (5, "Expected a number, found a string".to_string()));
+    let source = scene(&sphere(r#""center": [0, 0], "radius": 1, "material": "white""#));
+    assert_eq!(error(&source), (4, "Expected an array of 3 numbers".to_string()));
+    assert_eq!(error("{\"world\": {}}"), (1, "Expected an array, found an object".to_string()));
+    assert_eq!(error("[]"), (1, "Expected an object, found an array".to_string()));
+}
+
+#[test]
+fn missing_fields_and_unknown_names_are_reported() {
+    let source = scene(&sphere("\n\"center\": [0, 0, 0], \"radius\": 1"));
+    assert_eq!(error(&source), (4, "Missing field 'material'".to_string()));
+    let source = scene(&sphere(r#""center": [0, 0, 0], "radius": 1, "material": "red""#));
+    assert_eq!(error(&source), (4, "Unknown material 'red'".to_string()));
+    assert_eq!(error("{}"), (1, "Missing field 'world'".to_string()));
+}
+
+#[test]
+fn syntax_errors_keep_the_json_line() {
+    assert_eq!(error("{\n\"world\": [\n}").0, 3);
+    let nested = format!("{{\"world\": {}{}}}", "[".repeat(300), "]".repeat(300));
+    assert_eq!(error(&nested), (1, "Nested more than 128 levels deep".to_string()));
+}
+
+#[test]
+fn degenerate_shapes_are_reported_on_the_offending_field() {
+    let upright = r#""base": [0, 0, 0], "radius": 1"#;
+    let cases = [
+        (
+            "cylinder", 
+            upright, 
+            r#""top": [0, 0, 0]"#, 
+            "'top' is the same point as 'base'",
+        ),
+        (
+            "cylinder", 
+            r#""base": [0, 0, 0], "top": [0, 1, 0]"#, 
+            r#""radius": 0"#, 
+            "Expected a positive number",
+        ),
+        (
+            "cone", 
+            upright, 
+            r#""apex": [0, 0, 0]"#, 
+            "'apex' is the same point as 'base'",
+        ),
+        (
+            "cone", 
+            r#""base": [0, 0, 0], "apex": [0, 1, 0]"#, 
+            r#""radius": -1"#, 
+            "Expected a positive number",
+        ),
+        (
+            "torus", 
+            r#""center": [0, 0, 0], "major_radius": 1, "minor_radius": 0.5"#, 
+            r#""axis": [0, 0, 0]"#, 
+            "Torus axis is zero",
+        ),
+        (
+            "torus", 
+            r#""center": [0, 0, 0], "axis": [0, 1, 0], "minor_radius": 0.5"#, 
+            r#""major_radius": 0"#, 
+            "Expected a positive number",
+        ),
+        (
+            "torus", 
+            r#""center": [0, 0, 0], "axis": [0, 1, 0], "major_radius": 1"#, 
+            r#""minor_radius": 1"#, 
+            "Minor radius must be smaller than the major radius",
+        ),
+    ];
+    for (kind, fields, offending, expected) in cases {
+        let source = scene(&format!(
+            "{{\"type\": \"{}\", {}, \"material\": \"white\",\n{}}}", 
+            kind, 
+            fields, 
+            offending
+        ));
+        assert_eq!(error(&source), (5, expected.to_string()), "{}", offending);
+    }
+}
+
+#[test]
+fn mesh_warnings_are_kept_with_their_line() {
+    let source = b"mtllib nowhere.mtl\nv 0 0 0\nv 1 0 0\nv 0 1 0\nusemtl red\nf 1 2 3\n";
+    let path = common::temp_file("scene-missing-library.obj", source);
+    let mesh = format!(
+        "{{\"type\": \"mesh\",\n\"file\": \"{}\"}}", 
+        path.file_name().unwrap().to_str().unwrap()
+    );
+    let scene = Scene::parse(&scene(&mesh), path.parent().unwrap()).unwrap();
+    assert_eq!(scene.warnings.len(), 1);
+    assert!(scene.warnings[0].starts_with("line 5: "), "{}", scene.warnings[0]);
+    assert!(scene.warnings[0].contains("nowhere.mtl"), "{}", scene.warnings[0]);
+}
+
+#[test]
+fn camera_counts_must_fit_and_be_nonzero() {
+    let camera = |fields: &str| format!("{{\"world\": [],\n\"camera\": {{{}}}}}", fields);
+    let cases = [
+        (r#""image": {"width": 1e12}"#, "Out of range, the largest allowed is 2147483647"),
+        (r#""max_depth": 4294967296"#, "Out of range, the largest allowed is 2147483647"),
+        (r#""tile_size": 2147483648"#, "Out of range, the largest allowed is 2147483647"),
+        (r#""image": {"width": 0}"#, "'width' must be at least 1"),
+        (
+            r#""image": {"width": 8, "height": 0}"#, 
+            "'height' must be at least 1, leave it out to derive it from the width",
+        ),
+        (r#""samples_per_pixel": 0"#, "'samples_per_pixel' must be at least 1"),
+    ];
+    for (fields, expected) in cases {
+        assert_eq!(error(&camera(fields)), (2, expected.to_string()), "{}", fields);
+    }
+
+    let source = camera(r#""image": {"width": 2147483647}, "russian_roulette_depth": 0"#);
+    let scene = Scene::parse(&source, Path::new("")).unwrap();
+    assert_eq!(scene.camera.image.width, i32::MAX);
+    assert_eq!(scene.camera.russian_roulette_depth, 0);
+}
+
+#[test]
+fn emissive_shapes_that_cant_be_sampled_are_warned_about() {
+    let world = r#"{"type": "sphere", "center": [0, 0, 0], "radius": 1,
+"material": {"type": "diffuse_light", "emit": [4, 4, 4]}},
+{"type": "triangle", "a": [0, 0, 0], "b": [1, 0, 0], "c": [0, 1, 0],
+"material": {"type": "diffuse_light", "emit": [4, 4, 4]}},
+{"type": "plane", "point": [0, 0, 0], "normal": [0, 1, 0], "material": "white"}"#;
+    let scene = Scene::parse(&scene(world), Path::new("")).unwrap();
+    assert_eq!(scene.lights.lights.len(), 1);
+    assert_eq!(scene.warnings.len(), 1);
+    assert!(scene.warnings[0].starts_with("line 7: Emissive 'triangle'"), "{}", scene.warnings[0]);
+}
+
+#[test]
+fn shutters_must_stay_within_the_motion_span() {
+    let camera = |shutter: &str| {
+        format!("{{\"world\": [],\n\"camera\": {{\"shutter\": {}}}}}", shutter)
+    };
+    let message = "Shutter must open and then close between times 0 and 1".to_string();
+    for shutter in ["[-0.5, 0.5]", "[0, 2]", "[0.75, 0.25]"] {
+        assert_eq!(error(&camera(shutter)), (2, message.clone()), "{}", shutter);
+    }
+
+    let scene = Scene::parse(&camera("[0.25, 0.25]"), Path::new("")).unwrap();
+    assert_eq!((scene.camera.shutter.min, scene.camera.shutter.max), (0.25, 0.25));
+}
@@ -0,0 +1,45 @@
+use std::{path::Path, sync::Arc};
+
+use raytracer::scene::Scene;
+
+mod common;
+
+const SCENE: &str = r#"{
+    "camera": {
+        "image": {"width": 16, "height": 12},
+        "samples_per_pixel": 4,
+        "look_from": [0, 1, 4],
+        "look_at": [0, 0.5, 0],
+        "thread_count": 2,
+        "seed": 5
+    },
+    "materials": {
+        "ground": {"type": "lambertian", "albedo": [0.5, 0.5, 0.5]},
+        "lamp": {"type": "diffuse_light", "emit": [6, 6, 5]}
+    },
+    "world": [
+        {"type": "sphere", "center": [0, -100, 0], "radius": 100, "material": "ground"},
+        {"type": "sphere", "center": [0, 0.5, 0], "radius": 0.5, "material": "ground"},
+        {"type": "sphere", "center": [1, 2, 1], "radius": 0.3, "material": "lamp"}
+    ]
+}"#;
+
+fn render(scene: &Scene) -> Vec<[u32; 3]> {
+    common::bits(&common::render(&scene.camera, &scene.world, &scene.materials, &scene.lights))
+}
+
+#[test]
+fn one_scene_is_shared_between_render_threads() {
+    let scene = Arc::<_>::new(Scene::parse(SCENE, Path::new("")).unwrap());
+    let reference = render(&scene);
+
+    let renders: Vec<_> = (0..3)
+        .map(|_| {
+            let scene = scene.clone();
+            std::thread::spawn(move || render(&scene))
+        })
+        .collect();
+    for render in renders {
+        assert_eq!(render.join().unwrap(), reference);
+    }
+}
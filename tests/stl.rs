@@ -0,0 +1,88 @@
+use raytracer::{
+    stl::{StlError, read_stl}, 
+    triangle_mesh::MeshData,
+};
+
+mod common;
+
+fn read(name: &str, bytes: &[u8]) -> Result<MeshData, StlError> {
+    read_stl(common::temp_file(name, bytes))
+}
+
+fn facet(vertices: &str) -> String {
+    format!("facet normal 0 0 1\nouter loop\n{}endloop\nendfacet\n", vertices)
+}
+
+const TRIANGLE: &str = "vertex 0 0 0\nvertex 1 0 0\nvertex 0 1 0\n";
+
+/// A binary file whose 80 byte header starts with "solid", as many exporters write them.
+fn binary(triangle_count: u32, triangles: usize) -> Vec<u8> {
+    let mut bytes = b"solid binary".to_vec();
+    bytes.resize(80, b' ');
+    bytes.extend(triangle_count.to_le_bytes());
+    for triangle in 0..triangles {
+        bytes.extend([0; 12]);
+        for value in [0.0, 0.0, triangle as f32, 1.0, 0.0, 0.0, 0.0, 1.0, 0.0f32] {
+            bytes.extend(value.to_le_bytes());
+        }
+        bytes.extend([0; 2]);
+    }
+    bytes
+}
+
+#[test]
+fn ascii_and_binary_files_read_the_same() {
+    let ascii = format!("solid test\n{}{}endsolid test\n", facet(TRIANGLE), facet(TRIANGLE));
+    let data = read("valid.stl", ascii.as_bytes()).unwrap();
+    assert_eq!(data.positions.len(), 6);
+    assert_eq!(data.faces[1].positions, [3, 4, 5]);
+
+    let data = read("valid-binary.stl", &binary(2, 2)).unwrap();
+    assert_eq!(data.positions.len(), 6);
+    assert_eq!(data.positions[3].z, 1.0);
+    assert_eq!(data.faces[1].positions, [3, 4, 5]);
+}
+
+#[test]
+fn truncated_binary_is_an_error() {
+    match read("truncated.stl", &binary(3, 2)) {
+        // Falls back to ASCII since the declared size doesn't match, which then fails
+        Err(StlError::Parse {line: 1, ..}) => {},
+        Err(error) => panic!("unexpected error {}", error),
+        Ok(_) => panic!("parsed"),
+    }
+
+    let mut bytes = binary(3, 2);
+    bytes[..5].copy_from_slice(b"model");
+    let error = read("truncated-header.stl", &bytes).err();
+    let expected_bytes = 84 + 3 * 50;
+    let actual_bytes = 84 + 2 * 50;
+    assert!(matches!(
+        error, 
+        Some(StlError::Truncated {expected_bytes: expected, actual_bytes: actual})
+            if expected == expected_bytes && actual == actual_bytes
+    ));
+    assert!(matches!(read("short.stl", b"model").err(), Some(StlError::Truncated {..})));
+}
+
+#[test]
+fn malformed_ascii_reports_its_line() {
+    let cases = [
+        (facet("vertex 0 0 0\nvertex 1 0 0\n"), 7, "Facet does not have exactly 3 vertices"),
+        (facet("vertex 0 0 0\nvertex 1 zero 0\nvertex 0 1 0\n"), 6, "Vertex needs three numbers"),
+        (facet("vertex 0 0\nvertex 1 0 0\nvertex 0 1 0\n"), 5, "Vertex needs three numbers"),
+        (facet(&format!("{}color 1 0 0\n", TRIANGLE)), 8, "Unexpected 'color'"),
+        (format!("facet normal 0 0 1\nouter loop\n{}", TRIANGLE), 8, "Unterminated facet"),
+    ];
+    for (index, (body, expected_line, expected_message)) in cases.into_iter().enumerate() {
+        let source = format!("solid test\n\n{}endsolid test\n", body);
+        match read(&format!("malformed-{}.stl", index), source.as_bytes()) {
+            Err(StlError::Parse {line, message}) => {
+                assert_eq!(line, expected_line, "{}", body);
+                assert_eq!(message, expected_message, "{}", body);
+            },
+            Err(error) => panic!("{}: unexpected error {}", body, error),
+            Ok(_) => panic!("{}: parsed", body),
+        }
+    }
+}
@@ -0,0 +1,71 @@
+use std::sync::Arc;
+
+use raytracer::{
+    hittable::Hittable, 
+    material_arena::MaterialId, 
+    ray::Ray, 
+    sphere::Sphere, 
+    transform::Transform, 
+    transformed::Transformed, 
+    vec::{Point3, Vec3},
+};
+
+mod common;
+
+fn unit_sphere() -> Sphere {
+    Sphere {center: Point3::default(), radius: 1.0, material: MaterialId::default()}
+}
+
+fn assert_close(a: &Vec3, b: &Vec3) {
+    let message = format!("({}, {}, {}) != ({}, {}, {})", a.x, a.y, a.z, b.x, b.y, b.z);
+    assert!((*a - *b).length() < 1e-4, "{}", message);
+}
+
+#[test]
+fn transformed_sphere_hits_like_the_sphere_it_becomes() {
+    let unit = Arc::<_>::new(unit_sphere());
+    let offset = Vec3 {x: 1.0, y: -2.0, z: 0.5};
+    let transform = Transform::scale(&Vec3 {x: 2.0, y: 2.0, z: 2.0})
+        .then(&Transform::rotate(&Vec3 {x: 1.0, y: 1.0, z: 0.0}, 70.0))
+        .then(&Transform::translate(&offset));
+    let instance = Transformed::new(unit, transform);
+    let expected = Sphere {center: offset, radius: 2.0, material: MaterialId::default()};
+
+    let origins = [
+        Point3 {x: 0.0, y: 0.0, z: 10.0},
+        Point3 {x: 5.0, y: 3.0, z: -4.0},
+        // From inside, hitting the back face
+        offset + Vec3 {x: 0.2, y: -0.1, z: 0.3},
+    ];
+    for origin in origins {
+        for target in [offset, offset + Vec3 {x: 0.5, y: 1.0, z: -0.3}] {
+            let ray = Ray {origin, direction: target - origin, time: 0.0};
+            let actual = common::hit(&instance, &ray).unwrap();
+            let expected = common::hit(&expected, &ray).unwrap();
+            assert!((actual.t - expected.t).abs() < 1e-4);
+            assert_close(&actual.point, &expected.point);
+            assert_close(&actual.normal, &expected.normal);
+            assert_eq!(actual.front_face, expected.front_face);
+        }
+    }
+
+    let bbox = instance.bounding_box();
+    assert!(bbox.x.min <= -1.0 && bbox.x.max >= 3.0 && bbox.y.min <= -4.0 && bbox.y.max >= 0.0);
+}
+
+#[test]
+fn non_uniform_scale_keeps_normals_perpendicular() {
+    let unit = Arc::<_>::new(unit_sphere());
+    let instance = Transformed::new(unit, Transform::scale(&Vec3 {x: 4.0, y: 1.0, z: 1.0}));
+
+    // On the ellipsoid x²/16 + y² + z² = 1 the gradient gives the normal
+    let ray = Ray {
+        origin: Point3 {x: 2.0, y: 5.0, z: 0.0}, 
+        direction: Vec3 {x: 0.0, y: -1.0, z: 0.0},
+        time: 0.0,
+    };
+    let hit_record = common::hit(&instance, &ray).unwrap();
+    let point = hit_record.point;
+    let gradient = Vec3 {x: point.x / 16.0, y: point.y, z: point.z}.unit_vector();
+    assert_close(&hit_record.normal, &gradient);
+}